@@ -8,7 +8,7 @@ use ark_dh_commitments::{
 use ark_ec::{PairingEngine};
 use ark_ff::{UniformRand};
 use ark_inner_products::{
-    ExtensionFieldElement, InnerProduct, PairingInnerProduct,
+    ExtensionFieldElement, InnerProduct, MultiScalarMul, PairingInnerProduct,
 };
 use ark_dory::dory::{
     DORY,
@@ -35,6 +35,7 @@ where
     LMC::Message: MulAssign<P::Fr>,
     RMC::Message: MulAssign<P::Fr>,
     IPC::Message: MulAssign<P::Fr>,
+    IPC::Message: MultiScalarMul<P::Fr>,
     IPC::Key: MulAssign<P::Fr>,
     LMC::Output: MulAssign<P::Fr>,
     RMC::Output: MulAssign<P::Fr>,
@@ -55,7 +56,7 @@ where
         r.push(<IP::RightMessage>::rand(rng));
     }
 
-    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(rng, len).unwrap();
+    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(len).unwrap();
     let d1 = IP::inner_product(&l, &gamma2).unwrap();
     let d2 = IP::inner_product(&gamma1, &r).unwrap();
     let c = IP::inner_product(&l, &r).unwrap();
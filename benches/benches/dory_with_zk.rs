@@ -1,6 +1,6 @@
 use ark_bls12_381::Bls12_381;
 use ark_dh_commitments::{
-    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    afgho16::{HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2},
     identity::IdentityCommitment,
     // pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
@@ -30,8 +30,8 @@ where
         RightMessage = RMC::Message,
         Output = IPC::Message,
     >,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective, Message = P::G1Projective>,
-    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::G2Projective>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective, Message = P::G1Projective, Output = <IPC as DoublyHomomorphicCommitment>::Message>,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::G2Projective, Output = <IPC as DoublyHomomorphicCommitment>::Message>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     LMC::Message: MulAssign<P::Fr>,
     RMC::Message: MulAssign<P::Fr>,
@@ -56,20 +56,16 @@ where
         r.push(<IP::RightMessage>::rand(rng));
     }
 
-    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(rng, len).unwrap();
+    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(len).unwrap();
     
     let r_c = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
     let r_d1 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
     let r_d2 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
-    let mut h1 = Vec::new();
-    let mut h2 = Vec::new();
-    h1.push(<IP::LeftMessage>::rand(rng));
-    h2.push(<IP::RightMessage>::rand(rng));
 
     let (c, d1, d2)
-         = DORY::<IP,LMC,RMC,IPC, D>::init_commit(&l, &r, &gamma1, &gamma2, &r_c, &r_d1, &r_d2, &h1, &h2).unwrap();
+         = DORY::<IP,LMC,RMC,IPC, D>::init_commit(&l, &r, &gamma1, &gamma2, &r_c, &r_d1, &r_d2).unwrap();
 
-    let mut dory_srs = DORY::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
+    let mut dory_srs = DORY::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone()))).unwrap();
 
     let mut start = Instant::now();
     let mut proof =
@@ -97,8 +93,8 @@ fn main() {
     // let len: usize =arg.parse().unwrap();
 
     const LEN: usize = 16;
-    type GC1 = AFGHOCommitmentG1<Bls12_381>;
-    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type GC1 = HidingAFGHOCommitmentG1<Bls12_381>;
+    type GC2 = HidingAFGHOCommitmentG2<Bls12_381>;
     let mut rng = StdRng::seed_from_u64(0u64);
 
     println!("Benchmarking DORY_with_zk with vector length: {}", LEN);
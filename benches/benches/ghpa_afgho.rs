@@ -80,12 +80,11 @@ where
     println!("X == X' : {}", bool_x);   
 
 
-    // let mut hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
-    // let mut hpa_srs_ = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
+    let hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&gamma1, &gamma2), &h1, &h2).unwrap();
 
 
     let mut start = Instant::now();
-    let mut proof =
+    let proof =
         HPA::<IP, LMC, RMC, IPC, D>::prove((&(v1.clone()), &(v2.clone()), &(w_vec.clone())),
         //  &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -97,7 +96,7 @@ where
         ).unwrap();
 
     // let mut start = Instant::now();
-    let mut proof_ =
+    let proof_ =
         HPA::<IP, LMC, RMC, IPC, D>::prove((&(u1.clone()), &(u2.clone()), &(w_vec_.clone())),// &(k_vec_.clone())),
             // &hpa_srs_, 
             (&(gamma1.clone()), &(gamma2.clone())), 
@@ -112,11 +111,11 @@ where
 
 
     start = Instant::now();
-    let result = HPA::<IP, LMC, RMC, IPC, D>::verify( (gamma1.clone(), gamma2.clone()),
-         (&c, &x, &d1, &d2, &d3), &mut proof, &gm, &h1, &h2)
+    let result = HPA::<IP, LMC, RMC, IPC, D>::verify(&hpa_srs,
+         (&c, &x, &d1, &d2, &d3), &proof, &gm)
         .unwrap();
-    let result2 = HPA::<IP, LMC, RMC, IPC, D>::verify( (gamma1.clone(), gamma2.clone()),
-    (&c_, &x_, &d1_, &d2_, &d3_), &mut proof_, &gm, &h1, &h2)
+    let result2 = HPA::<IP, LMC, RMC, IPC, D>::verify(&hpa_srs,
+    (&c_, &x_, &d1_, &d2_, &d3_), &proof_, &gm)
    .unwrap();
     bench = start.elapsed().as_millis();
     println!("\t verification time: {} ms", bench);
@@ -98,12 +98,11 @@ where
 
         let mut bench = start.elapsed().as_millis();
         println!("\t batching time: {} ms", bench);
-    // let mut hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
-    // let mut hpa_srs_ = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
+    let hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&gamma1, &gamma2), &h1, &h2).unwrap();
 
 
     start = Instant::now();
-    let mut bat_proof =
+    let bat_proof =
         HPA::<IP, LMC, RMC, IPC, D>::prove((&(bat_v1.clone()), &(bat_v2.clone()), &(bat_w_vec.clone())),
         //  &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -142,8 +141,8 @@ where
             &delta
         ).unwrap();
 
-    let result = HPA::<IP, LMC, RMC, IPC, D>::verify( (gamma1.clone(), gamma2.clone()),
-         (&bat_c, &bat_x, &bat_d1, &bat_d2, &bat_d3), &mut bat_proof, &gm, &h1, &h2)
+    let result = HPA::<IP, LMC, RMC, IPC, D>::verify(&hpa_srs,
+         (&bat_c, &bat_x, &bat_d1, &bat_d2, &bat_d3), &bat_proof, &gm)
         .unwrap();
 //     let result2 = HPA::<IP, LMC, RMC, IPC, D>::verify( (gamma1.clone(), gamma2.clone()),
 //     (&c_, &x_, &d1_, &d2_, &d3_), &mut proof_, &gm, &h1, &h2)
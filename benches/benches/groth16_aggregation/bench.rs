@@ -357,11 +357,21 @@ fn main() {
                 .unwrap();
             csv_writer.flush().unwrap();
             let v_srs = srs.get_verifier_key();
+            let public_inputs = hash_outputs
+                .iter()
+                .map(|h| h.to_field_elements())
+                .collect::<Option<Vec<Vec<<Bls12_377 as PairingEngine>::Fr>>>>()
+                .unwrap();
 
             for i in 1..=num_trials {
                 start = Instant::now();
-                let aggregate_proof =
-                    aggregate_proofs::<Bls12_377, Blake2b>(&srs, &proofs).unwrap();
+                let aggregate_proof = aggregate_proofs::<Bls12_377, Blake2b>(
+                    &srs,
+                    &hash_circuit_parameters.0.vk,
+                    &public_inputs,
+                    &proofs,
+                )
+                .unwrap();
                 time = start.elapsed().as_millis();
                 csv_writer
                     .write_record(&[
@@ -378,11 +388,7 @@ fn main() {
                 let result = verify_aggregate_proof(
                     &v_srs,
                     &hash_circuit_parameters.0.vk,
-                    &hash_outputs
-                        .iter()
-                        .map(|h| h.to_field_elements())
-                        .collect::<Option<Vec<Vec<<Bls12_377 as PairingEngine>::Fr>>>>()
-                        .unwrap(),
+                    &public_inputs,
                     &aggregate_proof,
                 )
                 .unwrap();
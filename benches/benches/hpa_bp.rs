@@ -5,7 +5,8 @@ use ark_dh_commitments::{
     pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
 };
-use ark_ec::PairingEngine;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ed_on_bls12_381::EdwardsProjective as JubJub;
 use ark_ff::UniformRand;
 use ark_inner_products::{
     InnerProduct, ScalarInnerProduct//, PairingInnerProduct, //ExtensionFieldElement
@@ -20,16 +21,16 @@ use std::{time::Instant, env}; //, env,ops::MulAssign};
 
 
 // fn bench_hpa_bp<IP, LMC, RMC, IPC, P, D, R: Rng>(rng: &mut R, len: usize)
-fn bench_hpa_bp<IP, CM, P, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_hpa_bp<IP, CM, G, D, R: Rng>(rng: &mut R, len: usize)
 where
     D: Digest,
-    P: PairingEngine,
+    G: ProjectiveCurve,
     IP: InnerProduct<
         LeftMessage = CM::Message,
         RightMessage = CM::Message,
         Output = CM::Message,
     >,
-    CM: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G1Projective, Message = P::Fr>,
+    CM: DoublyHomomorphicCommitment<Scalar = G::ScalarField, Key = G, Message = G::ScalarField, Output = G>,
     // LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G1Projective, Message = P::Fr>,
     // RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::Fr>,
     // IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
@@ -85,7 +86,7 @@ where
 
 
     let mut start = Instant::now();
-    let mut proof =
+    let proof =
         HPA::<IP, CM, D>::prove((&(v1.clone()), &(v2.clone()), &(w_vec.clone())),
         //  &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -96,7 +97,7 @@ where
         ).unwrap();
 
     // let mut start = Instant::now();
-    let mut proof_ =
+    let proof_ =
         HPA::<IP, CM, D>::prove((&(u1.clone()), &(u2.clone()), &(w_vec_.clone())),// &(k_vec_.clone())),
             // &hpa_srs_, 
             (&(gamma1.clone()), &(gamma2.clone())), 
@@ -110,17 +111,29 @@ where
 
 
     start = Instant::now();
-    let result = HPA::<IP,CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-         (&c, &x, &d1, &d2, &d3), &mut proof, &gm)
+    let result_msm = HPA::<IP, CM, D>::verify_msm( (&gamma1, &gamma2),
+         (&c, &x, &d1, &d2, &d3), &proof, &gm)
         .unwrap();
-    let result2 = HPA::<IP, CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-    (&c_, &x_, &d1_, &d2_, &d3_), &mut proof_, &gm)
+    let result2_msm = HPA::<IP, CM, D>::verify_msm( (&gamma1, &gamma2),
+         (&c_, &x_, &d1_, &d2_, &d3_), &proof_, &gm)
+        .unwrap();
+    bench = start.elapsed().as_millis();
+    println!("\t verification time (single MSM): {} ms", bench);
+
+    start = Instant::now();
+    let result = HPA::<IP,CM, D>::verify( (&gamma1, &gamma2),
+         (&c, &x, &d1, &d2, &d3), &proof, &gm)
+        .unwrap();
+    let result2 = HPA::<IP, CM, D>::verify( (&gamma1, &gamma2),
+    (&c_, &x_, &d1_, &d2_, &d3_), &proof_, &gm)
    .unwrap();
     bench = start.elapsed().as_millis();
     println!("\t verification time: {} ms", bench);
 
     println!("v1, v2 - result : {}", result);
     println!("u1, u2 - result : {}", result2);
+    assert_eq!(result, result_msm);
+    assert_eq!(result2, result2_msm);
 }
 
 
@@ -144,7 +157,16 @@ fn main() {
         // GC2,
         CM,
         // IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>,
-        Bls12_381,
+        <Bls12_381 as PairingEngine>::G1Projective,
+        Blake2b,
+        StdRng,
+    >(&mut rng, LEN);
+
+    println!("2) Jubjub hadamard product...");
+    bench_hpa_bp::<
+        ScalarInnerProduct<<JubJub as ProjectiveCurve>::ScalarField>,
+        PedersenCommitment<JubJub>,
+        JubJub,
         Blake2b,
         StdRng,
     >(&mut rng, LEN);
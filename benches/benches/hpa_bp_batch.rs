@@ -5,7 +5,8 @@ use ark_dh_commitments::{
     pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
 };
-use ark_ec::PairingEngine;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ed_on_bls12_381::EdwardsProjective as JubJub;
 use ark_ff::UniformRand;
 use ark_inner_products::{
     InnerProduct, ScalarInnerProduct//, PairingInnerProduct, //ExtensionFieldElement
@@ -20,16 +21,16 @@ use std::{time::Instant, env}; //, env,ops::MulAssign};
 
 
 // fn bench_hpa_bp<IP, LMC, RMC, IPC, P, D, R: Rng>(rng: &mut R, len: usize)
-fn bench_hpa_bp<IP, CM, P, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_hpa_bp<IP, CM, G, D, R: Rng>(rng: &mut R, len: usize)
 where
     D: Digest,
-    P: PairingEngine,
+    G: ProjectiveCurve,
     IP: InnerProduct<
         LeftMessage = CM::Message,
         RightMessage = CM::Message,
         Output = CM::Message,
     >,
-    CM: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G1Projective, Message = P::Fr>,
+    CM: DoublyHomomorphicCommitment<Scalar = G::ScalarField, Key = G, Message = G::ScalarField>,
     // LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G1Projective, Message = P::Fr>,
     // RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::Fr>,
     // IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
@@ -100,7 +101,7 @@ where
 
 
     start = Instant::now();
-    let mut bat_proof =
+    let bat_proof =
         HPA::<IP, CM, D>::prove((&(bat_v1.clone()), &(bat_v2.clone()), &(bat_w_vec.clone())),
         //  &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -137,11 +138,11 @@ where
             &delta
         ).unwrap();
 
-    let result = HPA::<IP,CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-         (&bat_c, &bat_x, &bat_d1, &bat_d2, &bat_d3), &mut bat_proof, &gm)
+    let result = HPA::<IP,CM, D>::verify( (&gamma1, &gamma2),
+         (&bat_c, &bat_x, &bat_d1, &bat_d2, &bat_d3), &bat_proof, &gm)
         .unwrap();
-//     let result2 = HPA::<IP, CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-//     (&c_, &x_, &d1_, &d2_, &d3_), &mut proof_, &gm)
+//     let result2 = HPA::<IP, CM, D>::verify( (&gamma1, &gamma2),
+//     (&c_, &x_, &d1_, &d2_, &d3_), &proof_, &gm)
 //    .unwrap();
     bench = start.elapsed().as_millis();
     println!("\t verification time: {} ms", bench);
@@ -171,7 +172,16 @@ fn main() {
         // GC2,
         CM,
         // IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>,
-        Bls12_381,
+        <Bls12_381 as PairingEngine>::G1Projective,
+        Blake2b,
+        StdRng,
+    >(&mut rng, LEN);
+
+    println!("2) Jubjub hadamard product...");
+    bench_hpa_bp::<
+        ScalarInnerProduct<<JubJub as ProjectiveCurve>::ScalarField>,
+        PedersenCommitment<JubJub>,
+        JubJub,
         Blake2b,
         StdRng,
     >(&mut rng, LEN);
@@ -0,0 +1,91 @@
+use ark_bls12_381::Bls12_381;
+use ark_dh_commitments::{pedersen::PedersenCommitment, DoublyHomomorphicCommitment};
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ed_on_bls12_381::EdwardsProjective as JubJub;
+use ark_ff::UniformRand;
+use ark_hpa_bp::hpa_bp_with_zk::HPA;
+use ark_inner_products::{InnerProduct, ScalarInnerProduct};
+
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use blake2::Blake2b;
+use digest::Digest;
+
+use std::{env, time::Instant};
+
+fn bench_hpa_bp_with_zk<IP, CM, G, D, R: Rng>(rng: &mut R, len: usize)
+where
+    D: Digest,
+    G: ProjectiveCurve,
+    IP: InnerProduct<LeftMessage = CM::Message, RightMessage = CM::Message, Output = CM::Message>,
+    CM: DoublyHomomorphicCommitment<Scalar = G::ScalarField, Key = G, Message = G::ScalarField>,
+{
+    let mut v1 = Vec::new();
+    let mut v2 = Vec::new();
+
+    for _ in 0..len {
+        v1.push(<CM as DoublyHomomorphicCommitment>::Scalar::rand(rng));
+        v2.push(<CM as DoublyHomomorphicCommitment>::Scalar::rand(rng));
+    }
+
+    let (gamma1, gamma2, g, h) = HPA::<IP, CM, D>::setup(rng, len).unwrap();
+
+    let (c, d1, d2, x, d3, gm, _gm_vec, r_c, r_x, r_d1, r_d2, r_d3, w_vec) =
+        HPA::<IP, CM, D>::init_commit(&v1, &v2, &gamma1, &gamma2, &g, &h, rng).unwrap();
+
+    let mut start = Instant::now();
+    let proof = HPA::<IP, CM, D>::prove(
+        (&v1, &v2, &w_vec),
+        (&gamma1, &gamma2),
+        &g,
+        &h,
+        (&r_c, &r_x, &r_d1, &r_d2, &r_d3),
+        &gm,
+        rng,
+    )
+    .unwrap();
+    let mut bench = start.elapsed().as_millis();
+    println!("\t proving time: {} ms", bench);
+
+    start = Instant::now();
+    let result = HPA::<IP, CM, D>::verify(
+        (&gamma1, &gamma2),
+        &g,
+        &h,
+        (&c, &x, &d1, &d2, &d3),
+        &proof,
+        &gm,
+    )
+    .unwrap();
+    bench = start.elapsed().as_millis();
+    println!("\t verification time: {} ms", bench);
+
+    println!("v1, v2 - result : {}", result);
+}
+
+fn main() {
+    let arg = env::args().nth(1).unwrap();
+    let len: usize = arg.parse().unwrap();
+
+    type CM = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
+    let mut rng = StdRng::seed_from_u64(0u64);
+
+    println!("Benchmarking HPA_BP_with_zk with vector length: {}", len);
+
+    println!("1) Scalar hadamard product...");
+    bench_hpa_bp_with_zk::<
+        ScalarInnerProduct<<Bls12_381 as PairingEngine>::Fr>,
+        CM,
+        <Bls12_381 as PairingEngine>::G1Projective,
+        Blake2b,
+        StdRng,
+    >(&mut rng, len);
+
+    println!("2) Jubjub hadamard product...");
+    bench_hpa_bp_with_zk::<
+        ScalarInnerProduct<<JubJub as ProjectiveCurve>::ScalarField>,
+        PedersenCommitment<JubJub>,
+        JubJub,
+        Blake2b,
+        StdRng,
+    >(&mut rng, len);
+}
@@ -8,7 +8,7 @@ use ark_dh_commitments::{
 use ark_ec::{PairingEngine};
 use ark_ff::{UniformRand};
 use ark_inner_products::{
-    ExtensionFieldElement, InnerProduct, PairingInnerProduct,
+    ExtensionFieldElement, InnerProduct, MultiScalarMul, PairingInnerProduct,
 };
 use ark_dory::dory::{
     DORY,
@@ -36,6 +36,7 @@ where
     LMC::Message: MulAssign<P::Fr>,
     RMC::Message: MulAssign<P::Fr>,
     IPC::Message: MulAssign<P::Fr>,
+    IPC::Message: MultiScalarMul<P::Fr>,
     IPC::Key: MulAssign<P::Fr>,
     LMC::Output: MulAssign<P::Fr>,
     RMC::Output: MulAssign<P::Fr>,
@@ -48,7 +49,7 @@ where
     // IPC::Message: AddAssign<RMC::Output>,
     // RMC::Output: AddAssign<LMC::Output>,
 {
-    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(rng, len).unwrap();
+    let (gamma2, gamma1) = DORY::<IP,LMC,RMC,IPC, D>::setup(len).unwrap();
 
     // set matrix_a_trans : n*n length vector...
     /////// let matrix_a = MVP::<IP,LMC, RMC, IPC, D>::set_sparse_matrix(rng, len).unwrap();
@@ -0,0 +1,97 @@
+use ark_bls12_381::G1Projective;
+use ark_ec::ProjectiveCurve;
+use ark_hpa_bp::range_proof::{RangeArgument, RangeProof};
+
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use blake2::Blake2b;
+use digest::Digest;
+
+use std::time::Instant;
+
+fn bench_range_proof<G, D, R: Rng>(rng: &mut R, n_bits: usize)
+where
+    G: ProjectiveCurve,
+    D: Digest,
+{
+    let pk = RangeArgument::<G, D>::setup(rng, n_bits).unwrap();
+    let value: u64 = rng.gen::<u64>() >> (64 - n_bits);
+    let (com, gamma) = RangeArgument::<G, D>::commit(&pk, value, rng).unwrap();
+
+    let mut start = Instant::now();
+    let proof = RangeArgument::<G, D>::prove(&pk, n_bits, &com, (value, &gamma), rng).unwrap();
+    let mut bench = start.elapsed().as_millis();
+    println!("\t proving time: {} ms", bench);
+
+    start = Instant::now();
+    let result = RangeArgument::<G, D>::verify(&pk, n_bits, &com, &proof).unwrap();
+    bench = start.elapsed().as_millis();
+    println!("\t verification time: {} ms", bench);
+
+    println!("result : {}", result);
+}
+
+fn bench_aggregated_range_proof<G, D, R: Rng>(
+    rng: &mut R,
+    n_bits: usize,
+    m: usize,
+    num_proofs: usize,
+) where
+    G: ProjectiveCurve,
+    D: Digest,
+{
+    let pk = RangeArgument::<G, D>::setup(rng, n_bits * m.next_power_of_two()).unwrap();
+
+    let mut statements = Vec::new();
+    let mut start = Instant::now();
+    for _ in 0..num_proofs {
+        let mut coms = Vec::new();
+        let mut witnesses = Vec::new();
+        for _ in 0..m {
+            let value: u64 = rng.gen::<u64>() >> (64 - n_bits);
+            let (com, gamma) = RangeArgument::<G, D>::commit(&pk, value, rng).unwrap();
+            coms.push(com);
+            witnesses.push((value, gamma));
+        }
+        let proof =
+            RangeArgument::<G, D>::prove_aggregated(&pk, n_bits, &coms, &witnesses, rng).unwrap();
+        statements.push((coms, proof));
+    }
+    let mut bench = start.elapsed().as_millis();
+    println!("\t proving time ({} proofs): {} ms", num_proofs, bench);
+
+    start = Instant::now();
+    let mut result = true;
+    for (coms, proof) in statements.iter() {
+        result &= RangeArgument::<G, D>::verify_aggregated(&pk, n_bits, coms, proof).unwrap();
+    }
+    bench = start.elapsed().as_millis();
+    println!("\t verification time (one by one): {} ms", bench);
+
+    let batch = statements
+        .iter()
+        .map(|(coms, proof)| (&coms[..], proof))
+        .collect::<Vec<(&[G], &RangeProof<G, D>)>>();
+    start = Instant::now();
+    result &= RangeArgument::<G, D>::batch_verify(&pk, n_bits, &batch, rng).unwrap();
+    bench = start.elapsed().as_millis();
+    println!("\t verification time (batch): {} ms", bench);
+
+    println!("result : {}", result);
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0u64);
+
+    for n_bits in [8, 16, 32, 64].iter() {
+        println!("Benchmarking range proof with bit size: {}", n_bits);
+        bench_range_proof::<G1Projective, Blake2b, StdRng>(&mut rng, *n_bits);
+    }
+
+    for m in [2, 4, 8].iter() {
+        println!(
+            "Benchmarking aggregated range proof with bit size: 64, values: {}",
+            m
+        );
+        bench_aggregated_range_proof::<G1Projective, Blake2b, StdRng>(&mut rng, 64, *m, 8);
+    }
+}
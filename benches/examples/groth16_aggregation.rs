@@ -89,7 +89,9 @@ fn main() {
     // Aggregate proofs using inner product proofs
     start = Instant::now();
     println!("Aggregating {} Groth16 proofs...", NUM_PROOFS_TO_AGGREGATE);
-    let aggregate_proof = aggregate_proofs::<Bls12_381, Blake2b>(&srs, &proofs).unwrap();
+    let aggregate_proof =
+        aggregate_proofs::<Bls12_381, Blake2b>(&srs, &parameters.0.vk, &statements, &proofs)
+            .unwrap();
     let prover_time = start.elapsed().as_millis();
 
     println!("Verifying aggregated proof...");
@@ -1,6 +1,6 @@
 use ark_bls12_381::Bls12_381;
 use ark_dh_commitments::{
-    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    afgho16::{HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2},
     identity::IdentityCommitment,
     // pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
@@ -8,7 +8,7 @@ use ark_dh_commitments::{
 use ark_ec::{PairingEngine};
 use ark_ff::{UniformRand};
 use ark_inner_products::{
-    ExtensionFieldElement, InnerProduct, PairingInnerProduct,
+    ExtensionFieldElement, InnerProduct, MultiScalarMul, PairingInnerProduct,
 };
 use ark_hpa::hpa::HPA;
 
@@ -29,12 +29,13 @@ where
         RightMessage = RMC::Message,
         Output = IPC::Message,
     >,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective, Message = P::G1Projective>,
-    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::G2Projective>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective, Message = P::G1Projective, Output = <IPC as DoublyHomomorphicCommitment>::Message>,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective, Message = P::G2Projective, Output = <IPC as DoublyHomomorphicCommitment>::Message>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     LMC::Message: MulAssign<P::Fr>,
     RMC::Message: MulAssign<P::Fr>,
     IPC::Message: MulAssign<P::Fr>,
+    IPC::Message: MultiScalarMul<P::Fr>,
     IPC::Key: MulAssign<P::Fr>,
     LMC::Output: MulAssign<P::Fr>,
     RMC::Output: MulAssign<P::Fr>,
@@ -59,24 +60,20 @@ where
     
     let (v1, v2, u1, u2) = HPA::<IP, LMC, RMC, IPC, D>::set_values(&l, &r, &generator_g1, &generator_g2).unwrap();
 
-    let (gamma2, gamma1) = HPA::<IP,LMC,RMC,IPC, D>::setup(rng, len).unwrap();
+    let (gamma2, gamma1) = HPA::<IP,LMC,RMC,IPC, D>::setup(len).unwrap();
     
     
-    let mut h1 = Vec::new();
-    let mut h2 = Vec::new();
-    h1.push(<IP::LeftMessage>::rand(rng));
-    h2.push(<IP::RightMessage>::rand(rng));
 
     let (c, d1, d2,
         x, y, d3, d4,
         gm, gm_vec, r_c, r_d1, r_d2, r_x, r_y, r_d3, r_d4,
         w_vec, k_vec)
-         = HPA::<IP,LMC,RMC,IPC, D>::init_commit(&v1, &v2, &gamma1, &gamma2, &h1, &h2, rng).unwrap();
+         = HPA::<IP,LMC,RMC,IPC, D>::init_commit(&v1, &v2, &gamma1, &gamma2, rng).unwrap();
 
     let (c_, d1_, d2_,
         x_, y_, d3_, d4_,
         w_vec_, k_vec_)
-         = HPA::<IP,LMC,RMC,IPC, D>::init_commit2(&u1, &u2, &gamma1, &gamma2, &h1, &h2, &gm_vec,
+         = HPA::<IP,LMC,RMC,IPC, D>::init_commit2(&u1, &u2, &gamma1, &gamma2, &gm_vec,
             &r_c, &r_d1, &r_d2, &r_x, &r_y, &r_d3, &r_d4, rng).unwrap();
 
     // X ?= X'
@@ -84,12 +81,11 @@ where
     println!("X == X' : {}", bool_x);   
 
 
-    let mut hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
-    let mut hpa_srs_ = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone())), &h1, &h2).unwrap();
+    let hpa_srs = HPA::<IP, LMC, RMC, IPC, D>::precompute((&(gamma1.clone()), &(gamma2.clone()))).unwrap();
 
 
     let mut start = Instant::now();
-    let mut proof =
+    let proof =
         HPA::<IP, LMC, RMC, IPC, D>::prove((&(v1.clone()), &(v2.clone()), &(w_vec.clone()), &(k_vec.clone())),
          &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -100,9 +96,9 @@ where
         ).unwrap();
 
     // let mut start = Instant::now();
-    let mut proof_ =
+    let proof_ =
         HPA::<IP, LMC, RMC, IPC, D>::prove((&(u1.clone()), &(u2.clone()), &(w_vec_.clone()), &(k_vec_.clone())),
-            &hpa_srs_, 
+            &hpa_srs, 
             (&(gamma1.clone()), &(gamma2.clone())), 
             //  (&(d1.clone()), &(d2.clone()), &(c.clone())),
             (&r_c, &r_x, &r_y, &r_d1, &r_d2, &r_d3, &r_d4),
@@ -114,11 +110,11 @@ where
 
 
     start = Instant::now();
-    let result = HPA::<IP, LMC, RMC, IPC, D>::verify(&mut hpa_srs, (&(gamma1.clone()), &(gamma2.clone())),
-         (&c, &x, &y, &d1, &d2, &d3, &d4), &mut proof, &gm, rng)
+    let result = HPA::<IP, LMC, RMC, IPC, D>::verify(&hpa_srs, (&(gamma1.clone()), &(gamma2.clone())),
+         (&c, &x, &y, &d1, &d2, &d3, &d4), &proof, &gm, rng)
         .unwrap();
-    let result2 = HPA::<IP, LMC, RMC, IPC, D>::verify(&mut hpa_srs_, (&(gamma1.clone()), &(gamma2.clone())),
-    (&c_, &x_, &y_, &d1_, &d2_, &d3_, &d4_), &mut proof_, &gm, rng)
+    let result2 = HPA::<IP, LMC, RMC, IPC, D>::verify(&hpa_srs, (&(gamma1.clone()), &(gamma2.clone())),
+    (&c_, &x_, &y_, &d1_, &d2_, &d3_, &d4_), &proof_, &gm, rng)
    .unwrap();
     bench = start.elapsed().as_millis();
     println!("\t verification time: {} ms", bench);
@@ -132,8 +128,8 @@ fn main() {
     // let len: usize =arg.parse().unwrap();
 
     const LEN: usize = 4096;
-    type GC1 = AFGHOCommitmentG1<Bls12_381>;
-    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type GC1 = HidingAFGHOCommitmentG1<Bls12_381>;
+    type GC2 = HidingAFGHOCommitmentG2<Bls12_381>;
     let mut rng = StdRng::seed_from_u64(0u64);
 
     println!("Benchmarking HPA_with_zk with vector length: {}", LEN);
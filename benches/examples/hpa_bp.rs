@@ -85,7 +85,7 @@ where
 
 
     let mut start = Instant::now();
-    let mut proof =
+    let proof =
         HPA::<IP, CM, D>::prove((&(v1.clone()), &(v2.clone()), &(w_vec.clone())),
         //  &hpa_srs, 
          (&(gamma1.clone()), &(gamma2.clone())), 
@@ -96,7 +96,7 @@ where
         ).unwrap();
 
     // let mut start = Instant::now();
-    let mut proof_ =
+    let proof_ =
         HPA::<IP, CM, D>::prove((&(u1.clone()), &(u2.clone()), &(w_vec_.clone())),// &(k_vec_.clone())),
             // &hpa_srs_, 
             (&(gamma1.clone()), &(gamma2.clone())), 
@@ -110,12 +110,12 @@ where
 
 
     start = Instant::now();
-    let result = HPA::<IP,CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-         (&c, &x, &d1, &d2, &d3), &mut proof, &gm)
+    let result = HPA::<IP,CM, D>::verify( (&gamma1, &gamma2),
+         (&c, &x, &d1, &d2, &d3), &proof, &gm)
         .unwrap();
     println!("result1 : {}", result);
-    let result2 = HPA::<IP, CM, D>::verify( (gamma1.clone(), gamma2.clone()),
-    (&c_, &x_, &d1_, &d2_, &d3_), &mut proof_, &gm)
+    let result2 = HPA::<IP, CM, D>::verify( (&gamma1, &gamma2),
+    (&c_, &x_, &d1_, &d2_, &d3_), &proof_, &gm)
    .unwrap();
     bench = start.elapsed().as_millis();
     println!("\t verification time: {} ms", bench);
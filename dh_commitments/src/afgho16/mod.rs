@@ -2,9 +2,13 @@ use ark_ec::PairingEngine;
 use ark_std::rand::Rng;
 use std::marker::PhantomData;
 
-use crate::{random_generators, DoublyHomomorphicCommitment, Error};
+use crate::{
+    hash_to_curve, random_generators, seeded_generators, DoublyHomomorphicCommitment, Error,
+};
 
-use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+use ark_inner_products::{
+    CyclotomicField, ExtensionFieldElement, InnerProduct, PairingInnerProduct,
+};
 
 #[derive(Clone)]
 pub struct AFGHOCommitment<P: PairingEngine> {
@@ -17,7 +21,10 @@ pub struct AFGHOCommitmentG1<P: PairingEngine>(AFGHOCommitment<P>);
 #[derive(Clone)]
 pub struct AFGHOCommitmentG2<P: PairingEngine>(AFGHOCommitment<P>);
 
-impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG1<P> {
+impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG1<P>
+where
+    P::Fqk: CyclotomicField,
+{
     type Scalar = P::Fr;
     type Message = P::G1Projective;
     type Key = P::G2Projective;
@@ -27,12 +34,19 @@ impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG1<P> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(seeded_generators(domain_separator, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
         Ok(PairingInnerProduct::<P>::inner_product(m, k)?)
     }
 }
 
-impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG2<P> {
+impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG2<P>
+where
+    P::Fqk: CyclotomicField,
+{
     type Scalar = P::Fr;
     type Message = P::G2Projective;
     type Key = P::G1Projective;
@@ -42,16 +56,99 @@ impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG2<P> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(seeded_generators(domain_separator, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
         Ok(PairingInnerProduct::<P>::inner_product(k, m)?)
     }
 }
 
+// Domain separators of the G1 and G2 generators paired into the blinding generator of hiding
+// AFGHO commitments
+const AFGHO_BLINDING_DOMAINS: (&[u8], &[u8]) = (
+    b"afgho16-blinding-generator-g1",
+    b"afgho16-blinding-generator-g2",
+);
+
+impl<P: PairingEngine> AFGHOCommitment<P> {
+    fn blinding_generator() -> ExtensionFieldElement<P> {
+        ExtensionFieldElement(P::pairing(
+            hash_to_curve::<P::G1Projective>(AFGHO_BLINDING_DOMAINS.0, 0),
+            hash_to_curve::<P::G2Projective>(AFGHO_BLINDING_DOMAINS.1, 0),
+        ))
+    }
+}
+
+// AFGHO commitments blinded by a fixed target group generator in `commit_with_randomness`.
+// `commit` is the unblinded AFGHO commitment: a hiding commitment must be made with
+// `commit_with_randomness`, and a protocol using it must carry the randomness, as HPA, Dory with
+// zero knowledge and zero-knowledge GIPA do with `blinding_generator()` as their blinding base.
+#[derive(Clone)]
+pub struct HidingAFGHOCommitmentG1<P: PairingEngine>(AFGHOCommitment<P>);
+
+#[derive(Clone)]
+pub struct HidingAFGHOCommitmentG2<P: PairingEngine>(AFGHOCommitment<P>);
+
+impl<P: PairingEngine> DoublyHomomorphicCommitment for HidingAFGHOCommitmentG1<P>
+where
+    P::Fqk: CyclotomicField,
+{
+    type Scalar = P::Fr;
+    type Message = P::G1Projective;
+    type Key = P::G2Projective;
+    type Output = ExtensionFieldElement<P>;
+
+    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+        AFGHOCommitmentG1::<P>::setup(rng, size)
+    }
+
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        AFGHOCommitmentG1::<P>::setup_from_seed(domain_separator, size)
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+        AFGHOCommitmentG1::<P>::commit(k, m)
+    }
+
+    fn blinding_generator() -> Option<Self::Output> {
+        Some(AFGHOCommitment::<P>::blinding_generator())
+    }
+}
+
+impl<P: PairingEngine> DoublyHomomorphicCommitment for HidingAFGHOCommitmentG2<P>
+where
+    P::Fqk: CyclotomicField,
+{
+    type Scalar = P::Fr;
+    type Message = P::G2Projective;
+    type Key = P::G1Projective;
+    type Output = ExtensionFieldElement<P>;
+
+    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+        AFGHOCommitmentG2::<P>::setup(rng, size)
+    }
+
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        AFGHOCommitmentG2::<P>::setup_from_seed(domain_separator, size)
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+        AFGHOCommitmentG2::<P>::commit(k, m)
+    }
+
+    fn blinding_generator() -> Option<Self::Output> {
+        Some(AFGHOCommitment::<P>::blinding_generator())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
-    use ark_ff::UniformRand;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{UniformRand, Zero};
     use ark_std::rand::{rngs::StdRng, SeedableRng};
 
     type C1 = AFGHOCommitmentG1<Bls12_381>;
@@ -91,4 +188,72 @@ mod tests {
         message.push(<Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng));
         assert!(C2::verify(&commit_keys, &message, &com).is_err());
     }
+
+    #[test]
+    fn hiding_afgho_test() {
+        type HC1 = HidingAFGHOCommitmentG1<Bls12_381>;
+        type HC2 = HidingAFGHOCommitmentG2<Bls12_381>;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys_1 = HC1::setup(&mut rng, TEST_SIZE).unwrap();
+        let commit_keys_2 = HC2::setup(&mut rng, TEST_SIZE).unwrap();
+        let message_1 = random_generators(&mut rng, TEST_SIZE);
+        let message_2 = random_generators(&mut rng, TEST_SIZE);
+        let r = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+        let other_r = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+
+        let com_1 = HC1::commit_with_randomness(&commit_keys_1, &message_1, &r).unwrap();
+        assert!(HC1::verify_with_randomness(&commit_keys_1, &message_1, &r, &com_1).unwrap());
+        assert!(
+            !HC1::verify_with_randomness(&commit_keys_1, &message_1, &other_r, &com_1).unwrap()
+        );
+        assert!(!HC1::verify(&commit_keys_1, &message_1, &com_1).unwrap());
+
+        let com_2 = HC2::commit_with_randomness(&commit_keys_2, &message_2, &r).unwrap();
+        assert!(HC2::verify_with_randomness(&commit_keys_2, &message_2, &r, &com_2).unwrap());
+        assert!(!HC2::verify(&commit_keys_2, &message_2, &com_2).unwrap());
+
+        // Blinding is homomorphic in the randomness
+        let mut scaled_com = com_1.clone();
+        scaled_com *= other_r;
+        let scaled_message = message_1
+            .iter()
+            .map(|m| {
+                let mut m = *m;
+                m *= other_r;
+                m
+            })
+            .collect::<Vec<_>>();
+        assert!(HC1::verify_with_randomness(
+            &commit_keys_1,
+            &scaled_message,
+            &(r * other_r),
+            &scaled_com
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn seeded_afgho_setup_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g1_keys = C2::setup_from_seed(b"afgho16-test", TEST_SIZE).unwrap();
+        let g2_keys = C1::setup_from_seed(b"afgho16-test", TEST_SIZE).unwrap();
+
+        // Reproducible from the seed alone and separated by domain
+        assert!(g1_keys == C2::setup_from_seed(b"afgho16-test", TEST_SIZE).unwrap());
+        assert!(g2_keys == C1::setup_from_seed(b"afgho16-test", TEST_SIZE).unwrap());
+        assert!(g1_keys != C2::setup_from_seed(b"afgho16-other", TEST_SIZE).unwrap());
+        for (i, (k1, k2)) in g1_keys.iter().zip(&g2_keys).enumerate() {
+            assert!(!k1.is_zero() && !k2.is_zero());
+            assert!(k1.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(k2.into_affine().is_in_correct_subgroup_assuming_on_curve());
+            assert!(!g1_keys[..i].contains(k1) && !g2_keys[..i].contains(k2));
+        }
+
+        let mut message = Vec::new();
+        for _ in 0..TEST_SIZE {
+            message.push(<Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng));
+        }
+        let com = C1::commit(&g2_keys, &message).unwrap();
+        assert!(C1::verify(&g2_keys, &message, &com).unwrap());
+    }
 }
@@ -100,6 +100,10 @@ where
         Ok(vec![HomomorphicPlaceholderValue {}; size])
     }
 
+    fn setup_from_seed(_domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(vec![HomomorphicPlaceholderValue {}; size])
+    }
+
     fn commit(_k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
         Ok(IdentityOutput(m.to_vec()))
     }
@@ -1,10 +1,13 @@
-use ark_ec::group::Group;
-use ark_ff::{bytes::ToBytes, fields::PrimeField};
+use ark_ec::{group::Group, AffineCurve, ProjectiveCurve};
+use ark_ff::{bytes::ToBytes, fields::PrimeField, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::Rng;
+use blake2::Blake2b;
+use digest::Digest;
 use std::{
     cmp::Eq,
     error::Error as ErrorTrait,
+    fmt::{Display, Formatter, Result as FmtResult},
     ops::{Add, MulAssign},
 };
 
@@ -14,6 +17,30 @@ pub mod pedersen;
 
 pub type Error = Box<dyn ErrorTrait>;
 
+#[derive(Debug)]
+pub enum CommitmentError {
+    TransparentSetupUnsupported,
+    BlindingUnsupported,
+}
+
+impl ErrorTrait for CommitmentError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for CommitmentError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            CommitmentError::TransparentSetupUnsupported => {
+                "commitment has no transparent setup".to_string()
+            }
+            CommitmentError::BlindingUnsupported => "commitment is not hiding".to_string(),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 //TODO: support CanonicalSerialize
 //TODO: Using MulAssign instead of Mul because Group does not support Mul
 
@@ -50,15 +77,86 @@ pub trait DoublyHomomorphicCommitment: Clone {
 
     fn setup<R: Rng>(r: &mut R, size: usize) -> Result<Vec<Self::Key>, Error>;
 
+    // Transparent setup: every party derives the same key from the public domain separator
+    fn setup_from_seed(_domain_separator: &[u8], _size: usize) -> Result<Vec<Self::Key>, Error> {
+        Err(Box::new(CommitmentError::TransparentSetupUnsupported))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error>;
 
     fn verify(k: &[Self::Key], m: &[Self::Message], com: &Self::Output) -> Result<bool, Error> {
         Ok(Self::commit(k, m)? == *com)
     }
+
+    // Generator of the output group used to blind commitments, none for non-hiding commitments
+    fn blinding_generator() -> Option<Self::Output> {
+        None
+    }
+
+    // Commitment blinded by `r` times the blinding generator. `commit` never blinds, even for
+    // hiding commitments
+    fn commit_with_randomness(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        r: &Self::Scalar,
+    ) -> Result<Self::Output, Error> {
+        let com = Self::commit(k, m)?;
+        Ok(match Self::blinding_generator() {
+            Some(mut blinding) => {
+                blinding *= *r;
+                com + blinding
+            }
+            None => com,
+        })
+    }
+
+    fn verify_with_randomness(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        r: &Self::Scalar,
+        com: &Self::Output,
+    ) -> Result<bool, Error> {
+        Ok(Self::commit_with_randomness(k, m, r)? == *com)
+    }
 }
 
 // Helpers for generator commitment keys used by Pedersen and AFGHO16
 
 pub fn random_generators<R: Rng, G: Group>(rng: &mut R, num: usize) -> Vec<G> {
     (0..num).map(|_| G::rand(rng)).collect()
-}
\ No newline at end of file
+}
+
+pub fn seeded_generators<G: ProjectiveCurve>(domain_separator: &[u8], num: usize) -> Vec<G> {
+    (0..num)
+        .map(|i| hash_to_curve(domain_separator, i as u64))
+        .collect()
+}
+
+// Try-and-increment hash to the prime order subgroup, so no discrete logarithm between the
+// generators (or relative to the curve generator) is known to anyone
+pub fn hash_to_curve<G: ProjectiveCurve>(domain_separator: &[u8], index: u64) -> G {
+    let num_bytes = G::Affine::zero().serialized_size();
+    let mut attempt = 0u64;
+    loop {
+        let mut bytes = Vec::with_capacity(num_bytes);
+        let mut block = 0u64;
+        while bytes.len() < num_bytes {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&(domain_separator.len() as u64).to_le_bytes());
+            hash_input.extend_from_slice(domain_separator);
+            hash_input.extend_from_slice(&index.to_le_bytes());
+            hash_input.extend_from_slice(&attempt.to_le_bytes());
+            hash_input.extend_from_slice(&block.to_le_bytes());
+            bytes.extend_from_slice(&Blake2b::digest(&hash_input)[..]);
+            block += 1;
+        }
+        bytes.truncate(num_bytes);
+        if let Some(point) = G::Affine::from_random_bytes(&bytes) {
+            let point = point.mul_by_cofactor_to_projective();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+        attempt += 1;
+    }
+}
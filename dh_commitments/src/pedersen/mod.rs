@@ -2,7 +2,9 @@ use ark_ec::ProjectiveCurve;
 use ark_std::rand::Rng;
 use std::marker::PhantomData;
 
-use crate::{random_generators, DoublyHomomorphicCommitment, Error};
+use crate::{
+    hash_to_curve, random_generators, seeded_generators, DoublyHomomorphicCommitment, Error,
+};
 
 use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct};
 
@@ -21,11 +23,48 @@ impl<G: ProjectiveCurve> DoublyHomomorphicCommitment for PedersenCommitment<G> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(seeded_generators(domain_separator, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
         Ok(MultiexponentiationInnerProduct::<G>::inner_product(k, m)?)
     }
 }
 
+// Domain separator of the blinding generator of hiding Pedersen commitments
+const PEDERSEN_BLINDING_DOMAIN: &[u8] = b"pedersen-blinding-generator";
+
+// Pedersen commitment blinded by a fixed generator in `commit_with_randomness`. `commit` is
+// unblinded, so hiding requires `commit_with_randomness` and a protocol carrying the randomness.
+#[derive(Clone)]
+pub struct HidingPedersenCommitment<G: ProjectiveCurve> {
+    _group: PhantomData<G>,
+}
+
+impl<G: ProjectiveCurve> DoublyHomomorphicCommitment for HidingPedersenCommitment<G> {
+    type Scalar = G::ScalarField;
+    type Message = G::ScalarField;
+    type Key = G;
+    type Output = G;
+
+    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+        PedersenCommitment::<G>::setup(rng, size)
+    }
+
+    fn setup_from_seed(domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        PedersenCommitment::<G>::setup_from_seed(domain_separator, size)
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+        PedersenCommitment::<G>::commit(k, m)
+    }
+
+    fn blinding_generator() -> Option<Self::Output> {
+        Some(hash_to_curve(PEDERSEN_BLINDING_DOMAIN, 0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +91,44 @@ mod tests {
         message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
         assert!(C::verify(&commit_keys, &message, &com).is_err());
     }
+
+    #[test]
+    fn hiding_pedersen_test() {
+        type HC = HidingPedersenCommitment<JubJub>;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = HC::setup(&mut rng, TEST_SIZE).unwrap();
+        let mut message = Vec::new();
+        let mut other_message = Vec::new();
+        for _ in 0..TEST_SIZE {
+            message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+            other_message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+        }
+        let r = <JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng);
+        let other_r = <JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng);
+        let com = HC::commit_with_randomness(&commit_keys, &message, &r).unwrap();
+        assert!(HC::verify_with_randomness(&commit_keys, &message, &r, &com).unwrap());
+        assert!(!HC::verify_with_randomness(&commit_keys, &message, &other_r, &com).unwrap());
+        assert!(!HC::verify(&commit_keys, &message, &com).unwrap());
+
+        // Homomorphic in message and randomness
+        let other_com = HC::commit_with_randomness(&commit_keys, &other_message, &other_r).unwrap();
+        let sum_message = message
+            .iter()
+            .zip(&other_message)
+            .map(|(m1, m2)| *m1 + m2)
+            .collect::<Vec<_>>();
+        assert!(HC::verify_with_randomness(
+            &commit_keys,
+            &sum_message,
+            &(r + other_r),
+            &(com + other_com)
+        )
+        .unwrap());
+
+        // Unblinded commitments match Pedersen
+        assert_eq!(
+            HC::commit(&commit_keys, &message).unwrap(),
+            C::commit(&commit_keys, &message).unwrap()
+        );
+    }
 }
@@ -9,11 +9,11 @@ extern  crate digest;
 use self::digest::Digest;
 use std::{convert::TryInto, marker::PhantomData, ops::MulAssign};
 
-use crate::{mul_helper, Error, InnerProductArgumentError};
+use crate::{mul_helper, multi_mul_helper, Error, InnerProductArgumentError};
 extern crate ark_dh_commitments;
 use self::ark_dh_commitments::DoublyHomomorphicCommitment;
 extern crate ark_inner_products;
-use self::ark_inner_products::InnerProduct;
+use self::ark_inner_products::{InnerProduct, InnerProductArgument, MultiScalarMul};
 use self::ark_std::cfg_iter;
 
 use std::fmt;
@@ -42,6 +42,9 @@ impl fmt::Display for List {
     }
 }
 
+// Domain separator of the transparent Dory commitment keys
+pub const DORY_DOMAIN_SEPARATOR: &[u8] = b"dory-commitment-key";
+
 pub struct DORY<IP, LMC, RMC, IPC, D> {
     _inner_product: PhantomData<IP>,
     _left_commitment: PhantomData<LMC>,
@@ -81,6 +84,7 @@ where
 
 
 // #[derive(Clone)]
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct DORYSRS<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -147,6 +151,7 @@ where
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     RMC::Message: MulAssign<LMC::Scalar>,
     IPC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MultiScalarMul<LMC::Scalar>,
     RMC::Key: MulAssign<LMC::Scalar>,
     LMC::Key: MulAssign<LMC::Scalar>,
     IPC::Key: MulAssign<LMC::Scalar>,
@@ -160,13 +165,18 @@ where
     // RMC::Output: AddAssign<LMC::Output>,
     // IPC::Output: AddAssign<LMC::Output>,
 {
-    pub fn setup<R: Rng>(
-        rng: &mut R,
+    // Keys are hashed from the public DORY_DOMAIN_SEPARATOR, so every party derives the same ones
+    pub fn setup(size: usize) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>), Error> {
+        Self::setup_from_seed(DORY_DOMAIN_SEPARATOR, size)
+    }
+
+    pub fn setup_from_seed(
+        domain_separator: &[u8],
         size: usize,
     ) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>), Error> {
         Ok((
-            LMC::setup(rng, size)?,
-            RMC::setup(rng, size)?,
+            LMC::setup_from_seed(&[domain_separator, b"/left"].concat(), size)?,
+            RMC::setup_from_seed(&[domain_separator, b"/right"].concat(), size)?,
         ))
     }
 
@@ -358,19 +368,20 @@ where
                 // Verifier's work in reduce
                 let last_commitment = proof.r_commitment_steps.pop().unwrap();
                 let last_transcript = transcript.pop().unwrap();
-                let temp2 = mul_helper(&d1_prime, &(last_transcript.3));
-                let temp = mul_helper(&d2_prime, &(last_transcript.2));
-                let temp = temp + temp2;//add_helper(&temp, &temp2);
                 let last_kai = srs.kai.pop().unwrap();
-                let temp = last_kai + temp;//add_helper(&last_kai, &temp);
-
-                c_prime = c_prime + temp + mul_helper(&(last_commitment.0.2), &(last_transcript.0)) + mul_helper(&(last_commitment.1.2), &(last_transcript.1)); 
-                let temp = mul_helper(&(last_commitment.0.0.clone()), &(last_transcript.0)) + last_commitment.1.0;
-                d1_prime = mul_helper(&(srs.delta1_l.pop().unwrap()), &(last_transcript.0 * last_transcript.2)) + mul_helper(&(srs.delta1_r.pop().unwrap()), &(last_transcript.2));
-                d1_prime = d1_prime + temp; //add_helper(&d1_prime, &temp);
-                let temp2 =  mul_helper(&(last_commitment.0.1), &(last_transcript.1)) + last_commitment.1.1;
-                d2_prime = mul_helper(&(srs.delta2_l.pop().unwrap()), &(last_transcript.1 * last_transcript.3)) + mul_helper(&(srs.delta2_r.pop().unwrap()), &(last_transcript.3));
-                d2_prime = d2_prime + temp2;
+                let temp = multi_mul_helper(
+                    &[d1_prime.clone(), d2_prime.clone(), last_commitment.0.2.clone(), last_commitment.1.2.clone()],
+                    &[last_transcript.3, last_transcript.2, last_transcript.0, last_transcript.1],
+                );
+                c_prime = c_prime + last_kai + temp;
+                d1_prime = multi_mul_helper(
+                    &[srs.delta1_l.pop().unwrap(), srs.delta1_r.pop().unwrap(), last_commitment.0.0.clone()],
+                    &[last_transcript.0 * last_transcript.2, last_transcript.2, last_transcript.0],
+                ) + last_commitment.1.0;
+                d2_prime = multi_mul_helper(
+                    &[srs.delta2_l.pop().unwrap(), srs.delta2_r.pop().unwrap(), last_commitment.0.1.clone()],
+                    &[last_transcript.1 * last_transcript.3, last_transcript.3, last_transcript.1],
+                ) + last_commitment.1.1;
 
                 // Scalar product
                 if i == round-1 {
@@ -406,9 +417,7 @@ where
 
                     let left = IP::inner_product(&e1, &e2)?;
                     let temp1 = c_prime.clone() + kai_scalar;
-                    let temp2 = mul_helper(&d2_prime, &d);
-                    let temp4 = mul_helper(&d1_prime, &d_inv);
-                    let temp5 = temp2 + temp4;//add_helper(&temp2, &temp4);
+                    let temp5 = multi_mul_helper(&[d2_prime.clone(), d1_prime.clone()], &[d, d_inv]);
                     let right = temp1 + temp5;//add_helper(&temp1,&temp5);
                     // let left = IP::inner_product(&e1, &e2)?;
                     // let temp1 = c_prime + kai_scalar;
@@ -455,9 +464,7 @@ where
             e2[0] = e2[0].clone() + mul_helper(&(gamma2[0]), &(d_inv));
             let left = IP::inner_product(&e1, &e2)?;
             let temp1 = c_prime.clone() + kai_scalar;
-            let temp2 = mul_helper(&d2_prime, &d);
-            let temp4 = mul_helper(&d1_prime, &d_inv);
-            let temp5 = temp2 + temp4;//add_helper(&temp2, &temp4);
+            let temp5 = multi_mul_helper(&[d2_prime.clone(), d1_prime.clone()], &[d, d_inv]);
             let right = temp1 + temp5;//add_helper(&temp1,&temp5);
             result = left == right;
             Ok(result)
@@ -527,10 +534,11 @@ where
                 let gamma2_prime = &gamma2_message[..split];
 
                 let cl = start_timer!(|| "Compute D");
-                let d1_l = IP::inner_product(&v1_l, &gamma2_prime)?;
-                let d1_r = IP::inner_product(&v1_r, &gamma2_prime)?;
-                let d2_l = IP::inner_product(&gamma1_prime, &v2_l)?;
-                let d2_r = IP::inner_product(&gamma1_prime, &v2_r)?;
+                let mut d1 = IP::multi_inner_product(&[v1_l, v1_r], gamma2_prime)?.into_iter();
+                let (d1_l, d1_r) = (d1.next().unwrap(), d1.next().unwrap());
+                let mut d2 =
+                    IP::multi_inner_product_shared_left(gamma1_prime, &[v2_l, v2_r])?.into_iter();
+                let (d2_l, d2_r) = (d2.next().unwrap(), d2.next().unwrap());
                 
                  // Fiat-Shamir challenge
                  let mut counter_nonce: usize = 0;
@@ -789,6 +797,85 @@ where
     
     
 
+// DORY works with the commitment keys in message form (Γ1 in the left group, Γ2 in the right),
+// so keys are sampled directly as messages
+impl<IP, LMC, RMC, IPC, D> InnerProductArgument for DORY<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MultiScalarMul<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    LMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+    LMC::Output: MulAssign<LMC::Scalar>,
+    LMC: DoublyHomomorphicCommitment<Key = <RMC as DoublyHomomorphicCommitment>::Message>,
+    RMC: DoublyHomomorphicCommitment<Key = <LMC as DoublyHomomorphicCommitment>::Message>,
+{
+    type InnerProduct = IP;
+    type ProverKey = (Vec<LMC::Message>, Vec<RMC::Message>);
+    type VerifierKey = (DORYSRS<IP, LMC, RMC, IPC, D>, Vec<LMC::Message>, Vec<RMC::Message>);
+    // (d1, d2, c)
+    type Commitment = (IP::Output, IP::Output, IP::Output);
+    type Proof = DORYProof<IP, LMC, RMC, IPC, D>;
+
+    // Keys are derived from the public DORY_DOMAIN_SEPARATOR, so the rng is not used
+    fn setup<R: Rng>(
+        _rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error> {
+        // gamma1 keys the right commitment and gamma2 the left one
+        let (gamma2, gamma1) = Self::setup(size)?;
+        let srs = Self::precompute((&gamma1, &gamma2))?;
+        Ok(((gamma1.clone(), gamma2.clone()), (srs, gamma1, gamma2)))
+    }
+
+    fn commit(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+    ) -> Result<Self::Commitment, Error> {
+        Ok((
+            IP::inner_product(left, &pk.1)?,
+            IP::inner_product(&pk.0, right)?,
+            IP::inner_product(left, right)?,
+        ))
+    }
+
+    fn prove(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+        com: &Self::Commitment,
+    ) -> Result<Self::Proof, Error> {
+        Self::prove((left, right), (&pk.0, &pk.1), (&com.0, &com.1, &com.2))
+    }
+
+    // verify consumes the SRS and proof, so run it on copies
+    fn verify(
+        vk: &Self::VerifierKey,
+        com: &Self::Commitment,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        Self::verify(
+            &mut vk.0.clone(),
+            (&vk.1, &vk.2),
+            (&com.0, &com.1, &com.2),
+            &mut proof.clone(),
+        )
+    }
+}
+
 impl<IP, LMC, RMC, IPC, D> Clone for DORYProof<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -11,7 +11,7 @@ use std::{convert::TryInto, marker::PhantomData, ops::MulAssign};
 
 use crate::{mul_helper, Error, InnerProductArgumentError};
 extern crate ark_dh_commitments;
-use self::ark_dh_commitments::DoublyHomomorphicCommitment;
+use self::ark_dh_commitments::{CommitmentError, DoublyHomomorphicCommitment};
 extern crate ark_inner_products;
 use self::ark_inner_products::InnerProduct;
 use self::ark_std::cfg_iter;
@@ -42,6 +42,9 @@ impl fmt::Display for List {
     }
 }
 
+// Domain separator of the transparent Dory commitment keys
+pub const DORY_DOMAIN_SEPARATOR: &[u8] = b"dory-with-zk-commitment-key";
+
 pub struct DORY<IP, LMC, RMC, IPC, D> {
     _inner_product: PhantomData<IP>,
     _left_commitment: PhantomData<LMC>,
@@ -87,7 +90,7 @@ where
 }
 
 
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct DORYSRS<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -169,6 +172,14 @@ where
     // IPC::Message: AddAssign<LMC::Output>,
     // RMC::Output: AddAssign<LMC::Output>,
     // IPC::Output: AddAssign<LMC::Output>,
+    LMC: DoublyHomomorphicCommitment<
+        Key = <RMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
+    RMC: DoublyHomomorphicCommitment<
+        Key = <LMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
 {
 
     pub fn init_commit(
@@ -179,36 +190,39 @@ where
         r_c: &<LMC as DoublyHomomorphicCommitment>::Scalar,
         r_d1: &<LMC as DoublyHomomorphicCommitment>::Scalar,
         r_d2: &<LMC as DoublyHomomorphicCommitment>::Scalar,
-        h1: &Vec<IP::LeftMessage>,
-        h2: &Vec<IP::RightMessage>
     ) -> Result<(IP::Output, IP::Output, IP::Output), Error> {
         
         let l = left_value.clone();
         let r = right_value.clone();
         let gamma1 = gamma1.clone();
         let gamma2 = gamma2.clone();
-        let h1 = h1.clone();
-        let h2 = h2.clone();
-        let ht = IP::inner_product(&h1, &h2)?;
+        let ht = Self::_blinding_generator()?;
 
 
 
         let c = IP::inner_product(&l, &r)? + mul_helper(&ht, &r_c);
-        let d1 = IP::inner_product(&l, &gamma2)? + mul_helper(&ht, &r_d1);
-        let d2 = IP::inner_product(&gamma1, &r)? + mul_helper(&ht, &r_d2);
+        let d1 = LMC::commit_with_randomness(&gamma2, &l, &r_d1)?;
+        let d2 = RMC::commit_with_randomness(&gamma1, &r, &r_d2)?;
         
         Ok((
             c, d1, d2
         ))
     }
  
-    pub fn setup<R: Rng>(
-        rng: &mut R,
+    // Blinding base of the hiding LMC/RMC commitments, shared by every blinded message of the
+    // protocol
+    fn _blinding_generator() -> Result<IP::Output, Error> {
+        LMC::blinding_generator()
+            .ok_or_else(|| Box::new(CommitmentError::BlindingUnsupported) as Error)
+    }
+
+    // Keys are hashed from the public DORY_DOMAIN_SEPARATOR, so every party derives the same ones
+    pub fn setup(
         size: usize,
     ) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>), Error> {
 
-        let gamma1 = RMC::setup(rng, size)?;
-        let gamma2 = LMC::setup(rng, size)?;
+        let gamma1 = RMC::setup_from_seed(&[DORY_DOMAIN_SEPARATOR, b"/gamma1"].concat(), size)?;
+        let gamma2 = LMC::setup_from_seed(&[DORY_DOMAIN_SEPARATOR, b"/gamma2"].concat(), size)?;
 
         Ok((
             gamma2,
@@ -218,13 +232,9 @@ where
 
     pub fn precompute(
         ck_message: (&[LMC::Message], &[RMC::Message]),
-        h1: &[LMC::Message],
-        h2: &[RMC::Message]
     ) -> Result<DORYSRS<IP,LMC,RMC,IPC,D>, Error> {
         // loop : until ck.len() >= 1
         let (mut gamma1, mut gamma2) = ck_message.clone();
-        let h1 = h1.clone();
-        let h2 = h2.clone();
         // let mut i = ck_message.0.len();
         let mut delta1_l = Vec::new();
         let mut delta1_r = Vec::new();
@@ -257,7 +267,7 @@ where
         delta2_r.reverse();
         kai.reverse();
 
-        let ht = IP::inner_product(&h1, &h2)?;
+        let ht = Self::_blinding_generator()?;
 
         Ok(DORYSRS { delta1_l: delta1_l, delta1_r: delta1_r, delta2_l: delta2_l, delta2_r: delta2_r, kai: kai, ht:ht, _dory: PhantomData })
     }
@@ -0,0 +1,51 @@
+#![deny(warnings, unused, future_incompatible, nonstandard_style)]
+extern crate ark_ff;
+extern crate ark_std;
+
+use ark_ff::PrimeField;
+use ark_std::rand::Rng;
+use std::error::Error as ErrorTrait;
+
+pub type Error = Box<dyn ErrorTrait>;
+
+/// Argument that committed vectors `left` and `right` have Hadamard product `left ∘ right`.
+///
+/// Implemented by the `hpa`, `hpa_bp` and `ghpa_afgho` backends so that protocols built on
+/// top of a Hadamard product argument can be written once and instantiated per deployment.
+pub trait HadamardProductArgument {
+    type Scalar: PrimeField;
+    type ProverKey;
+    type VerifierKey;
+    type Statement;
+    type Witness;
+    type Proof;
+
+    /// Samples keys for vectors of length `size` (a power of two).
+    fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error>;
+
+    /// Commits to `left`, `right` and their Hadamard product.
+    fn commit<R: Rng>(
+        pk: &Self::ProverKey,
+        left: &[Self::Scalar],
+        right: &[Self::Scalar],
+        rng: &mut R,
+    ) -> Result<(Self::Statement, Self::Witness), Error>;
+
+    fn prove<R: Rng>(
+        pk: &Self::ProverKey,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Error>;
+
+    /// `rng` is only drawn from by backends whose verifier samples its own challenges.
+    fn verify<R: Rng>(
+        vk: &Self::VerifierKey,
+        statement: &Self::Statement,
+        proof: &Self::Proof,
+        rng: &mut R,
+    ) -> Result<bool, Error>;
+}
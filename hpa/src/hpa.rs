@@ -13,11 +13,13 @@ extern crate digest;
 use self::digest::Digest;
 use std::{convert::TryInto, f32, marker::PhantomData, ops::MulAssign};
 
-use crate::{mul_helper, Error, InnerProductArgumentError};
+use crate::{mul_helper, multi_mul_helper, Error, InnerProductArgumentError};
 extern crate ark_dh_commitments;
-use self::ark_dh_commitments::DoublyHomomorphicCommitment;
+use self::ark_dh_commitments::{CommitmentError, DoublyHomomorphicCommitment};
+extern crate ark_hadamard_products;
+use self::ark_hadamard_products::HadamardProductArgument;
 extern crate ark_inner_products;
-use self::ark_inner_products::InnerProduct;
+use self::ark_inner_products::{InnerProduct, MultiScalarMul};
 use self::ark_std::cfg_iter;
 
 use std::fmt;
@@ -48,6 +50,9 @@ impl fmt::Display for List {
     }
 }
 
+// Domain separator of the transparent HPA commitment keys
+pub const HPA_DOMAIN_SEPARATOR: &[u8] = b"hpa-commitment-key";
+
 pub struct HPA<IP, LMC, RMC, IPC, D> {
     _inner_product: PhantomData<IP>,
     _left_commitment: PhantomData<LMC>,
@@ -104,7 +109,7 @@ where
     _hpa: PhantomData<HPA<IP, LMC, RMC, IPC, D>>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct HPASRS<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -157,6 +162,112 @@ where
     _hpa: PhantomData<HPA<IP, LMC, RMC, IPC, D>>,
 }
 
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct HPAProverKey<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) gamma1: Vec<LMC::Message>,
+    pub(crate) gamma2: Vec<RMC::Message>,
+    pub(crate) generator_g1: LMC::Message,
+    pub(crate) generator_g2: RMC::Message,
+    pub(crate) srs: HPASRS<IP, LMC, RMC, IPC, D>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct HPAVerifierKey<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) srs: HPASRS<IP, LMC, RMC, IPC, D>,
+    pub(crate) gamma1_base: LMC::Message,
+    pub(crate) gamma2_base: RMC::Message,
+}
+
+// Commitments ( c, x, y, d1, d2, d3, d4 ) to (v1, v2) and (u1, u2) sharing their blindings
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct HPAStatement<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) com: (
+        IP::Output, IP::Output, IP::Output, IP::Output, IP::Output, IP::Output, IP::Output,
+    ),
+    pub(crate) com_: (
+        IP::Output, IP::Output, IP::Output, IP::Output, IP::Output, IP::Output, IP::Output,
+    ),
+    _hpa: PhantomData<HPA<IP, LMC, RMC, IPC, D>>,
+}
+
+pub struct HPAWitness<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) values: (Vec<LMC::Message>, Vec<RMC::Message>, Vec<LMC::Message>, Vec<LMC::Message>),
+    pub(crate) values_: (Vec<LMC::Message>, Vec<RMC::Message>, Vec<LMC::Message>, Vec<LMC::Message>),
+    // r_c, r_x, r_y, r_d1, r_d2, r_d3, r_d4
+    pub(crate) randomness: (
+        LMC::Scalar, LMC::Scalar, LMC::Scalar, LMC::Scalar, LMC::Scalar, LMC::Scalar, LMC::Scalar,
+    ),
+    _hpa: PhantomData<HPA<IP, LMC, RMC, IPC, D>>,
+}
+
 //TODO: Can extend HPA to support "identity commitments" in addition to "compact commitments", i.e. for SIPP
 
 impl<IP, LMC, RMC, IPC, D> HPA<IP, LMC, RMC, IPC, D>
@@ -172,6 +283,7 @@ where
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     RMC::Message: MulAssign<LMC::Scalar>,
     IPC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MultiScalarMul<LMC::Scalar>,
     RMC::Key: MulAssign<LMC::Scalar>,
     LMC::Key: MulAssign<LMC::Scalar>,
     IPC::Key: MulAssign<LMC::Scalar>,
@@ -180,6 +292,14 @@ where
     LMC::Output: MulAssign<LMC::Scalar>,
     IP::LeftMessage: UniformRand,
     IP::RightMessage: UniformRand,
+    LMC: DoublyHomomorphicCommitment<
+        Key = <RMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
+    RMC: DoublyHomomorphicCommitment<
+        Key = <LMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
 {
 
     pub fn set_values(
@@ -218,8 +338,6 @@ where
         right_value: &Vec<IP::RightMessage>,
         gamma1: &Vec<IP::LeftMessage>,
         gamma2: &Vec<IP::RightMessage>,
-        h1: &Vec<IP::LeftMessage>,
-        h2: &Vec<IP::RightMessage>,
         rng: &mut R,
     ) -> Result<
         (
@@ -248,28 +366,17 @@ where
         let r = right_value.clone();
         let gamma1 = gamma1.clone();
         let gamma2 = gamma2.clone();
-        let h1 = h1.clone();
-        let h2 = h2.clone();
-        let ht = IP::inner_product(&h1, &h2)?;
+        let ht = Self::_blinding_generator()?;
 
         let r_c = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
         let r_d1 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
         let r_d2 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
 
         let c = IP::inner_product(&l, &r)? + mul_helper(&ht, &r_c);
-        let d1 = IP::inner_product(&l, &gamma2)? + mul_helper(&ht, &r_d1);
-        let d2 = IP::inner_product(&gamma1, &r)? + mul_helper(&ht, &r_d2);
+        let d1 = LMC::commit_with_randomness(&gamma2, &l, &r_d1)?;
+        let d2 = RMC::commit_with_randomness(&gamma1, &r, &r_d2)?;
 
-        // Fiat-Schamir challenge
-        let gm = 'challenge: loop {
-            let mut hash_input = Vec::new();
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![c, d1, d2]?);
-            let gm: LMC::Scalar =
-                u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
-                    .into();
-            break 'challenge gm;
-        };
+        let gm = Self::_compute_gm(&c, &d1, &d2)?;
         let mut gm_vec = Vec::new();
         gm_vec.push(<LMC as DoublyHomomorphicCommitment>::Scalar::one());
         for i in 1..l.len() {
@@ -294,8 +401,8 @@ where
 
         let x = IP::inner_product(&w_vec, &r).unwrap() + mul_helper(&ht, &r_x);
         let y = IP::inner_product(&k_vec, &r).unwrap() + mul_helper(&ht, &r_y);
-        let d3 = IP::inner_product(&w_vec, &gamma2).unwrap() + mul_helper(&ht, &r_d3);
-        let d4 = IP::inner_product(&k_vec, &gamma2).unwrap() + mul_helper(&ht, &r_d4);
+        let d3 = LMC::commit_with_randomness(&gamma2, &w_vec, &r_d3)?;
+        let d4 = LMC::commit_with_randomness(&gamma2, &k_vec, &r_d4)?;
 
         Ok((
             c, d1, d2, x, y, d3, d4, gm, gm_vec, r_c, r_d1, r_d2, r_x, r_y, r_d3, r_d4, w_vec,
@@ -308,8 +415,6 @@ where
         right_value: &Vec<IP::RightMessage>,
         gamma1: &Vec<IP::LeftMessage>,
         gamma2: &Vec<IP::RightMessage>,
-        h1: &Vec<IP::LeftMessage>,
-        h2: &Vec<IP::RightMessage>,
         // gm: &<LMC as DoublyHomomorphicCommitment>::Scalar,
         gm_vec: &Vec<<LMC as DoublyHomomorphicCommitment>::Scalar>,
         r_c: &<LMC as DoublyHomomorphicCommitment>::Scalar,
@@ -347,17 +452,15 @@ where
         let r = right_value.clone();
         let gamma1 = gamma1.clone();
         let gamma2 = gamma2.clone();
-        let h1 = h1.clone();
-        let h2 = h2.clone();
-        let ht = IP::inner_product(&h1, &h2)?;
+        let ht = Self::_blinding_generator()?;
 
         // let r_c = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
         // let r_d1 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
         // let r_d2 = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
 
         let c = IP::inner_product(&l, &r)? + mul_helper(&ht, &r_c);
-        let d1 = IP::inner_product(&l, &gamma2)? + mul_helper(&ht, &r_d1);
-        let d2 = IP::inner_product(&gamma1, &r)? + mul_helper(&ht, &r_d2);
+        let d1 = LMC::commit_with_randomness(&gamma2, &l, &r_d1)?;
+        let d2 = RMC::commit_with_randomness(&gamma1, &r, &r_d2)?;
 
         // Fiat-Schamir challenge
         // let gm = 'challenge: loop {
@@ -393,8 +496,8 @@ where
 
         let x = IP::inner_product(&w_vec, &r).unwrap() + mul_helper(&ht, &r_x);
         let y = IP::inner_product(&k_vec, &r).unwrap() + mul_helper(&ht, &r_y);
-        let d3 = IP::inner_product(&w_vec, &gamma2).unwrap() + mul_helper(&ht, &r_d3);
-        let d4 = IP::inner_product(&k_vec, &gamma2).unwrap() + mul_helper(&ht, &r_d4);
+        let d3 = LMC::commit_with_randomness(&gamma2, &w_vec, &r_d3)?;
+        let d4 = LMC::commit_with_randomness(&gamma2, &k_vec, &r_d4)?;
 
         Ok((
             c, d1, d2, x, y, d3, d4, w_vec,
@@ -409,7 +512,6 @@ pub fn batch_commit<R: Rng>(
         v2: &Vec<IP::RightMessage>, u2: &Vec<IP::RightMessage>,
         w_vec: &Vec<IP::LeftMessage>, w_vec_: &Vec<IP::LeftMessage>,
         k_vec: &Vec<IP::LeftMessage>, k_vec_: &Vec<IP::LeftMessage>,
-        h1: &Vec<IP::LeftMessage>, h2: &Vec<IP::RightMessage>,
         rng: &mut R,
     ) -> Result<
         (
@@ -427,7 +529,7 @@ pub fn batch_commit<R: Rng>(
         ),
         Error,
     > {
-        let ht = IP::inner_product(&h1, &h2).unwrap();
+        let ht = Self::_blinding_generator()?;
 
         let r_zc = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
         let r_zx = <LMC as DoublyHomomorphicCommitment>::Scalar::rand(rng);
@@ -487,25 +589,33 @@ pub fn batch_commit<R: Rng>(
     }
 
 
-    pub fn setup<R: Rng>(
-        rng: &mut R,
+    // Blinding base of the hiding LMC/RMC commitments, shared by every blinded message of the
+    // protocol
+    fn _blinding_generator() -> Result<IP::Output, Error> {
+        LMC::blinding_generator()
+            .ok_or_else(|| Box::new(CommitmentError::BlindingUnsupported) as Error)
+    }
+
+    // Keys are hashed from the public HPA_DOMAIN_SEPARATOR, so every party derives the same ones
+    pub fn setup(size: usize) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>), Error> {
+        Self::setup_from_seed(HPA_DOMAIN_SEPARATOR, size)
+    }
+
+    pub fn setup_from_seed(
+        domain_separator: &[u8],
         size: usize,
     ) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>), Error> {
-        let gamma1 = RMC::setup(rng, size)?;
-        let gamma2 = LMC::setup(rng, size)?;
+        let gamma1 = RMC::setup_from_seed(&[domain_separator, b"/gamma1"].concat(), size)?;
+        let gamma2 = LMC::setup_from_seed(&[domain_separator, b"/gamma2"].concat(), size)?;
 
         Ok((gamma2, gamma1))
     }
 
     pub fn precompute(
         ck_message: (&[LMC::Message], &[RMC::Message]),
-        h1: &[LMC::Message],
-        h2: &[RMC::Message],
     ) -> Result<HPASRS<IP, LMC, RMC, IPC, D>, Error> {
         // loop : until ck.len() >= 1
         let (mut gamma1, mut gamma2) = ck_message.clone();
-        let h1 = h1.clone();
-        let h2 = h2.clone();
         // let mut i = ck_message.0.len();
         let mut delta1_l = Vec::new();
         let mut delta1_r = Vec::new();
@@ -538,7 +648,7 @@ pub fn batch_commit<R: Rng>(
         delta2_r.reverse();
         kai.reverse();
 
-        let ht = IP::inner_product(&h1, &h2)?;
+        let ht = Self::_blinding_generator()?;
 
         Ok(HPASRS {
             delta1_l: delta1_l,
@@ -604,11 +714,11 @@ pub fn batch_commit<R: Rng>(
     }
 
     pub fn verify<R: Rng>(
-        srs: &mut HPASRS<IP, LMC, RMC, IPC, D>, //
+        srs: &HPASRS<IP, LMC, RMC, IPC, D>, //
         // ck: (&[LMC::Key], &[RMC::Key]),
         ck_message: (&[LMC::Message], &[RMC::Message]),
         com: (&IP::Output, &IP::Output, &IP::Output, &IP::Output, &IP::Output, &IP::Output, &IP::Output), // com ( c, x, y, d1, d2, d3, d4 )
-        proof: &mut HPAProof<IP, LMC, RMC, IPC, D>,
+        proof: &HPAProof<IP, LMC, RMC, IPC, D>,
         gm: &<LMC as DoublyHomomorphicCommitment>::Scalar,
         rng: &mut R
     ) -> Result<bool, Error> {
@@ -648,11 +758,20 @@ pub fn batch_commit<R: Rng>(
         let mut d4_prime = com.6.clone();
         let mut result = false;
 
+        // Rounds are verified from the last one, as the proof and the srs store them
+        let mut r_commitment_steps = proof.r_commitment_steps.iter().rev();
+        let (mut c_x, mut x_plus, mut x_minus) =
+            (proof.c_x.iter().rev(), proof.x_plus.iter().rev(), proof.x_minus.iter().rev());
+        let (mut y_plus, mut y_minus) = (proof.y_plus.iter().rev(), proof.y_minus.iter().rev());
+        let mut kai = srs.kai.iter().rev();
+        let (mut delta1_l, mut delta1_r) = (srs.delta1_l.iter().rev(), srs.delta1_r.iter().rev());
+        let (mut delta2_l, mut delta2_r) = (srs.delta2_l.iter().rev(), srs.delta2_r.iter().rev());
+
         if round > 0 {
             for i in 0..round {
                 // println!("check");
                 // Verifier's work in reduce
-                let last_commitment = proof.r_commitment_steps.pop().unwrap();
+                let last_commitment = r_commitment_steps.next().unwrap().clone();
                 let last_transcript = transcript.pop().unwrap();
 
                 let c_l = last_commitment.0.0.clone();
@@ -664,10 +783,13 @@ pub fn batch_commit<R: Rng>(
                 let d3_l = last_commitment.1.2.clone();
                 let d3_r = last_commitment.1.3.clone();
 
-                assert!(c_prime == c_l + c_r);
-                assert!(x_prime == x_l + x_r);
-                assert!(d1_prime == d1_l + d1_r);
-                assert!(d3_prime == d3_l + d3_r);
+                if c_prime != c_l + c_r
+                    || x_prime != x_l + x_r
+                    || d1_prime != d1_l + d1_r
+                    || d3_prime != d3_l + d3_r
+                {
+                    return Ok(false);
+                }
 
                 let alpha = last_transcript.0;
                 let alpha_inv = last_transcript.1;
@@ -681,21 +803,34 @@ pub fn batch_commit<R: Rng>(
                 let alpha_beta_inv = alpha * beta_inv;
                 let alpha_inv_beta = alpha_inv * beta;
 
-                let last_c_x = proof.c_x.pop().unwrap();
-                let last_x_plus = proof.x_plus.pop().unwrap();
-                let last_x_minus = proof.x_minus.pop().unwrap();
-                let last_y_plus = proof.y_plus.pop().unwrap();
-                let last_y_minus = proof.y_minus.pop().unwrap();
-
-                c_prime = mul_helper(&last_commitment.0.0, &alpha_sqr) + last_commitment.0.1 + mul_helper(&last_commitment.1.0, &alpha_sqr_beta_inv) 
-                    + mul_helper(&last_commitment.1.1, &beta_inv) + mul_helper(&last_c_x, &alpha);
-                x_prime = mul_helper(&last_commitment.0.2, &alpha_sqr) + mul_helper(&last_commitment.0.3, &gm_inv) + mul_helper(&last_commitment.1.2, &alpha_sqr_beta_inv)
-                    + mul_helper(&last_commitment.1.3, &gm_inv_beta_inv) + mul_helper(&last_x_plus, &alpha) + mul_helper(&last_x_minus, &alpha_gm_inv);
-                y_prime = y_prime + srs.kai.pop().unwrap() + mul_helper(&d2_prime, &beta) + mul_helper(&d4_prime, &beta_inv) + mul_helper(&last_y_minus, &alpha) + mul_helper(&last_y_plus, &alpha_inv);
+                let last_c_x = c_x.next().unwrap().clone();
+                let last_x_plus = x_plus.next().unwrap().clone();
+                let last_x_minus = x_minus.next().unwrap().clone();
+                let last_y_plus = y_plus.next().unwrap().clone();
+                let last_y_minus = y_minus.next().unwrap().clone();
+
+                c_prime = multi_mul_helper(
+                    &[last_commitment.0.0.clone(), last_commitment.1.0.clone(), last_commitment.1.1.clone(), last_c_x],
+                    &[alpha_sqr, alpha_sqr_beta_inv, beta_inv, alpha],
+                ) + last_commitment.0.1.clone();
+                x_prime = multi_mul_helper(
+                    &[last_commitment.0.2.clone(), last_commitment.0.3.clone(), last_commitment.1.2.clone(), last_commitment.1.3.clone(), last_x_plus, last_x_minus],
+                    &[alpha_sqr, gm_inv, alpha_sqr_beta_inv, gm_inv_beta_inv, alpha, alpha_gm_inv],
+                );
+                y_prime = y_prime + kai.next().unwrap().clone() + multi_mul_helper(
+                    &[d2_prime, d4_prime, last_y_minus, last_y_plus],
+                    &[beta, beta_inv, alpha, alpha_inv],
+                );
                 d1_prime = mul_helper(&last_commitment.2.0, &alpha) + last_commitment.2.1;
-                d2_prime = mul_helper(&last_commitment.2.2, &alpha) + last_commitment.2.3 + mul_helper(&srs.delta2_l.pop().unwrap(), &alpha_beta_inv) + mul_helper(&srs.delta2_r.pop().unwrap(), &beta_inv);
-                d3_prime = mul_helper(&last_commitment.3.0, &alpha) + mul_helper(&last_commitment.3.1, &gm_inv);
-                d4_prime = mul_helper(&last_commitment.3.2, &alpha_inv) + last_commitment.3.3 + mul_helper(&srs.delta1_l.pop().unwrap(), &alpha_inv_beta) + mul_helper(&srs.delta1_r.pop().unwrap(), &beta);
+                d2_prime = multi_mul_helper(
+                    &[last_commitment.2.2.clone(), delta2_l.next().unwrap().clone(), delta2_r.next().unwrap().clone()],
+                    &[alpha, alpha_beta_inv, beta_inv],
+                ) + last_commitment.2.3.clone();
+                d3_prime = multi_mul_helper(&[last_commitment.3.0.clone(), last_commitment.3.1.clone()], &[alpha, gm_inv]);
+                d4_prime = multi_mul_helper(
+                    &[last_commitment.3.2.clone(), delta1_l.next().unwrap().clone(), delta1_r.next().unwrap().clone()],
+                    &[alpha_inv, alpha_inv_beta, beta],
+                ) + last_commitment.3.3.clone();
 
                 // Scalar product
                 if i == round - 1 {
@@ -726,18 +861,28 @@ pub fn batch_commit<R: Rng>(
                         ch_c_vec.push(ch_c_vec[i-1] * ch_c);
                     }
 
-                    let mut temp2 = c_prime.clone() + mul_helper(&x_prime, &(ch_c_vec[2] + ch_c_vec[5])) + mul_helper(&y_prime, &ch_c_vec[8]);
-                    temp2 = mul_helper(&temp2, &ch_c_vec[1]);
-                    let mut temp3 = d1_prime.clone() + mul_helper(&d3_prime, &(ch_c_vec[2] + ch_c_vec[5])) + mul_helper(&d4_prime, &ch_c_vec[8]);
-                    temp3 = mul_helper(&temp3, &(ch_c * ch_d_inv));
-                    let mut temp4 = proof.q1.clone() + mul_helper(&proof.q2, &ch_c_vec[2]) + mul_helper(&proof.q3, &ch_c_vec[5]) + mul_helper(&proof.p3, &ch_c_vec[8]);
-                    temp4 = mul_helper(&temp4, &ch_c);
+                    // c^2 (c' + (c^3 + c^6) x' + c^9 y') + c d^-1 (d1' + (c^3 + c^6) d3' + c^9 d4') + c d d2'
+                    // + c (q1 + c^3 q2 + c^6 q3 + c^9 p3) + d^-1 p1 + d p2 - (d^-1 r1 + d r2 + r3) ht as one
+                    // multi-exponentiation
+                    let c_3_6 = ch_c_vec[2] + ch_c_vec[5];
+                    let c_d_inv = ch_c * ch_d_inv;
                     let temp5 = ch_d_inv * proof.r1 + ch_d * proof.r2 + proof.r3;
-                    let mut temp6 = mul_helper(&srs.ht, &temp5);
-                    temp6 = mul_helper(&temp6, &minus_one);
-
-                    let right = kai_scalar + temp2 + mul_helper(&d2_prime, &(ch_c * ch_d)) + temp3 + temp4 + mul_helper(&proof.p1, &ch_d_inv)
-                        + mul_helper(&proof.p2, &ch_d) + proof.r.clone() + temp6;
+                    let temp2 = multi_mul_helper(
+                        &[
+                            c_prime.clone(), x_prime.clone(), y_prime.clone(),
+                            d1_prime.clone(), d3_prime.clone(), d4_prime.clone(), d2_prime.clone(),
+                            proof.q1.clone(), proof.q2.clone(), proof.q3.clone(), proof.p3.clone(),
+                            proof.p1.clone(), proof.p2.clone(), srs.ht.clone(),
+                        ],
+                        &[
+                            ch_c_vec[1], c_3_6 * ch_c_vec[1], ch_c_vec[8] * ch_c_vec[1],
+                            c_d_inv, c_3_6 * c_d_inv, ch_c_vec[8] * c_d_inv, ch_c * ch_d,
+                            ch_c, ch_c_vec[2] * ch_c, ch_c_vec[5] * ch_c, ch_c_vec[8] * ch_c,
+                            ch_d_inv, ch_d, temp5 * minus_one,
+                        ],
+                    );
+
+                    let right = kai_scalar + temp2 + proof.r.clone();
                     
                     result = left == right;
                 }
@@ -769,18 +914,28 @@ pub fn batch_commit<R: Rng>(
                 ch_c_vec.push(ch_c_vec[i-1] * ch_c);
             }
 
-            let mut temp2 = c_prime.clone() + mul_helper(&x_prime, &(ch_c_vec[2] + ch_c_vec[5])) + mul_helper(&y_prime, &ch_c_vec[8]);
-            temp2 = mul_helper(&temp2, &ch_c_vec[1]);
-            let mut temp3 = d1_prime.clone() + mul_helper(&d3_prime, &(ch_c_vec[2] + ch_c_vec[5])) + mul_helper(&d4_prime, &ch_c_vec[8]);
-            temp3 = mul_helper(&temp3, &(ch_c * ch_d_inv));
-            let mut temp4 = proof.q1.clone() + mul_helper(&proof.q2, &ch_c_vec[2]) + mul_helper(&proof.q3, &ch_c_vec[5]) + mul_helper(&proof.p3, &ch_c_vec[8]);
-            temp4 = mul_helper(&temp4, &ch_c);
+            // c^2 (c' + (c^3 + c^6) x' + c^9 y') + c d^-1 (d1' + (c^3 + c^6) d3' + c^9 d4') + c d d2'
+            // + c (q1 + c^3 q2 + c^6 q3 + c^9 p3) + d^-1 p1 + d p2 - (d^-1 r1 + d r2 + r3) ht as one
+            // multi-exponentiation
+            let c_3_6 = ch_c_vec[2] + ch_c_vec[5];
+            let c_d_inv = ch_c * ch_d_inv;
             let temp5 = ch_d_inv * proof.r1 + ch_d * proof.r2 + proof.r3;
-            let mut temp6 = mul_helper(&srs.ht, &temp5);
-            temp6 = mul_helper(&temp6, &minus_one);
-
-            let right = kai_scalar + temp2 + mul_helper(&d2_prime, &(ch_c * ch_d)) + temp3 + temp4 + mul_helper(&proof.p1, &ch_d_inv)
-                + mul_helper(&proof.p2, &ch_d) + proof.r.clone() + temp6;
+            let temp2 = multi_mul_helper(
+                &[
+                    c_prime.clone(), x_prime.clone(), y_prime.clone(),
+                    d1_prime.clone(), d3_prime.clone(), d4_prime.clone(), d2_prime.clone(),
+                    proof.q1.clone(), proof.q2.clone(), proof.q3.clone(), proof.p3.clone(),
+                    proof.p1.clone(), proof.p2.clone(), srs.ht.clone(),
+                ],
+                &[
+                    ch_c_vec[1], c_3_6 * ch_c_vec[1], ch_c_vec[8] * ch_c_vec[1],
+                    c_d_inv, c_3_6 * c_d_inv, ch_c_vec[8] * c_d_inv, ch_c * ch_d,
+                    ch_c, ch_c_vec[2] * ch_c, ch_c_vec[5] * ch_c, ch_c_vec[8] * ch_c,
+                    ch_d_inv, ch_d, temp5 * minus_one,
+                ],
+            );
+
+            let right = kai_scalar + temp2 + proof.r.clone();
             
             result = left == right;
             Ok(result)
@@ -912,30 +1067,33 @@ pub fn batch_commit<R: Rng>(
                 let k_vec_r = &k_vec[split..];
 
                 let cl = start_timer!(|| "Compute D");
-                let c_l = IP::inner_product(&v1_l, &v2_l).unwrap() + mul_helper(&ht, &r_cl);
-                let c_r = IP::inner_product(&v1_r, &v2_r).unwrap() + mul_helper(&ht, &r_cr);
-                let x_l = IP::inner_product(&w_vec_l, &v2_l).unwrap() + mul_helper(&ht, &r_xl);
-                let x_r = IP::inner_product(&w_vec_r, &v2_r).unwrap() + mul_helper(&ht, &r_xr);
-                let d1_l = IP::inner_product(&v1_l, &gamma2_prime)? + mul_helper(&ht, &r_d1l);
-                let d1_r = IP::inner_product(&v1_r, &gamma2_r)? + mul_helper(&ht, &r_d1r);
-                let d3_l = IP::inner_product(&w_vec_l, &gamma2_prime)? + mul_helper(&ht, &r_d3l);
-                let d3_r = IP::inner_product(&w_vec_r, &gamma2_r)? + mul_helper(&ht, &r_d3r);
-                let d1_l_prime = IP::inner_product(&v1_l, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d1l_prime);
-                let d1_r_prime = IP::inner_product(&v1_r, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d1r_prime);
-                let d2_l_prime = IP::inner_product(&gamma1_prime, &v2_l).unwrap()
-                    + mul_helper(&ht, &r_d2l_prime);
-                let d2_r_prime = IP::inner_product(&gamma1_prime, &v2_r).unwrap()
-                    + mul_helper(&ht, &r_d2r_prime);
-                let d3_l_prime = IP::inner_product(&w_vec_l, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d3l_prime);
-                let d3_r_prime = IP::inner_product(&w_vec_r, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d3r_prime);
-                let d4_l_prime = IP::inner_product(&k_vec_l, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d4l_prime);
-                let d4_r_prime = IP::inner_product(&k_vec_r, &gamma2_prime).unwrap()
-                    + mul_helper(&ht, &r_d4r_prime);
+                // Inner products sharing a side are batched
+                let v2_l_ips = IP::multi_inner_product(&[v1_l, w_vec_l], v2_l)?;
+                let v2_r_ips = IP::multi_inner_product(&[v1_r, w_vec_r], v2_r)?;
+                let gamma2_prime_ips = IP::multi_inner_product(
+                    &[v1_l, v1_r, w_vec_l, w_vec_r, k_vec_l, k_vec_r],
+                    gamma2_prime,
+                )?;
+                let gamma2_r_ips = IP::multi_inner_product(&[v1_r, w_vec_r], gamma2_r)?;
+                let gamma1_prime_ips =
+                    IP::multi_inner_product_shared_left(gamma1_prime, &[v2_l, v2_r])?;
+
+                let c_l = v2_l_ips[0].clone() + mul_helper(&ht, &r_cl);
+                let c_r = v2_r_ips[0].clone() + mul_helper(&ht, &r_cr);
+                let x_l = v2_l_ips[1].clone() + mul_helper(&ht, &r_xl);
+                let x_r = v2_r_ips[1].clone() + mul_helper(&ht, &r_xr);
+                let d1_l = gamma2_prime_ips[0].clone() + mul_helper(&ht, &r_d1l);
+                let d1_r = gamma2_r_ips[0].clone() + mul_helper(&ht, &r_d1r);
+                let d3_l = gamma2_prime_ips[2].clone() + mul_helper(&ht, &r_d3l);
+                let d3_r = gamma2_r_ips[1].clone() + mul_helper(&ht, &r_d3r);
+                let d1_l_prime = gamma2_prime_ips[0].clone() + mul_helper(&ht, &r_d1l_prime);
+                let d1_r_prime = gamma2_prime_ips[1].clone() + mul_helper(&ht, &r_d1r_prime);
+                let d2_l_prime = gamma1_prime_ips[0].clone() + mul_helper(&ht, &r_d2l_prime);
+                let d2_r_prime = gamma1_prime_ips[1].clone() + mul_helper(&ht, &r_d2r_prime);
+                let d3_l_prime = gamma2_prime_ips[2].clone() + mul_helper(&ht, &r_d3l_prime);
+                let d3_r_prime = gamma2_prime_ips[3].clone() + mul_helper(&ht, &r_d3r_prime);
+                let d4_l_prime = gamma2_prime_ips[4].clone() + mul_helper(&ht, &r_d4l_prime);
+                let d4_r_prime = gamma2_prime_ips[5].clone() + mul_helper(&ht, &r_d4r_prime);
 
                 // Fiat-Shamir challenge
                 let mut counter_nonce: usize = 0;
@@ -999,17 +1157,14 @@ pub fn batch_commit<R: Rng>(
                 let k_vec_l = k_vec[..split].to_vec();
                 let k_vec_r = k_vec[split..].to_vec();
 
-                let c_x = IP::inner_product(&v1_l, &v2_r).unwrap()
-                    + IP::inner_product(&v1_r, &v2_l).unwrap()
-                    + mul_helper(&ht, &r_c_x);
-                let x_plus =
-                    IP::inner_product(&w_vec_l, &v2_r).unwrap() + mul_helper(&ht, &r_x_plus);
-                let x_minus =
-                    IP::inner_product(&w_vec_r, &v2_l).unwrap() + mul_helper(&ht, &r_x_minus);
-                let y_plus =
-                    IP::inner_product(&k_vec_l, &v2_r).unwrap() + mul_helper(&ht, &r_y_plus);
-                let y_minus =
-                    IP::inner_product(&k_vec_r, &v2_l).unwrap() + mul_helper(&ht, &r_y_minus);
+                let v2_r_ips = IP::multi_inner_product(&[v1_l, w_vec_l, &k_vec_l], &v2_r)?;
+                let v2_l_ips = IP::multi_inner_product(&[v1_r, w_vec_r, &k_vec_r], &v2_l)?;
+
+                let c_x = v2_r_ips[0].clone() + v2_l_ips[0].clone() + mul_helper(&ht, &r_c_x);
+                let x_plus = v2_r_ips[1].clone() + mul_helper(&ht, &r_x_plus);
+                let x_minus = v2_l_ips[1].clone() + mul_helper(&ht, &r_x_minus);
+                let y_plus = v2_r_ips[2].clone() + mul_helper(&ht, &r_y_plus);
+                let y_minus = v2_l_ips[2].clone() + mul_helper(&ht, &r_y_minus);
 
                 end_timer!(cr);
 
@@ -1134,15 +1289,16 @@ pub fn batch_commit<R: Rng>(
         d1.push(<IP::LeftMessage>::rand(rng));
         d2.push(<IP::RightMessage>::rand(rng));
 
+        let d2_ips =
+            IP::multi_inner_product(&[&gamma1_message, &k_vec, &v1, &w_vec, &d1], &d2)?;
+
         let p1 = IP::inner_product(&d1, &gamma2_message).unwrap() + mul_helper(&ht, &r_p1);
-        let p2 = IP::inner_product(&gamma1_message, &d2).unwrap() + mul_helper(&ht, &r_p2);
-        let p3 = IP::inner_product(&k_vec, &d2).unwrap() + mul_helper(&ht, &r_p3);
-        let q1 = IP::inner_product(&d1, &v2).unwrap()
-            + IP::inner_product(&v1, &d2).unwrap()
-            + mul_helper(&ht, &r_q1);
-        let q2 = IP::inner_product(&v1, &d2).unwrap() + mul_helper(&ht, &r_q2);
-        let q3 = IP::inner_product(&w_vec, &d2).unwrap() + mul_helper(&ht, &r_q3);
-        let r = IP::inner_product(&d1, &d2).unwrap() + mul_helper(&ht, &r_r);
+        let p2 = d2_ips[0].clone() + mul_helper(&ht, &r_p2);
+        let p3 = d2_ips[1].clone() + mul_helper(&ht, &r_p3);
+        let q1 = IP::inner_product(&d1, &v2).unwrap() + d2_ips[2].clone() + mul_helper(&ht, &r_q1);
+        let q2 = d2_ips[2].clone() + mul_helper(&ht, &r_q2);
+        let q3 = d2_ips[3].clone() + mul_helper(&ht, &r_q3);
+        let r = d2_ips[4].clone() + mul_helper(&ht, &r_r);
 
         let ch_c = 'challenge: loop {
             let mut hash_input = Vec::new();
@@ -1216,6 +1372,21 @@ pub fn batch_commit<R: Rng>(
         Self::_compute_recursive_challenges(proof, gm)
     }
 
+    // Fiat-Shamir challenge gm = H(c, d1, d2), recomputed by the verifier from the statement
+    fn _compute_gm(
+        c: &IP::Output,
+        d1: &IP::Output,
+        d2: &IP::Output,
+    ) -> Result<LMC::Scalar, Error> {
+        let mut hash_input = Vec::new();
+        //TODO: Should use CanonicalSerialize instead of ToBytes
+        hash_input.extend_from_slice(&to_bytes![c, d1, d2]?);
+        let gm: LMC::Scalar =
+            u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                .into();
+        Ok(gm)
+    }
+
     fn _compute_recursive_challenges(
         proof: &HPAProof<IP, LMC, RMC, IPC, D>,
         gm: &<LMC as DoublyHomomorphicCommitment>::Scalar
@@ -1406,6 +1577,194 @@ pub fn batch_commit<R: Rng>(
 //         && IPC::verify(&ck_t, &t_base, &com_t)?)
 // }
 
+// `verify` consumes its SRS, so hand it a fresh copy
+fn _copy_srs<IP, LMC, RMC, IPC, D>(srs: &HPASRS<IP, LMC, RMC, IPC, D>) -> HPASRS<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    HPASRS {
+        delta1_l: srs.delta1_l.clone(),
+        delta1_r: srs.delta1_r.clone(),
+        delta2_l: srs.delta2_l.clone(),
+        delta2_r: srs.delta2_r.clone(),
+        kai: srs.kai.clone(),
+        ht: srs.ht.clone(),
+        _hpa: PhantomData,
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> HadamardProductArgument for HPA<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MultiScalarMul<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    LMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+    LMC::Output: MulAssign<LMC::Scalar>,
+    IP::LeftMessage: UniformRand,
+    IP::RightMessage: UniformRand,
+    LMC: DoublyHomomorphicCommitment<
+        Key = <RMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
+    RMC: DoublyHomomorphicCommitment<
+        Key = <LMC as DoublyHomomorphicCommitment>::Message,
+        Output = <IPC as DoublyHomomorphicCommitment>::Message,
+    >,
+{
+    type Scalar = LMC::Scalar;
+    type ProverKey = HPAProverKey<IP, LMC, RMC, IPC, D>;
+    type VerifierKey = HPAVerifierKey<IP, LMC, RMC, IPC, D>;
+    type Statement = HPAStatement<IP, LMC, RMC, IPC, D>;
+    type Witness = HPAWitness<IP, LMC, RMC, IPC, D>;
+    type Proof = (HPAProof<IP, LMC, RMC, IPC, D>, HPAProof<IP, LMC, RMC, IPC, D>);
+
+    // Keys are derived from the public HPA_DOMAIN_SEPARATOR, so the rng is not used
+    fn setup<R: Rng>(
+        _rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error> {
+        // gamma1 keys the right commitment and gamma2 the left one, as taken by `prove` and
+        // `precompute`
+        let (gamma2, gamma1) = Self::setup(size)?;
+        // Generators are hashed under their own domain, independent of the keys. Blinding uses the
+        // base of the hiding commitments
+        let generator_g1 =
+            RMC::setup_from_seed(&[HPA_DOMAIN_SEPARATOR, b"/generator1"].concat(), 1)?;
+        let generator_g2 =
+            LMC::setup_from_seed(&[HPA_DOMAIN_SEPARATOR, b"/generator2"].concat(), 1)?;
+        let srs = Self::precompute((&gamma1, &gamma2))?;
+
+        let vk = HPAVerifierKey {
+            srs: _copy_srs(&srs),
+            gamma1_base: gamma1[0].clone(),
+            gamma2_base: gamma2[0].clone(),
+        };
+        let pk = HPAProverKey {
+            gamma1,
+            gamma2,
+            generator_g1: generator_g1[0].clone(),
+            generator_g2: generator_g2[0].clone(),
+            srs,
+        };
+        Ok((pk, vk))
+    }
+
+    fn commit<R: Rng>(
+        pk: &Self::ProverKey,
+        left: &[LMC::Scalar],
+        right: &[LMC::Scalar],
+        rng: &mut R,
+    ) -> Result<(Self::Statement, Self::Witness), Error> {
+        if left.len() != pk.gamma1.len() || right.len() != pk.gamma1.len() {
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        }
+        let (v1, v2, u1, u2) =
+            Self::set_values(left, right, &pk.generator_g1, &pk.generator_g2)?;
+
+        let (c, d1, d2, x, y, d3, d4, _gm, gm_vec, r_c, r_d1, r_d2, r_x, r_y, r_d3, r_d4, w_vec, k_vec) =
+            Self::init_commit(&v1, &v2, &pk.gamma1, &pk.gamma2, rng)?;
+        let (c_, d1_, d2_, x_, y_, d3_, d4_, w_vec_, k_vec_) = Self::init_commit2(
+            &u1, &u2, &pk.gamma1, &pk.gamma2, &gm_vec,
+            &r_c, &r_d1, &r_d2, &r_x, &r_y, &r_d3, &r_d4, rng,
+        )?;
+
+        Ok((
+            HPAStatement {
+                com: (c, x, y, d1, d2, d3, d4),
+                com_: (c_, x_, y_, d1_, d2_, d3_, d4_),
+                _hpa: PhantomData,
+            },
+            HPAWitness {
+                values: (v1, v2, w_vec, k_vec),
+                values_: (u1, u2, w_vec_, k_vec_),
+                randomness: (r_c, r_x, r_y, r_d1, r_d2, r_d3, r_d4),
+                _hpa: PhantomData,
+            },
+        ))
+    }
+
+    fn prove<R: Rng>(
+        pk: &Self::ProverKey,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Error> {
+        let (v1, v2, w_vec, k_vec) = &witness.values;
+        let (u1, u2, w_vec_, k_vec_) = &witness.values_;
+        let (r_c, r_x, r_y, r_d1, r_d2, r_d3, r_d4) = &witness.randomness;
+        let (c, _, _, d1, d2, _, _) = &statement.com;
+        let gm = Self::_compute_gm(c, d1, d2)?;
+        let proof = Self::prove(
+            (v1, v2, w_vec, k_vec),
+            &pk.srs,
+            (&pk.gamma1, &pk.gamma2),
+            (r_c, r_x, r_y, r_d1, r_d2, r_d3, r_d4),
+            &gm,
+            rng,
+        )?;
+        let proof_ = Self::prove(
+            (u1, u2, w_vec_, k_vec_),
+            &pk.srs,
+            (&pk.gamma1, &pk.gamma2),
+            (r_c, r_x, r_y, r_d1, r_d2, r_d3, r_d4),
+            &gm,
+            rng,
+        )?;
+        Ok((proof, proof_))
+    }
+
+    fn verify<R: Rng>(
+        vk: &Self::VerifierKey,
+        statement: &Self::Statement,
+        proof: &Self::Proof,
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let (c, x, y, d1, d2, d3, d4) = &statement.com;
+        let (c_, x_, y_, d1_, d2_, d3_, d4_) = &statement.com_;
+        // Both instances must open X to the same weighted sum
+        if x != x_ {
+            return Ok(false);
+        }
+        let gm = Self::_compute_gm(c, d1, d2)?;
+        let ck_base = (
+            std::slice::from_ref(&vk.gamma1_base),
+            std::slice::from_ref(&vk.gamma2_base),
+        );
+        Ok(Self::verify(&vk.srs, ck_base, (c, x, y, d1, d2, d3, d4), &proof.0, &gm, rng)?
+            && Self::verify(&vk.srs, ck_base, (c_, x_, y_, d1_, d2_, d3_, d4_), &proof.1, &gm, rng)?)
+    }
+}
+
 impl<IP, LMC, RMC, IPC, D> Clone for HPAProof<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -1569,3 +1928,56 @@ where
 //         );
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    extern crate ark_bls12_381;
+    extern crate blake2;
+
+    use super::*;
+    use self::ark_bls12_381::{Bls12_381, Fr};
+    use self::blake2::Blake2b;
+    use super::ark_dh_commitments::{
+        afgho16::{HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2},
+        identity::IdentityCommitment,
+    };
+    use super::ark_inner_products::{ExtensionFieldElement, PairingInnerProduct};
+    use super::ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    type PairingHPA = HPA<
+        PairingInnerProduct<Bls12_381>,
+        HidingAFGHOCommitmentG1<Bls12_381>,
+        HidingAFGHOCommitmentG2<Bls12_381>,
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>,
+        Blake2b,
+    >;
+
+    #[test]
+    fn pairing_hadamard_product_argument_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (pk, vk) = <PairingHPA as HadamardProductArgument>::setup(&mut rng, TEST_SIZE).unwrap();
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for _ in 0..TEST_SIZE {
+            left.push(Fr::rand(&mut rng));
+            right.push(Fr::rand(&mut rng));
+        }
+
+        let (statement, witness) = PairingHPA::commit(&pk, &left, &right, &mut rng).unwrap();
+        let proof =
+            <PairingHPA as HadamardProductArgument>::prove(&pk, &statement, &witness, &mut rng)
+                .unwrap();
+        assert!(
+            <PairingHPA as HadamardProductArgument>::verify(&vk, &statement, &proof, &mut rng)
+                .unwrap()
+        );
+
+        let (other, _) = PairingHPA::commit(&pk, &left, &left, &mut rng).unwrap();
+        assert!(
+            !<PairingHPA as HadamardProductArgument>::verify(&vk, &other, &proof, &mut rng)
+                .unwrap()
+        );
+    }
+}
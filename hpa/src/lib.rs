@@ -5,6 +5,9 @@ use std::{
     ops::MulAssign,
 };
 
+extern crate ark_inner_products;
+use ark_inner_products::MultiScalarMul;
+
 pub mod hpa;
 
 pub type Error = Box<dyn ErrorTrait>;
@@ -16,6 +19,11 @@ pub(crate) fn mul_helper<T: MulAssign<F> + Clone, F: Clone>(t: &T, f: &F) -> T {
     clone
 }
 
+// Linear combination sum_i fs[i] * ts[i], with the multiplications done jointly
+pub(crate) fn multi_mul_helper<T: MultiScalarMul<F>, F>(ts: &[T], fs: &[F]) -> T {
+    T::multi_scalar_mul(ts, fs)
+}
+
 //TODO: helper function for mul because relying on MulAssign
 // pub(crate) fn add_helper<T: AddAssign<F> + Clone, F: Clone>(t: &T, f: &F) -> T {
 //     let mut clone = t.clone();
@@ -1,8 +1,9 @@
 extern crate ark_ff;
-// extern crate ark_ec;
+extern crate ark_ec;
 // use self::ark_ec::PairingEngine;
+use self::ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
 
-use self::ark_ff::{to_bytes, Field, One, Zero, UniformRand};
+use self::ark_ff::{to_bytes, Field, One, PrimeField, Zero, UniformRand};
 extern crate ark_serialize;
 use self::ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
@@ -14,9 +15,11 @@ extern crate digest;
 use self::digest::Digest;
 use std::{convert::TryInto, f32, marker::PhantomData, ops::MulAssign};
 
-use crate::{mul_helper, Error, InnerProductArgumentError};
+use crate::{challenge_products, fold, mul_helper, Error, InnerProductArgumentError};
 extern crate ark_dh_commitments;
 use self::ark_dh_commitments::DoublyHomomorphicCommitment;
+extern crate ark_hadamard_products;
+use self::ark_hadamard_products::HadamardProductArgument;
 extern crate ark_inner_products;
 use self::ark_inner_products::InnerProduct;
 use self::ark_std::cfg_iter;
@@ -151,6 +154,40 @@ where
     _hpa: PhantomData<HPA<IP, CM, D>>,
 }
 
+// Commitments to (v1, v2) and (v1 ∘ v2, 1) sharing the blinding of X
+pub struct HPAStatement<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = CM::Message,
+        RightMessage = CM::Message,
+        Output = CM::Message,
+    >,
+    CM: DoublyHomomorphicCommitment,
+{
+    pub(crate) com: (IP::Output, IP::Output, CM::Output, CM::Output, CM::Output),
+    pub(crate) com_: (IP::Output, IP::Output, CM::Output, CM::Output, CM::Output),
+    _hpa: PhantomData<HPA<IP, CM, D>>,
+}
+
+pub struct HPAWitness<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = CM::Message,
+        RightMessage = CM::Message,
+        Output = CM::Message,
+    >,
+    CM: DoublyHomomorphicCommitment,
+{
+    pub(crate) values: (Vec<CM::Message>, Vec<CM::Message>, Vec<CM::Message>),
+    pub(crate) values_: (Vec<CM::Message>, Vec<CM::Message>, Vec<CM::Message>),
+    pub(crate) r_c: CM::Message,
+    pub(crate) r_c_: CM::Message,
+    pub(crate) r_x: CM::Message,
+    _hpa: PhantomData<HPA<IP, CM, D>>,
+}
+
 //TODO: Can extend HPA to support "identity commitments" in addition to "compact commitments", i.e. for SIPP
 
 impl<IP, CM, D> HPA<IP, CM, D>
@@ -260,7 +297,7 @@ where
         //             .into();
         //     break 'challenge gm;
         // };
-        let gm = <CM as DoublyHomomorphicCommitment>::Scalar::rand(rng);
+        let gm = Self::_compute_gm(&c, &d1, &d2)?;
         let mut gm_vec = Vec::new();
         gm_vec.push(<CM as DoublyHomomorphicCommitment>::Scalar::one());
         for i in 1..l.len() {
@@ -552,9 +589,9 @@ where
     }
 
     pub fn verify(
-        ck_message: (Vec<CM::Key>, Vec<CM::Key>),
+        ck_message: (&[CM::Key], &[CM::Key]),
         com: (&IP::Output, &IP::Output, &CM::Output, &CM::Output, &CM::Output), // com ( c, x, d1, d2, d3 )
-        proof: &mut HPAProof<IP, CM, D>,
+        proof: &HPAProof<IP, CM, D>,
         gm: &<CM as DoublyHomomorphicCommitment>::Scalar,
         // rng: &mut R
     ) -> Result<bool, Error> {
@@ -569,7 +606,7 @@ where
         
         let (mut transcript, ch_c) = Self::_compute_recursive_challenges(proof, gm)?;
 
-        let (mut gamma1, mut gamma2) = ck_message.clone();
+        let (mut gamma1, mut gamma2) = (ck_message.0.to_vec(), ck_message.1.to_vec());
 
         let round = transcript.len();
         // let mut c_prime : &IP::Output;
@@ -590,6 +627,9 @@ where
         let mut d2_prime = com.3.clone();
         let mut d3_prime = com.4.clone();
         let mut result = false;
+        if proof.r_d1_x.len() != round || proof.r_d2_x.len() != round {
+            return Ok(false);
+        }
 
         if round > 0 {
             for i in 0..round {
@@ -597,10 +637,10 @@ where
                 // Verifier's work in reduce
                 let split = gamma1.len() / 2;
 
-                let last_commitment = proof.r_commitment_steps.pop().unwrap();
+                let last_commitment = proof.r_commitment_steps[round - 1 - i].clone();
                 let last_transcript = transcript.pop().unwrap();
-                let last_d1_x = proof.r_d1_x.pop().unwrap();
-                let last_d2_x = proof.r_d2_x.pop().unwrap();
+                let last_d1_x = proof.r_d1_x[round - 1 - i].clone();
+                let last_d2_x = proof.r_d2_x[round - 1 - i].clone();
 
                 let c_l = last_commitment.0.0.clone();
                 let c_r = last_commitment.0.1.clone();
@@ -647,11 +687,14 @@ where
                     .map(|(b_1, b_2)| b_1 + b_2.clone())
                     .collect::<Vec<CM::Key>>();
 
-                assert!(c_prime == c_l.clone() + c_r.clone());
-                assert!(x_prime == x_l.clone() + x_r.clone());
-                assert!(d1_prime == d1_l.clone() + d1_r.clone());
-                assert!(d2_prime == d2_l.clone() + d2_r.clone());
-                assert!(d3_prime == d3_l.clone() + d3_r.clone());
+                if c_prime != c_l.clone() + c_r.clone()
+                    || x_prime != x_l.clone() + x_r.clone()
+                    || d1_prime != d1_l.clone() + d1_r.clone()
+                    || d2_prime != d2_l.clone() + d2_r.clone()
+                    || d3_prime != d3_l.clone() + d3_r.clone()
+                {
+                    return Ok(false);
+                }
 
                 let alpha_sqr = alpha * alpha;
                 let alpha_gm_inv = alpha * gm_inv;
@@ -954,19 +997,11 @@ where
                 end_timer!(cl);
 
                 let rescale_v1 = start_timer!(|| "Rescale V1");
-                v1 = cfg_iter!(v1_l)
-                    .map(|a| mul_helper(a, &alpha))
-                    .zip(v1_r)
-                    .map(|(a_1, a_2)| a_1 + a_2.clone())
-                    .collect::<Vec<CM::Message>>();
+                v1 = fold(v1_l, v1_r, &alpha);
                 end_timer!(rescale_v1);
 
                 let rescale_v2 = start_timer!(|| "Rescale V2");
-                v2 = cfg_iter!(v2_l)
-                    .map(|b| mul_helper(b, &alpha))
-                    .zip(v2_r)
-                    .map(|(b_1, b_2)| b_1 + b_2.clone())
-                    .collect::<Vec<CM::Message>>();
+                v2 = fold(v2_l, v2_r, &alpha);
                 end_timer!(rescale_v2);
 
                 let rescale_w_vec = start_timer!(|| "Rescale W");
@@ -1112,6 +1147,21 @@ where
         Self::_compute_recursive_challenges(proof, gm)
     }
 
+    // Fiat-Shamir challenge gm = H(c, d1, d2), recomputed by the verifier from the statement
+    fn _compute_gm(
+        c: &IP::Output,
+        d1: &CM::Output,
+        d2: &CM::Output,
+    ) -> Result<CM::Scalar, Error> {
+        let mut hash_input = Vec::new();
+        //TODO: Should use CanonicalSerialize instead of ToBytes
+        hash_input.extend_from_slice(&to_bytes![c, d1, d2]?);
+        let gm: CM::Scalar =
+            u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                .into();
+        Ok(gm)
+    }
+
     fn _compute_recursive_challenges(
         proof: &HPAProof<IP, CM, D>,
         gm: &<CM as DoublyHomomorphicCommitment>::Scalar
@@ -1218,6 +1268,208 @@ where
 
 }
 
+impl<IP, CM, D, G> HPA<IP, CM, D>
+where
+    D: Digest,
+    G: ProjectiveCurve,
+    IP: InnerProduct<
+        LeftMessage = CM::Message,
+        RightMessage = CM::Message,
+        Output = CM::Message,
+    >,
+    CM: DoublyHomomorphicCommitment<
+        Scalar = G::ScalarField,
+        Message = G::ScalarField,
+        Key = G,
+        Output = G,
+    >,
+{
+    // Same verdict as verify, without folding the keys round by round.
+    // The final keys are <s, gamma1> and <s, gamma2> for the challenge products s,
+    // and every group equation is merged with powers of a weight rho into one MSM.
+    pub fn verify_msm(
+        ck_message: (&[G], &[G]),
+        com: (&G::ScalarField, &G::ScalarField, &G, &G, &G), // com ( c, x, d1, d2, d3 )
+        proof: &HPAProof<IP, CM, D>,
+        gm: &G::ScalarField,
+    ) -> Result<bool, Error> {
+        let (gamma1, gamma2) = ck_message;
+        if gamma1.len().count_ones() != 1 || gamma1.len() != gamma2.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                gamma1.len(),
+                gamma2.len(),
+            )));
+        }
+        let verify_time = start_timer!(|| "Verify with single MSM");
+
+        let (mut transcript, ch_c) = Self::_compute_recursive_challenges(proof, gm)?;
+        // transcript is stored from the last round, as the proof steps are
+        transcript.reverse();
+        let round = transcript.len();
+        if round == 0
+            || gamma1.len() != 1 << round
+            || proof.r_d1_x.len() != round
+            || proof.r_d2_x.len() != round
+            || proof.e1.len() != 1
+            || proof.e2.len() != 1
+        {
+            return Ok(false);
+        }
+        let alphas = transcript.iter().map(|t| t.0).collect::<Vec<G::ScalarField>>();
+        let rho = Self::_compute_msm_weight(com, &alphas, &ch_c, proof)?;
+
+        let one = G::ScalarField::one();
+        let minus_one = -one;
+
+        let mut bases = Vec::new();
+        let mut scalars = Vec::new();
+        let mut weight = one;
+
+        let mut c_prime = *com.0;
+        let mut x_prime = *com.1;
+        // d1', d2', d3' are kept as linear combinations of the proof elements
+        let mut d1_prime = vec![(*com.2, one)];
+        let mut d2_prime = vec![(*com.3, one)];
+        let mut d3_prime = vec![(*com.4, one)];
+
+        let steps = proof
+            .r_commitment_steps
+            .iter()
+            .rev()
+            .zip(proof.r_d1_x.iter().rev())
+            .zip(proof.r_d2_x.iter().rev());
+        for ((((com_1, com_2, com_3, com_4), d1_x), d2_x), (alpha, _, gm_inv)) in
+            steps.zip(&transcript)
+        {
+            if c_prime != com_1.0 + com_1.1 || x_prime != com_2.0 + com_2.1 {
+                return Ok(false);
+            }
+            // d' - d_l - d_r = 0
+            for (d_prime, d_l, d_r) in vec![
+                (&d1_prime, com_3.0, com_3.1),
+                (&d2_prime, com_3.2, com_3.3),
+                (&d3_prime, com_4.0, com_4.1),
+            ] {
+                Self::_push_terms(&mut bases, &mut scalars, d_prime, &weight);
+                Self::_push_terms(
+                    &mut bases,
+                    &mut scalars,
+                    &[(d_l, minus_one), (d_r, minus_one)],
+                    &weight,
+                );
+                weight *= &rho;
+            }
+
+            let alpha_sqr = *alpha * alpha;
+            let alpha_gm_inv = *alpha * gm_inv;
+
+            c_prime = com_1.0 * alpha_sqr + com_1.1 + com_1.2 * alpha;
+            x_prime = com_2.0 * alpha_sqr + com_2.1 * gm_inv + com_2.2 * alpha + com_2.3 * alpha_gm_inv;
+            d1_prime = vec![(com_3.0, alpha_sqr), (com_3.1, one), (*d1_x, *alpha)];
+            d2_prime = vec![(com_3.2, alpha_sqr), (com_3.3, one), (*d2_x, *alpha)];
+            d3_prime = vec![
+                (com_4.0, alpha_sqr),
+                (com_4.1, *gm_inv),
+                (com_4.2, *alpha),
+                (com_4.3, alpha_gm_inv),
+            ];
+        }
+
+        let mut ch_c_vec = Vec::new();
+        ch_c_vec.push(ch_c); // ch_c_vec = {c^1, c^2, c^3, ..., c^7}
+        for i in 1..7 {
+            ch_c_vec.push(ch_c_vec[i - 1] * ch_c);
+        }
+
+        // Scalar product
+        let left = IP::inner_product(&proof.e1, &proof.e2)?;
+        let right = c_prime * ch_c_vec[4]
+            + x_prime * (ch_c_vec[5] + ch_c_vec[6])
+            + proof.q1
+            + proof.q2 * ch_c_vec[3]
+            + proof.q3 * (ch_c_vec[0] + ch_c_vec[1])
+            + proof.q4 * ch_c_vec[2]
+            - proof.r;
+        if left != right {
+            return Ok(false);
+        }
+
+        let s = challenge_products(&alphas);
+
+        // <s, gamma1> e1 - d1' c - d3' (c^2 + c^3) - p1 = 0
+        bases.extend_from_slice(gamma1);
+        scalars.extend(s.iter().map(|s_i| weight * proof.e1[0] * s_i));
+        Self::_push_terms(&mut bases, &mut scalars, &d1_prime, &(-weight * ch_c_vec[0]));
+        Self::_push_terms(
+            &mut bases,
+            &mut scalars,
+            &d3_prime,
+            &(-weight * (ch_c_vec[1] + ch_c_vec[2])),
+        );
+        Self::_push_terms(&mut bases, &mut scalars, &[(proof.p1, minus_one)], &weight);
+        weight *= &rho;
+
+        // <s, gamma2> e2 - d2' c^4 - p2 = 0
+        bases.extend_from_slice(gamma2);
+        scalars.extend(s.iter().map(|s_i| weight * proof.e2[0] * s_i));
+        Self::_push_terms(&mut bases, &mut scalars, &d2_prime, &(-weight * ch_c_vec[3]));
+        Self::_push_terms(&mut bases, &mut scalars, &[(proof.p2, minus_one)], &weight);
+
+        let msm_time = start_timer!(|| format!("MSM of size {}", bases.len()));
+        let bases = G::batch_normalization_into_affine(&bases);
+        let scalars = cfg_iter!(scalars)
+            .map(|s| s.into_repr())
+            .collect::<Vec<_>>();
+        let result = VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero();
+        end_timer!(msm_time);
+
+        end_timer!(verify_time);
+        Ok(result)
+    }
+
+    fn _push_terms(
+        bases: &mut Vec<G>,
+        scalars: &mut Vec<G::ScalarField>,
+        terms: &[(G, G::ScalarField)],
+        weight: &G::ScalarField,
+    ) {
+        for (base, scalar) in terms {
+            bases.push(*base);
+            scalars.push(*scalar * weight);
+        }
+    }
+
+    // rho binds every term of the MSM: the statement, the cross terms r_d1_x and r_d2_x, which no
+    // round challenge covers, and through the challenges the remaining proof elements
+    fn _compute_msm_weight(
+        com: (&G::ScalarField, &G::ScalarField, &G, &G, &G),
+        alphas: &[G::ScalarField],
+        ch_c: &G::ScalarField,
+        proof: &HPAProof<IP, CM, D>,
+    ) -> Result<G::ScalarField, Error> {
+        let mut counter_nonce: usize = 0;
+        let rho = 'challenge: loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            hash_input.extend_from_slice(&to_bytes![
+                com.0, com.1, com.2, com.3, com.4,
+                proof.r_d1_x, proof.r_d2_x,
+                alphas, ch_c, proof.e1, proof.e2, proof.r
+            ]?);
+            let rho: G::ScalarField = u128::from_be_bytes(
+                D::digest(&hash_input).as_slice()[0..16].try_into().unwrap(),
+            )
+            .into();
+            if !rho.is_zero() {
+                break 'challenge rho;
+            }
+            counter_nonce += 1;
+        };
+        Ok(rho)
+    }
+}
+
 // pub(crate) fn _compute_final_commitment_keys(
 //     ck: (&[LMC::Key], &[RMC::Key]),
 //     transcript: &Vec<LMC::Scalar>,
@@ -1270,6 +1522,120 @@ where
 //         && IPC::verify(&ck_t, &t_base, &com_t)?)
 // }
 
+impl<IP, CM, D, F> HadamardProductArgument for HPA<IP, CM, D>
+where
+    D: Digest,
+    F: PrimeField,
+    IP: InnerProduct<
+        LeftMessage = CM::Message,
+        RightMessage = CM::Message,
+        Output = CM::Message,
+    >,
+    CM: DoublyHomomorphicCommitment<Scalar = F, Message = F>,
+    CM::Key: MulAssign<CM::Scalar>,
+    IP::Output: MulAssign<CM::Scalar>,
+{
+    type Scalar = F;
+    type ProverKey = (Vec<CM::Key>, Vec<CM::Key>);
+    type VerifierKey = (Vec<CM::Key>, Vec<CM::Key>);
+    type Statement = HPAStatement<IP, CM, D>;
+    type Witness = HPAWitness<IP, CM, D>;
+    type Proof = (HPAProof<IP, CM, D>, HPAProof<IP, CM, D>);
+
+    fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error> {
+        let ck = Self::setup(rng, size)?;
+        Ok((ck.clone(), ck))
+    }
+
+    fn commit<R: Rng>(
+        pk: &Self::ProverKey,
+        left: &[F],
+        right: &[F],
+        rng: &mut R,
+    ) -> Result<(Self::Statement, Self::Witness), Error> {
+        if left.len().count_ones() != 1 || left.len() != right.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        }
+        let (gamma1, gamma2) = pk;
+        let v1 = left.to_vec();
+        let v2 = right.to_vec();
+        let (u1, u2) = Self::set_values(left, right)?;
+
+        let (c, d1, d2, x, d3, _gm, gm_vec, r_c, r_x, w_vec) =
+            Self::init_commit(&v1, &v2, gamma1, gamma2, rng)?;
+        let (c_, d1_, d2_, x_, d3_, r_c_, w_vec_) =
+            Self::init_commit2(&u1, &u2, gamma1, gamma2, &gm_vec, &r_x, rng)?;
+
+        Ok((
+            HPAStatement {
+                com: (c, x, d1, d2, d3),
+                com_: (c_, x_, d1_, d2_, d3_),
+                _hpa: PhantomData,
+            },
+            HPAWitness {
+                values: (v1, v2, w_vec),
+                values_: (u1, u2, w_vec_),
+                r_c,
+                r_c_,
+                r_x,
+                _hpa: PhantomData,
+            },
+        ))
+    }
+
+    fn prove<R: Rng>(
+        pk: &Self::ProverKey,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Error> {
+        let (v1, v2, w_vec) = &witness.values;
+        let (u1, u2, w_vec_) = &witness.values_;
+        let (c, _, d1, d2, _) = &statement.com;
+        let gm = Self::_compute_gm(c, d1, d2)?;
+        let proof = Self::prove(
+            (v1, v2, w_vec),
+            (&pk.0, &pk.1),
+            (&witness.r_c, &witness.r_x),
+            &gm,
+            rng,
+        )?;
+        let proof_ = Self::prove(
+            (u1, u2, w_vec_),
+            (&pk.0, &pk.1),
+            (&witness.r_c_, &witness.r_x),
+            &gm,
+            rng,
+        )?;
+        Ok((proof, proof_))
+    }
+
+    fn verify<R: Rng>(
+        vk: &Self::VerifierKey,
+        statement: &Self::Statement,
+        proof: &Self::Proof,
+        _rng: &mut R,
+    ) -> Result<bool, Error> {
+        let (c, x, d1, d2, d3) = &statement.com;
+        let (c_, x_, d1_, d2_, d3_) = &statement.com_;
+        // Both instances must open X to the same weighted sum
+        if x != x_ {
+            return Ok(false);
+        }
+        let gm = Self::_compute_gm(c, d1, d2)?;
+        let ck_message = (&vk.0[..], &vk.1[..]);
+        Ok(Self::verify(ck_message, (c, x, d1, d2, d3), &proof.0, &gm)?
+            && Self::verify(ck_message, (c_, x_, d1_, d2_, d3_), &proof.1, &gm)?)
+    }
+}
+
 impl<IP, CM, D> Clone for HPAProof<IP, CM, D>
 where
     D: Digest,
@@ -1435,3 +1801,141 @@ where
 //         );
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    extern crate ark_bls12_381;
+    extern crate ark_ed_on_bls12_381;
+    extern crate blake2;
+
+    use super::*;
+    use self::ark_bls12_381::G1Projective;
+    use self::ark_ed_on_bls12_381::EdwardsProjective as JubJub;
+    use self::blake2::Blake2b;
+    use super::ark_dh_commitments::pedersen::PedersenCommitment;
+    use super::ark_inner_products::ScalarInnerProduct;
+    use super::ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    fn hadamard_product_test<G: ProjectiveCurve>() {
+        type IP<G> = ScalarInnerProduct<<G as ProjectiveCurve>::ScalarField>;
+        type ScalarHPA<G> = HPA<IP<G>, PedersenCommitment<G>, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut v1 = Vec::new();
+        let mut v2 = Vec::new();
+        for _ in 0..TEST_SIZE {
+            v1.push(G::ScalarField::rand(&mut rng));
+            v2.push(G::ScalarField::rand(&mut rng));
+        }
+        let (gamma1, gamma2) = ScalarHPA::<G>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (c, d1, d2, x, d3, gm, _gm_vec, r_c, r_x, w_vec) =
+            ScalarHPA::<G>::init_commit(&v1, &v2, &gamma1, &gamma2, &mut rng).unwrap();
+
+        let proof = ScalarHPA::<G>::prove(
+            (&v1, &v2, &w_vec),
+            (&gamma1, &gamma2),
+            (&r_c, &r_x),
+            &gm,
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(ScalarHPA::<G>::verify_msm(
+            (&gamma1, &gamma2),
+            (&c, &x, &d1, &d2, &d3),
+            &proof,
+            &gm,
+        )
+        .unwrap());
+        let wrong_d1 = d1 + gamma1[0];
+        assert!(!ScalarHPA::<G>::verify_msm(
+            (&gamma1, &gamma2),
+            (&c, &x, &wrong_d1, &d2, &d3),
+            &proof,
+            &gm,
+        )
+        .unwrap());
+        assert!(ScalarHPA::<G>::verify(
+            (&gamma1, &gamma2),
+            (&c, &x, &d1, &d2, &d3),
+            &proof,
+            &gm,
+        )
+        .unwrap());
+    }
+
+    fn hadamard_product_argument_test<H: HadamardProductArgument>() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (pk, vk) = H::setup(&mut rng, TEST_SIZE).unwrap();
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for _ in 0..TEST_SIZE {
+            left.push(H::Scalar::rand(&mut rng));
+            right.push(H::Scalar::rand(&mut rng));
+        }
+
+        let (statement, witness) = H::commit(&pk, &left, &right, &mut rng).unwrap();
+        let proof = H::prove(&pk, &statement, &witness, &mut rng).unwrap();
+        assert!(H::verify(&vk, &statement, &proof, &mut rng).unwrap());
+
+        let (other, _) = H::commit(&pk, &left, &left, &mut rng).unwrap();
+        assert!(!H::verify(&vk, &other, &proof, &mut rng).unwrap());
+    }
+
+    #[test]
+    fn jubjub_msm_weight_binding_test() {
+        type IP = ScalarInnerProduct<<JubJub as ProjectiveCurve>::ScalarField>;
+        type ScalarHPA = HPA<IP, PedersenCommitment<JubJub>, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut v1 = Vec::new();
+        let mut v2 = Vec::new();
+        for _ in 0..TEST_SIZE {
+            v1.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+            v2.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+        }
+        let (gamma1, gamma2) = ScalarHPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (c, d1, d2, x, d3, gm, _gm_vec, r_c, r_x, w_vec) =
+            ScalarHPA::init_commit(&v1, &v2, &gamma1, &gamma2, &mut rng).unwrap();
+        let proof = ScalarHPA::prove(
+            (&v1, &v2, &w_vec),
+            (&gamma1, &gamma2),
+            (&r_c, &r_x),
+            &gm,
+            &mut rng,
+        )
+        .unwrap();
+        let com = (&c, &x, &d1, &d2, &d3);
+
+        // The last cross terms enter the second round checks with weights rho^3 * alpha and
+        // rho^4 * alpha, so shifting them by rho * P and -P cancels for the weight of the honest proof
+        let (transcript, ch_c) = ScalarHPA::_compute_recursive_challenges(&proof, &gm).unwrap();
+        let alphas = transcript.iter().rev().map(|t| t.0).collect::<Vec<_>>();
+        let rho = ScalarHPA::_compute_msm_weight(com, &alphas, &ch_c, &proof).unwrap();
+        let mut forged = proof.clone();
+        let last = forged.r_d1_x.len() - 1;
+        forged.r_d1_x[last] += gamma1[0].mul(rho.into_repr());
+        forged.r_d2_x[last] -= gamma1[0];
+
+        assert!(ScalarHPA::verify_msm((&gamma1, &gamma2), com, &proof, &gm).unwrap());
+        assert!(!ScalarHPA::verify_msm((&gamma1, &gamma2), com, &forged, &gm).unwrap());
+    }
+
+    #[test]
+    fn jubjub_hadamard_product_test() {
+        hadamard_product_test::<JubJub>();
+    }
+
+    #[test]
+    fn jubjub_hadamard_product_argument_test() {
+        type IP = ScalarInnerProduct<<JubJub as ProjectiveCurve>::ScalarField>;
+        hadamard_product_argument_test::<HPA<IP, PedersenCommitment<JubJub>, Blake2b>>();
+    }
+
+    #[test]
+    fn bls12_381_g1_hadamard_product_test() {
+        hadamard_product_test::<G1Projective>();
+    }
+}
@@ -0,0 +1,810 @@
+extern crate ark_ff;
+use self::ark_ff::{to_bytes, Field, One, UniformRand, Zero};
+extern crate ark_serialize;
+use self::ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
+};
+extern crate ark_std;
+use self::ark_std::rand::Rng;
+use self::ark_std::{end_timer, start_timer};
+extern crate digest;
+use self::digest::Digest;
+use std::{convert::TryInto, f32, marker::PhantomData, ops::MulAssign};
+
+use crate::{fold, mul_helper, Error, InnerProductArgumentError};
+extern crate ark_dh_commitments;
+use self::ark_dh_commitments::DoublyHomomorphicCommitment;
+extern crate ark_inner_products;
+use self::ark_inner_products::InnerProduct;
+use self::ark_std::cfg_iter;
+
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "parallel")]
+use self::rayon::prelude::*;
+
+// Hiding variant of the Bulletproofs-style Hadamard product argument.
+// Every vector commitment d = <gamma, v> + h * r_d carries a blinder on the extra generator `h`,
+// the scalar claims (c, x and the cross terms) are Pedersen commitments g * v + h * r_v on a
+// second generator `g`, and the final opening reveals only Schnorr-style responses (r, r1, r2)
+// for the aggregated blinders.
+pub struct HPA<IP, CM, D> {
+    _inner_product: PhantomData<IP>,
+    _commitment: PhantomData<CM>,
+    _digest: PhantomData<D>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct HPAProof<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<LeftMessage = CM::Message, RightMessage = CM::Message, Output = CM::Message>,
+    CM: DoublyHomomorphicCommitment,
+{
+    // ((c_l, c_r, c_x, c_x), (x_l, x_r, x_plus, x_minus), (d1_l, d1_r, d2_l, d2_r), (d3_l, d3_r, d3_plus, d3_minus))
+    pub(crate) r_commitment_steps: Vec<(
+        (CM::Output, CM::Output, CM::Output, CM::Output),
+        (CM::Output, CM::Output, CM::Output, CM::Output),
+        (CM::Output, CM::Output, CM::Output, CM::Output),
+        (CM::Output, CM::Output, CM::Output, CM::Output),
+    )>,
+    pub(crate) r_d1_x: Vec<CM::Output>,
+    pub(crate) r_d2_x: Vec<CM::Output>,
+
+    pub(crate) e1: Vec<IP::LeftMessage>,
+    pub(crate) e2: Vec<IP::RightMessage>,
+    pub(crate) q1: CM::Output,
+    pub(crate) q2: CM::Output,
+    pub(crate) q3: CM::Output,
+    pub(crate) q4: CM::Output,
+    pub(crate) p1: CM::Output,
+    pub(crate) p2: CM::Output,
+    // Schnorr responses for the blinders of the scalar product, gamma1 and gamma2 openings
+    pub(crate) r: CM::Message,
+    pub(crate) r1: CM::Message,
+    pub(crate) r2: CM::Message,
+
+    _hpa: PhantomData<HPA<IP, CM, D>>,
+}
+
+#[derive(Clone)]
+pub struct HPAAux<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<LeftMessage = CM::Message, RightMessage = CM::Message, Output = CM::Message>,
+    CM: DoublyHomomorphicCommitment,
+{
+    _hpa: PhantomData<HPA<IP, CM, D>>,
+}
+
+impl<IP, CM, D> HPA<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<LeftMessage = CM::Message, RightMessage = CM::Message, Output = CM::Message>,
+    CM: DoublyHomomorphicCommitment,
+    CM::Message: MulAssign<CM::Message>,
+    CM::Message: MulAssign<CM::Scalar>,
+    CM::Key: MulAssign<CM::Scalar>,
+    IP::LeftMessage: UniformRand,
+    IP::RightMessage: UniformRand,
+    IP::Output: MulAssign<CM::Scalar>,
+{
+    // Returns (gamma1, gamma2, g, h)
+    pub fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Vec<CM::Key>, Vec<CM::Key>, CM::Key, CM::Key), Error> {
+        let gamma1 = CM::setup(rng, size)?;
+        let gamma2 = CM::setup(rng, size)?;
+        let gh = CM::setup(rng, 2)?;
+
+        Ok((gamma1, gamma2, gh[0].clone(), gh[1].clone()))
+    }
+
+    // Blinding term h * r
+    fn _blind(h: &CM::Key, r: &CM::Message) -> Result<CM::Output, Error> {
+        CM::commit(&[h.clone()], &[r.clone()])
+    }
+
+    // Pedersen commitment g * v + h * r to a scalar
+    fn _commit_scalar(
+        g: &CM::Key,
+        h: &CM::Key,
+        v: &IP::Output,
+        r: &CM::Message,
+    ) -> Result<CM::Output, Error> {
+        CM::commit(&[g.clone(), h.clone()], &[v.clone(), r.clone()])
+    }
+
+    pub fn init_commit<R: Rng>(
+        left_value: &Vec<IP::LeftMessage>,
+        right_value: &Vec<IP::RightMessage>,
+        gamma1: &Vec<CM::Key>,
+        gamma2: &Vec<CM::Key>,
+        g: &CM::Key,
+        h: &CM::Key,
+        rng: &mut R,
+    ) -> Result<
+        (
+            CM::Output,
+            CM::Output,
+            CM::Output,
+            CM::Output,
+            CM::Output,
+            CM::Scalar,
+            Vec<CM::Scalar>,
+            CM::Message,
+            CM::Message,
+            CM::Message,
+            CM::Message,
+            CM::Message,
+            Vec<IP::LeftMessage>,
+            // c, d1, d2, x, d3, gm, gm_vec, r_c, r_x, r_d1, r_d2, r_d3, w_vec
+        ),
+        Error,
+    > {
+        let l = left_value.clone();
+        let r = right_value.clone();
+
+        let r_c = CM::Message::rand(rng);
+        let r_x = CM::Message::rand(rng);
+        let r_d1 = CM::Message::rand(rng);
+        let r_d2 = CM::Message::rand(rng);
+        let r_d3 = CM::Message::rand(rng);
+
+        let c = Self::_commit_scalar(g, h, &IP::inner_product(&l, &r)?, &r_c)?;
+        let d1 = CM::commit(gamma1, &l)? + Self::_blind(h, &r_d1)?;
+        let d2 = CM::commit(gamma2, &r)? + Self::_blind(h, &r_d2)?;
+
+        let gm = CM::Scalar::rand(rng);
+        let mut gm_vec = Vec::new();
+        gm_vec.push(CM::Scalar::one());
+        for i in 1..l.len() {
+            gm_vec.push(gm_vec[i - 1] * gm);
+        }
+        let mut w_vec = Vec::new();
+        for i in 0..l.len() {
+            w_vec.push(mul_helper(&l[i], &gm_vec[i]));
+        }
+
+        let x = Self::_commit_scalar(g, h, &IP::inner_product(&w_vec, &r)?, &r_x)?;
+        let d3 = CM::commit(gamma1, &w_vec)? + Self::_blind(h, &r_d3)?;
+
+        Ok((
+            c, d1, d2, x, d3, gm, gm_vec, r_c, r_x, r_d1, r_d2, r_d3, w_vec,
+        ))
+    }
+
+    pub fn prove<R: Rng>(
+        values: (&[IP::LeftMessage], &[IP::RightMessage], &[IP::LeftMessage]),
+        ck_message: (&[CM::Key], &[CM::Key]),
+        g: &CM::Key,
+        h: &CM::Key,
+        witness: (
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+        ), // witness ( r_c, r_x, r_d1, r_d2, r_d3 )
+        gm: &CM::Scalar,
+        rng: &mut R,
+    ) -> Result<HPAProof<IP, CM, D>, Error> {
+        let (proof, _) = Self::prove_with_aux(values, ck_message, g, h, witness, gm, rng)?;
+        Ok(proof)
+    }
+
+    pub fn verify(
+        ck_message: (&[CM::Key], &[CM::Key]),
+        g: &CM::Key,
+        h: &CM::Key,
+        com: (
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+        ), // com ( c, x, d1, d2, d3 )
+        proof: &HPAProof<IP, CM, D>,
+        gm: &CM::Scalar,
+    ) -> Result<bool, Error> {
+        if ck_message.0.len().count_ones() != 1 || ck_message.0.len() != ck_message.1.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                ck_message.0.len(),
+                ck_message.1.len(),
+            )));
+        }
+        if proof.r_commitment_steps.len() != ck_message.0.len().trailing_zeros() as usize
+            || proof.r_d1_x.len() != proof.r_commitment_steps.len()
+            || proof.r_d2_x.len() != proof.r_commitment_steps.len()
+            || proof.e1.len() != 1
+            || proof.e2.len() != 1
+        {
+            return Ok(false);
+        }
+
+        let (transcript, ch_c) = Self::_compute_recursive_challenges(proof, gm)?;
+
+        let mut gamma1 = ck_message.0.to_vec();
+        let mut gamma2 = ck_message.1.to_vec();
+
+        let mut c_prime = com.0.clone();
+        let mut x_prime = com.1.clone();
+        let mut d1_prime = com.2.clone();
+        let mut d2_prime = com.3.clone();
+        let mut d3_prime = com.4.clone();
+
+        for (i, (com_1, com_2, com_3, com_4)) in proof.r_commitment_steps.iter().enumerate() {
+            let (c_l, c_r, c_x, _) = com_1.clone();
+            let (x_l, x_r, x_plus, x_minus) = com_2.clone();
+            let (d1_l, d1_r, d2_l, d2_r) = com_3.clone();
+            let (d3_l, d3_r, d3_plus, d3_minus) = com_4.clone();
+            let d1_x = proof.r_d1_x[i].clone();
+            let d2_x = proof.r_d2_x[i].clone();
+            let (alpha, _, gm_inv) = transcript[i];
+
+            if c_prime != c_l.clone() + c_r.clone()
+                || x_prime != x_l.clone() + x_r.clone()
+                || d1_prime != d1_l.clone() + d1_r.clone()
+                || d2_prime != d2_l.clone() + d2_r.clone()
+                || d3_prime != d3_l.clone() + d3_r.clone()
+            {
+                return Ok(false);
+            }
+
+            let split = gamma1.len() / 2;
+            let gamma1_l = &gamma1[..split];
+            let gamma1_r = &gamma1[split..];
+            let gamma2_l = &gamma2[..split];
+            let gamma2_r = &gamma2[split..];
+
+            let next_gamma1 = cfg_iter!(gamma1_l)
+                .map(|b| mul_helper(b, &alpha))
+                .zip(gamma1_r)
+                .map(|(b_1, b_2)| b_1 + b_2.clone())
+                .collect::<Vec<CM::Key>>();
+            let next_gamma2 = cfg_iter!(gamma2_l)
+                .map(|b| mul_helper(b, &alpha))
+                .zip(gamma2_r)
+                .map(|(b_1, b_2)| b_1 + b_2.clone())
+                .collect::<Vec<CM::Key>>();
+            gamma1 = next_gamma1;
+            gamma2 = next_gamma2;
+
+            let alpha_sqr = alpha * alpha;
+            let alpha_gm_inv = alpha * gm_inv;
+
+            c_prime = mul_helper(&c_l, &alpha_sqr) + c_r + mul_helper(&c_x, &alpha);
+            x_prime = mul_helper(&x_l, &alpha_sqr)
+                + mul_helper(&x_r, &gm_inv)
+                + mul_helper(&x_plus, &alpha)
+                + mul_helper(&x_minus, &alpha_gm_inv);
+            d1_prime = mul_helper(&d1_l, &alpha_sqr) + d1_r + mul_helper(&d1_x, &alpha);
+            d2_prime = mul_helper(&d2_l, &alpha_sqr) + d2_r + mul_helper(&d2_x, &alpha);
+            d3_prime = mul_helper(&d3_l, &alpha_sqr)
+                + mul_helper(&d3_r, &gm_inv)
+                + mul_helper(&d3_plus, &alpha)
+                + mul_helper(&d3_minus, &alpha_gm_inv);
+        }
+
+        let mut ch_c_vec = Vec::new();
+        ch_c_vec.push(ch_c.clone()); // ch_c_vec = {c^1, c^2, c^3, ..., c^7}
+        for i in 1..7 {
+            ch_c_vec.push(ch_c_vec[i - 1] * ch_c);
+        }
+
+        // Opening of the scalar product commitment
+        let temp_left =
+            Self::_commit_scalar(g, h, &IP::inner_product(&proof.e1, &proof.e2)?, &proof.r)?;
+        let temp_right = mul_helper(&c_prime, &ch_c_vec[4])
+            + mul_helper(&x_prime, &(ch_c_vec[5] + ch_c_vec[6]))
+            + proof.q1.clone()
+            + mul_helper(&proof.q2, &ch_c_vec[3])
+            + mul_helper(&proof.q3, &(ch_c_vec[0] + ch_c_vec[1]))
+            + mul_helper(&proof.q4, &ch_c_vec[2]);
+        let result1 = temp_left == temp_right;
+
+        // Openings of the blinded vector commitments
+        let temp_left = CM::commit(&gamma1, &proof.e1)? + Self::_blind(h, &proof.r1)?;
+        let temp_right = mul_helper(&d1_prime, &ch_c_vec[0])
+            + mul_helper(&d3_prime, &(ch_c_vec[1] + ch_c_vec[2]))
+            + proof.p1.clone();
+        let result2 = temp_left == temp_right;
+
+        let temp_left = CM::commit(&gamma2, &proof.e2)? + Self::_blind(h, &proof.r2)?;
+        let temp_right = mul_helper(&d2_prime, &ch_c_vec[3]) + proof.p2.clone();
+        let result3 = temp_left == temp_right;
+
+        Ok(result1 && result2 && result3)
+    }
+
+    pub fn prove_with_aux<R: Rng>(
+        values: (&[IP::LeftMessage], &[IP::RightMessage], &[IP::LeftMessage]),
+        ck_message: (&[CM::Key], &[CM::Key]),
+        g: &CM::Key,
+        h: &CM::Key,
+        witness: (
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+        ),
+        gm: &CM::Scalar,
+        rng: &mut R,
+    ) -> Result<(HPAProof<IP, CM, D>, HPAAux<IP, CM, D>), Error> {
+        let (v1, v2, w_vec) = values;
+        if v1.len().count_ones() != 1 || v1.len() != v2.len() || v1.len() != w_vec.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                v1.len(),
+                v2.len(),
+            )));
+        }
+        let (gamma1_message, gamma2_message) = ck_message;
+        Self::_prove(
+            &(v1.to_vec(), v2.to_vec(), w_vec.to_vec()),
+            (gamma1_message.to_vec(), gamma2_message.to_vec()),
+            g,
+            h,
+            witness,
+            gm,
+            rng,
+        )
+    }
+
+    // Returns vector of recursive commitments in round order
+    fn _prove<R: Rng>(
+        values: &(
+            Vec<IP::LeftMessage>,
+            Vec<IP::RightMessage>,
+            Vec<IP::LeftMessage>,
+        ),
+        ck_message: (Vec<CM::Key>, Vec<CM::Key>),
+        g: &CM::Key,
+        h: &CM::Key,
+        witness: (
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+            &CM::Message,
+        ),
+        gm: &CM::Scalar,
+        rng: &mut R,
+    ) -> Result<(HPAProof<IP, CM, D>, HPAAux<IP, CM, D>), Error> {
+        let (mut v1, mut v2, mut w_vec) = values.clone();
+        let (mut gamma1_message, mut gamma2_message) = ck_message;
+        let mut r_commitment_steps = Vec::new();
+        let mut r_d1_x = Vec::new();
+        let mut r_d2_x = Vec::new();
+
+        let mut r_c = witness.0.clone();
+        let mut r_x = witness.1.clone();
+        let mut r_d1 = witness.2.clone();
+        let mut r_d2 = witness.3.clone();
+        let mut r_d3 = witness.4.clone();
+
+        let zero = CM::Scalar::zero();
+        let minus_one = zero - CM::Scalar::one();
+
+        while v1.len() > 1 {
+            let recurse = start_timer!(|| format!("Recurse round size {}", v1.len()));
+            let split = v1.len() / 2;
+
+            let r_cl = CM::Message::rand(rng);
+            let r_cx = CM::Message::rand(rng);
+            let r_xl = CM::Message::rand(rng);
+            let r_x_plus = CM::Message::rand(rng);
+            let r_x_minus = CM::Message::rand(rng);
+            let r_d1l = CM::Message::rand(rng);
+            let r_d1x = CM::Message::rand(rng);
+            let r_d2l = CM::Message::rand(rng);
+            let r_d2x = CM::Message::rand(rng);
+            let r_d3l = CM::Message::rand(rng);
+            let r_d3_plus = CM::Message::rand(rng);
+            let r_d3_minus = CM::Message::rand(rng);
+
+            // Right halves of the blinders are fixed so that l + r opens the previous commitment
+            let r_cr = r_c + mul_helper(&r_cl, &minus_one);
+            let r_xr = r_x + mul_helper(&r_xl, &minus_one);
+            let r_d1r = r_d1 + mul_helper(&r_d1l, &minus_one);
+            let r_d2r = r_d2 + mul_helper(&r_d2l, &minus_one);
+            let r_d3r = r_d3 + mul_helper(&r_d3l, &minus_one);
+
+            let v1_l = &v1[..split];
+            let v1_r = &v1[split..];
+            let gamma1_l = &gamma1_message[..split];
+            let gamma1_r = &gamma1_message[split..];
+
+            let v2_l = &v2[..split];
+            let v2_r = &v2[split..];
+            let gamma2_l = &gamma2_message[..split];
+            let gamma2_r = &gamma2_message[split..];
+
+            let w_vec_l = &w_vec[..split];
+            let w_vec_r = &w_vec[split..];
+
+            let cl = start_timer!(|| "Compute D");
+
+            let c_l = Self::_commit_scalar(g, h, &IP::inner_product(&v1_l, &v2_l)?, &r_cl)?;
+            let c_r = Self::_commit_scalar(g, h, &IP::inner_product(&v1_r, &v2_r)?, &r_cr)?;
+            let c_x = Self::_commit_scalar(
+                g,
+                h,
+                &(IP::inner_product(&v1_l, &v2_r)? + IP::inner_product(&v1_r, &v2_l)?),
+                &r_cx,
+            )?;
+            let x_l = Self::_commit_scalar(g, h, &IP::inner_product(&w_vec_l, &v2_l)?, &r_xl)?;
+            let x_r = Self::_commit_scalar(g, h, &IP::inner_product(&w_vec_r, &v2_r)?, &r_xr)?;
+            let x_plus =
+                Self::_commit_scalar(g, h, &IP::inner_product(&w_vec_l, &v2_r)?, &r_x_plus)?;
+            let x_minus =
+                Self::_commit_scalar(g, h, &IP::inner_product(&w_vec_r, &v2_l)?, &r_x_minus)?;
+            let d1_l = CM::commit(&gamma1_l, &v1_l)? + Self::_blind(h, &r_d1l)?;
+            let d1_r = CM::commit(&gamma1_r, &v1_r)? + Self::_blind(h, &r_d1r)?;
+            let d1_x = CM::commit(&gamma1_l, &v1_r)?
+                + CM::commit(&gamma1_r, &v1_l)?
+                + Self::_blind(h, &r_d1x)?;
+            let d2_l = CM::commit(&gamma2_l, &v2_l)? + Self::_blind(h, &r_d2l)?;
+            let d2_r = CM::commit(&gamma2_r, &v2_r)? + Self::_blind(h, &r_d2r)?;
+            let d2_x = CM::commit(&gamma2_l, &v2_r)?
+                + CM::commit(&gamma2_r, &v2_l)?
+                + Self::_blind(h, &r_d2x)?;
+            let d3_l = CM::commit(&gamma1_l, &w_vec_l)? + Self::_blind(h, &r_d3l)?;
+            let d3_r = CM::commit(&gamma1_r, &w_vec_r)? + Self::_blind(h, &r_d3r)?;
+            let d3_plus = CM::commit(&gamma1_r, &w_vec_l)? + Self::_blind(h, &r_d3_plus)?;
+            let d3_minus = CM::commit(&gamma1_l, &w_vec_r)? + Self::_blind(h, &r_d3_minus)?;
+
+            end_timer!(cl);
+
+            // Fiat-Shamir challenge
+            let (alpha, _) = Self::_compute_round_challenge(
+                (&c_l, &c_r, &c_x),
+                (&x_l, &x_r, &x_plus, &x_minus),
+                (&d1_l, &d1_r, &d1_x, &d2_l, &d2_r, &d2_x),
+                (&d3_l, &d3_r, &d3_plus, &d3_minus),
+            )?;
+
+            let rescale_v1 = start_timer!(|| "Rescale V1");
+            v1 = fold(v1_l, v1_r, &alpha);
+            end_timer!(rescale_v1);
+
+            let rescale_v2 = start_timer!(|| "Rescale V2");
+            v2 = fold(v2_l, v2_r, &alpha);
+            end_timer!(rescale_v2);
+
+            let rescale_w_vec = start_timer!(|| "Rescale W");
+            let mut gm_inv = gm.inverse().unwrap();
+            let exp_m = f32::log2(w_vec.len() as f32) as usize;
+            for _ in 0..exp_m - 1 {
+                gm_inv = gm_inv * gm_inv;
+            }
+            w_vec = cfg_iter!(w_vec_l)
+                .map(|b| mul_helper(b, &alpha))
+                .zip(w_vec_r)
+                .map(|(b_1, b_2)| b_1 + mul_helper(b_2, &gm_inv))
+                .collect::<Vec<CM::Message>>();
+            end_timer!(rescale_w_vec);
+
+            let alpha_sqr = alpha * alpha;
+            let gm_alpha = alpha * gm_inv;
+            r_c = mul_helper(&r_cl, &alpha_sqr) + r_cr + mul_helper(&r_cx, &alpha);
+            r_x = mul_helper(&r_xl, &alpha_sqr)
+                + mul_helper(&r_xr, &gm_inv)
+                + mul_helper(&r_x_plus, &alpha)
+                + mul_helper(&r_x_minus, &gm_alpha);
+            r_d1 = mul_helper(&r_d1l, &alpha_sqr) + r_d1r + mul_helper(&r_d1x, &alpha);
+            r_d2 = mul_helper(&r_d2l, &alpha_sqr) + r_d2r + mul_helper(&r_d2x, &alpha);
+            r_d3 = mul_helper(&r_d3l, &alpha_sqr)
+                + mul_helper(&r_d3r, &gm_inv)
+                + mul_helper(&r_d3_plus, &alpha)
+                + mul_helper(&r_d3_minus, &gm_alpha);
+
+            gamma1_message = cfg_iter!(gamma1_l)
+                .map(|b| mul_helper(b, &alpha))
+                .zip(gamma1_r)
+                .map(|(b_1, b_2)| b_1 + b_2.clone())
+                .collect::<Vec<CM::Key>>();
+            gamma2_message = cfg_iter!(gamma2_l)
+                .map(|b| mul_helper(b, &alpha))
+                .zip(gamma2_r)
+                .map(|(b_1, b_2)| b_1 + b_2.clone())
+                .collect::<Vec<CM::Key>>();
+
+            let com1 = (c_l, c_r, c_x.clone(), c_x);
+            let com2 = (x_l, x_r, x_plus, x_minus);
+            let com3 = (d1_l, d1_r, d2_l, d2_r);
+            let com4 = (d3_l, d3_r, d3_plus, d3_minus);
+
+            r_commitment_steps.push((com1, com2, com3, com4));
+            r_d1_x.push(d1_x);
+            r_d2_x.push(d2_x);
+
+            end_timer!(recurse);
+        }
+
+        let v1_val = v1.pop().unwrap();
+        let v2_val = v2.pop().unwrap();
+        let w_val = w_vec.pop().unwrap();
+
+        // Final sigma step: mask the folded values and commit to the masks with fresh blinders
+        let s_d1 = <IP::LeftMessage>::rand(rng);
+        let s_d2 = <IP::RightMessage>::rand(rng);
+        let r_q1 = CM::Message::rand(rng);
+        let r_q2 = CM::Message::rand(rng);
+        let r_q3 = CM::Message::rand(rng);
+        let r_q4 = CM::Message::rand(rng);
+        let r_p1 = CM::Message::rand(rng);
+        let r_p2 = CM::Message::rand(rng);
+
+        let q1 = Self::_commit_scalar(g, h, &mul_helper(&s_d1, &s_d2), &r_q1)?;
+        let q2 = Self::_commit_scalar(g, h, &mul_helper(&s_d1, &v2_val), &r_q2)?;
+        let q3 = Self::_commit_scalar(g, h, &mul_helper(&v1_val, &s_d2), &r_q3)?;
+        let q4 = Self::_commit_scalar(g, h, &mul_helper(&w_val, &s_d2), &r_q4)?;
+        let p1 = CM::commit(&gamma1_message, &[s_d1.clone()])? + Self::_blind(h, &r_p1)?;
+        let p2 = CM::commit(&gamma2_message, &[s_d2.clone()])? + Self::_blind(h, &r_p2)?;
+
+        let ch_c = Self::_compute_final_challenge((&q1, &q2, &q3, &q4), (&p1, &p2))?;
+
+        let ch_c_2 = ch_c * ch_c;
+        let ch_c_3 = ch_c_2 * ch_c;
+        let ch_c_4 = ch_c_3 * ch_c;
+        let ch_c_5 = ch_c_4 * ch_c;
+        let ch_c_6 = ch_c_5 * ch_c;
+        let ch_c_7 = ch_c_6 * ch_c;
+
+        let e1 = s_d1 + mul_helper(&v1_val, &(ch_c + ch_c_2)) + mul_helper(&w_val, &ch_c_3);
+        let e2 = s_d2 + mul_helper(&v2_val, &ch_c_4);
+        let r = r_q1
+            + mul_helper(&r_q2, &ch_c_4)
+            + mul_helper(&r_q3, &(ch_c + ch_c_2))
+            + mul_helper(&r_q4, &ch_c_3)
+            + mul_helper(&r_c, &ch_c_5)
+            + mul_helper(&r_x, &(ch_c_6 + ch_c_7));
+        let r1 = r_p1 + mul_helper(&r_d1, &ch_c) + mul_helper(&r_d3, &(ch_c_2 + ch_c_3));
+        let r2 = r_p2 + mul_helper(&r_d2, &ch_c_4);
+
+        Ok((
+            HPAProof {
+                r_commitment_steps,
+                r_d1_x,
+                r_d2_x,
+                e1: vec![e1],
+                e2: vec![e2],
+                q1,
+                q2,
+                q3,
+                q4,
+                p1,
+                p2,
+                r,
+                r1,
+                r2,
+                _hpa: PhantomData,
+            },
+            HPAAux { _hpa: PhantomData },
+        ))
+    }
+
+    // Helper function used to calculate recursive challenges from proof execution (transcript in round order)
+    pub fn verify_recursive_challenge_transcript(
+        proof: &HPAProof<IP, CM, D>,
+        gm: &CM::Scalar,
+    ) -> Result<(Vec<(CM::Scalar, CM::Scalar, CM::Scalar)>, CM::Scalar), Error> {
+        Self::_compute_recursive_challenges(proof, gm)
+    }
+
+    fn _compute_recursive_challenges(
+        proof: &HPAProof<IP, CM, D>,
+        gm: &CM::Scalar,
+    ) -> Result<(Vec<(CM::Scalar, CM::Scalar, CM::Scalar)>, CM::Scalar), Error> {
+        let mut r_transcript = Vec::new();
+        let round = proof.r_commitment_steps.len();
+
+        for (i, (com_1, com_2, com_3, com_4)) in proof.r_commitment_steps.iter().enumerate() {
+            let (alpha, alpha_inv) = Self::_compute_round_challenge(
+                (&com_1.0, &com_1.1, &com_1.2),
+                (&com_2.0, &com_2.1, &com_2.2, &com_2.3),
+                (
+                    &com_3.0,
+                    &com_3.1,
+                    &proof.r_d1_x[i],
+                    &com_3.2,
+                    &com_3.3,
+                    &proof.r_d2_x[i],
+                ),
+                (&com_4.0, &com_4.1, &com_4.2, &com_4.3),
+            )?;
+
+            // gm^{-2^{k-1}} where 2^k is the length of w in this round
+            let mut gm_inv = gm.inverse().unwrap();
+            for _ in 0..(round - i - 1) {
+                gm_inv = gm_inv * gm_inv;
+            }
+
+            r_transcript.push((alpha, alpha_inv, gm_inv));
+        }
+
+        let ch_c = Self::_compute_final_challenge(
+            (&proof.q1, &proof.q2, &proof.q3, &proof.q4),
+            (&proof.p1, &proof.p2),
+        )?;
+
+        Ok((r_transcript, ch_c))
+    }
+
+    fn _compute_round_challenge(
+        com_c: (&CM::Output, &CM::Output, &CM::Output),
+        com_x: (&CM::Output, &CM::Output, &CM::Output, &CM::Output),
+        com_d: (
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+            &CM::Output,
+        ),
+        com_d3: (&CM::Output, &CM::Output, &CM::Output, &CM::Output),
+    ) -> Result<(CM::Scalar, CM::Scalar), Error> {
+        let mut counter_nonce: usize = 0;
+        let (alpha, alpha_inv) = 'challenge: loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            //TODO: Should use CanonicalSerialize instead of ToBytes
+            hash_input.extend_from_slice(&to_bytes![
+                com_c.0, com_c.1, com_c.2, com_x.0, com_x.1, com_x.2, com_x.3, com_d.0, com_d.1,
+                com_d.2, com_d.3, com_d.4, com_d.5, com_d3.0, com_d3.1, com_d3.2, com_d3.3
+            ]?);
+            let alpha: CM::Scalar =
+                u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                    .into();
+
+            if let Some(alpha_inv) = alpha.inverse() {
+                break 'challenge (alpha, alpha_inv);
+            }
+            counter_nonce += 1;
+        };
+        Ok((alpha, alpha_inv))
+    }
+
+    fn _compute_final_challenge(
+        q: (&CM::Output, &CM::Output, &CM::Output, &CM::Output),
+        p: (&CM::Output, &CM::Output),
+    ) -> Result<CM::Scalar, Error> {
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(&to_bytes![q.0, q.1, q.2, q.3, p.0, p.1]?);
+        let ch_c: CM::Scalar =
+            u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                .into();
+        Ok(ch_c)
+    }
+}
+
+impl<IP, CM, D> Clone for HPAProof<IP, CM, D>
+where
+    D: Digest,
+    IP: InnerProduct<LeftMessage = CM::Message, RightMessage = CM::Message, Output = CM::Message>,
+    CM: DoublyHomomorphicCommitment,
+{
+    fn clone(&self) -> Self {
+        HPAProof {
+            r_commitment_steps: self.r_commitment_steps.clone(),
+            r_d1_x: self.r_d1_x.clone(),
+            r_d2_x: self.r_d2_x.clone(),
+            e1: self.e1.clone(),
+            e2: self.e2.clone(),
+            q1: self.q1.clone(),
+            q2: self.q2.clone(),
+            q3: self.q3.clone(),
+            q4: self.q4.clone(),
+            p1: self.p1.clone(),
+            p2: self.p2.clone(),
+            r: self.r.clone(),
+            r1: self.r1.clone(),
+            r2: self.r2.clone(),
+            _hpa: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate ark_ed_on_bls12_381;
+    extern crate blake2;
+
+    use self::ark_ed_on_bls12_381::{EdwardsProjective as JubJub, Fr as JubJubFr};
+    use self::blake2::Blake2b;
+    use super::ark_dh_commitments::pedersen::PedersenCommitment;
+    use super::ark_inner_products::ScalarInnerProduct;
+    use super::ark_std::rand::{rngs::StdRng, SeedableRng};
+    use super::*;
+
+    type JubJubInnerProduct = ScalarInnerProduct<JubJubFr>;
+    type JubJubHPA = HPA<JubJubInnerProduct, PedersenCommitment<JubJub>, Blake2b>;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn jubjub_hadamard_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut v1 = Vec::new();
+        let mut v2 = Vec::new();
+        for _ in 0..TEST_SIZE {
+            v1.push(JubJubFr::rand(&mut rng));
+            v2.push(JubJubFr::rand(&mut rng));
+        }
+        let (gamma1, gamma2, g, h) = JubJubHPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (c, d1, d2, x, d3, gm, _gm_vec, r_c, r_x, r_d1, r_d2, r_d3, w_vec) =
+            JubJubHPA::init_commit(&v1, &v2, &gamma1, &gamma2, &g, &h, &mut rng).unwrap();
+
+        let proof = JubJubHPA::prove(
+            (&v1, &v2, &w_vec),
+            (&gamma1, &gamma2),
+            &g,
+            &h,
+            (&r_c, &r_x, &r_d1, &r_d2, &r_d3),
+            &gm,
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(JubJubHPA::verify(
+            (&gamma1, &gamma2),
+            &g,
+            &h,
+            (&c, &x, &d1, &d2, &d3),
+            &proof,
+            &gm
+        )
+        .unwrap());
+        let wrong_c = c + g;
+        assert!(!JubJubHPA::verify(
+            (&gamma1, &gamma2),
+            &g,
+            &h,
+            (&wrong_c, &x, &d1, &d2, &d3),
+            &proof,
+            &gm
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn jubjub_wrong_hadamard_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut v1 = Vec::new();
+        let mut v2 = Vec::new();
+        for _ in 0..TEST_SIZE {
+            v1.push(JubJubFr::rand(&mut rng));
+            v2.push(JubJubFr::rand(&mut rng));
+        }
+        let (gamma1, gamma2, g, h) = JubJubHPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (_, d1, d2, x, d3, gm, _gm_vec, r_c, r_x, r_d1, r_d2, r_d3, w_vec) =
+            JubJubHPA::init_commit(&v1, &v2, &gamma1, &gamma2, &g, &h, &mut rng).unwrap();
+
+        // c opens honestly under r_c, but to a value other than <v1, v2>
+        let wrong_product = JubJubInnerProduct::inner_product(&v1, &v2).unwrap() + JubJubFr::one();
+        let wrong_c = PedersenCommitment::<JubJub>::commit(&[g, h], &[wrong_product, r_c]).unwrap();
+
+        let proof = JubJubHPA::prove(
+            (&v1, &v2, &w_vec),
+            (&gamma1, &gamma2),
+            &g,
+            &h,
+            (&r_c, &r_x, &r_d1, &r_d2, &r_d3),
+            &gm,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(!JubJubHPA::verify(
+            (&gamma1, &gamma2),
+            &g,
+            &h,
+            (&wrong_c, &x, &d1, &d2, &d3),
+            &proof,
+            &gm
+        )
+        .unwrap());
+    }
+}
@@ -1,11 +1,22 @@
 #![deny(warnings, unused, future_incompatible, nonstandard_style)]
+extern crate ark_ff;
+use ark_ff::Field;
+extern crate ark_std;
+use ark_std::cfg_iter;
 use std::{
     error::Error as ErrorTrait,
     fmt::{Display, Formatter, Result as FmtResult},
-    ops::MulAssign,
+    ops::{Add, MulAssign},
 };
 
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub mod hpa_bp;
+pub mod hpa_bp_with_zk;
+pub mod range_proof;
 
 pub type Error = Box<dyn ErrorTrait>;
 
@@ -16,6 +27,42 @@ pub(crate) fn mul_helper<T: MulAssign<F> + Clone, F: Clone>(t: &T, f: &F) -> T {
     clone
 }
 
+// s_i = prod_j alpha_j over the rounds j in which index i falls in the left half,
+// so that the folded key is <s, gamma>
+pub(crate) fn challenge_products<F: Field>(alphas: &[F]) -> Vec<F> {
+    let mut s = vec![F::one()];
+    for alpha in alphas.iter().rev() {
+        let mut left = s.iter().map(|s_i| *s_i * alpha).collect::<Vec<F>>();
+        left.extend_from_slice(&s);
+        s = left;
+    }
+    s
+}
+
+// {1, s, s^2, ..., s^{n-1}}
+pub(crate) fn powers<F: Field>(s: &F, n: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(n);
+    let mut power = F::one();
+    for _ in 0..n {
+        powers.push(power);
+        power *= s;
+    }
+    powers
+}
+
+// alpha * left + right
+pub(crate) fn fold<T, F>(left: &[T], right: &[T], alpha: &F) -> Vec<T>
+where
+    T: MulAssign<F> + Add<T, Output = T> + Clone + Send + Sync,
+    F: Clone + Send + Sync,
+{
+    cfg_iter!(left)
+        .map(|a| mul_helper(a, alpha))
+        .zip(right)
+        .map(|(a_1, a_2)| a_1 + a_2.clone())
+        .collect::<Vec<T>>()
+}
+
 //TODO: helper function for mul because relying on MulAssign
 // pub(crate) fn add_helper<T: AddAssign<F> + Clone, F: Clone>(t: &T, f: &F) -> T {
 //     let mut clone = t.clone();
@@ -46,3 +93,29 @@ impl Display for InnerProductArgumentError {
         write!(f, "{}", msg)
     }
 }
+
+#[derive(Debug)]
+pub enum RangeProofError {
+    BitSizeInvalid(usize),
+    ValueOutOfRange(u64, usize),
+}
+
+impl ErrorTrait for RangeProofError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for RangeProofError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            RangeProofError::BitSizeInvalid(n_bits) => {
+                format!("bit size not in {{8, 16, 32, 64}}: {}", n_bits)
+            }
+            RangeProofError::ValueOutOfRange(value, n_bits) => {
+                format!("value {} not in [0, 2^{})", value, n_bits)
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
@@ -0,0 +1,902 @@
+extern crate ark_ec;
+use self::ark_ec::{msm::VariableBaseMSM, ProjectiveCurve};
+extern crate ark_ff;
+use self::ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+extern crate ark_serialize;
+use self::ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
+};
+extern crate ark_std;
+use self::ark_std::rand::Rng;
+use self::ark_std::{end_timer, start_timer};
+extern crate digest;
+use self::digest::Digest;
+use std::{convert::TryInto, marker::PhantomData};
+
+use crate::{
+    challenge_products, fold, mul_helper, powers, Error, InnerProductArgumentError, RangeProofError,
+};
+extern crate ark_dh_commitments;
+use self::ark_dh_commitments::{pedersen::PedersenCommitment, DoublyHomomorphicCommitment};
+extern crate ark_inner_products;
+use self::ark_inner_products::{InnerProduct, ScalarInnerProduct};
+use self::ark_std::cfg_iter;
+
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "parallel")]
+use self::rayon::prelude::*;
+
+// Bulletproofs range proof for a Pedersen commitment V = g * v + h * gamma, v in [0, 2^n_bits).
+// The bit vectors a_L and a_R = a_L - 1 satisfy a_L o a_R = 0; after the usual y, z, x challenges the
+// statement becomes t_hat = <l, r> for l committed under gamma1 and r under gamma2 * y^{-i}. That is
+// proven by an inner product reduction of its own, folding with the same gamma' = alpha * gamma_l + gamma_r
+// convention and helpers as hpa_bp.
+pub struct RangeArgument<G, D> {
+    _group: PhantomData<G>,
+    _digest: PhantomData<D>,
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeProofKey<G: ProjectiveCurve> {
+    pub g: G,
+    pub h: G,
+    pub gamma1: Vec<G>,
+    pub gamma2: Vec<G>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeProof<G: ProjectiveCurve, D: Digest> {
+    pub(crate) a1: G,
+    pub(crate) a2: G,
+    pub(crate) s1: G,
+    pub(crate) s2: G,
+    pub(crate) t1: G,
+    pub(crate) t2: G,
+    pub(crate) tau_x: G::ScalarField,
+    pub(crate) mu1: G::ScalarField,
+    pub(crate) mu2: G::ScalarField,
+    pub(crate) t_hat: G::ScalarField,
+    // ((c_l, c_x), (d1_l, d1_x), (d2_l, d2_x)); right halves follow from the running commitments
+    pub(crate) r_commitment_steps: Vec<((G::ScalarField, G::ScalarField), (G, G), (G, G))>,
+    pub(crate) e1: G::ScalarField,
+    pub(crate) e2: G::ScalarField,
+
+    _range: PhantomData<D>,
+}
+
+impl<G, D> RangeArgument<G, D>
+where
+    G: ProjectiveCurve,
+    D: Digest,
+{
+    pub fn setup<R: Rng>(rng: &mut R, max_bits: usize) -> Result<RangeProofKey<G>, Error> {
+        let gh = PedersenCommitment::<G>::setup(rng, 2)?;
+        let gamma1 = PedersenCommitment::<G>::setup(rng, max_bits)?;
+        let gamma2 = PedersenCommitment::<G>::setup(rng, max_bits)?;
+        Ok(RangeProofKey {
+            g: gh[0],
+            h: gh[1],
+            gamma1,
+            gamma2,
+        })
+    }
+
+    // Returns (V, gamma)
+    pub fn commit<R: Rng>(
+        pk: &RangeProofKey<G>,
+        value: u64,
+        rng: &mut R,
+    ) -> Result<(G, G::ScalarField), Error> {
+        let gamma = G::ScalarField::rand(rng);
+        let com =
+            PedersenCommitment::<G>::commit(&[pk.g, pk.h], &[G::ScalarField::from(value), gamma])?;
+        Ok((com, gamma))
+    }
+
+    pub fn prove<R: Rng>(
+        pk: &RangeProofKey<G>,
+        n_bits: usize,
+        com: &G,
+        witness: (u64, &G::ScalarField), // witness ( v, gamma )
+        rng: &mut R,
+    ) -> Result<RangeProof<G, D>, Error> {
+        Self::prove_aggregated(pk, n_bits, &[*com], &[(witness.0, *witness.1)], rng)
+    }
+
+    pub fn verify(
+        pk: &RangeProofKey<G>,
+        n_bits: usize,
+        com: &G,
+        proof: &RangeProof<G, D>,
+    ) -> Result<bool, Error> {
+        Self::verify_aggregated(pk, n_bits, &[*com], proof)
+    }
+
+    // Proves v_j in [0, 2^n_bits) for every V_j = g * v_j + h * gamma_j with a single proof of
+    // size O(log(n_bits * m)); the number of values m is padded to a power of two with V = 0
+    pub fn prove_aggregated<R: Rng>(
+        pk: &RangeProofKey<G>,
+        n_bits: usize,
+        coms: &[G],
+        witnesses: &[(u64, G::ScalarField)], // witnesses ( v_j, gamma_j )
+        rng: &mut R,
+    ) -> Result<RangeProof<G, D>, Error> {
+        if coms.len() != witnesses.len() {
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                coms.len(),
+                witnesses.len(),
+            )));
+        }
+        let m = Self::_check_bit_size(pk, n_bits, coms.len())?;
+        for (value, _) in witnesses.iter() {
+            if n_bits < 64 && value >> n_bits != 0 {
+                return Err(Box::new(RangeProofError::ValueOutOfRange(*value, n_bits)));
+            }
+        }
+        let one = G::ScalarField::one();
+        let zero = G::ScalarField::zero();
+        let nm = n_bits * m;
+
+        let gamma1 = &pk.gamma1[..nm];
+        let gamma2 = &pk.gamma2[..nm];
+        let coms = Self::_pad_commitments(coms, m);
+
+        let bits = start_timer!(|| "Commit to bits");
+        let a_l = (0..nm)
+            .map(|i| match witnesses.get(i / n_bits) {
+                Some((value, _)) if (value >> (i % n_bits)) & 1 == 1 => one,
+                _ => zero,
+            })
+            .collect::<Vec<G::ScalarField>>();
+        let a_r = a_l
+            .iter()
+            .map(|a| *a - one)
+            .collect::<Vec<G::ScalarField>>();
+        let s_l = (0..nm)
+            .map(|_| G::ScalarField::rand(rng))
+            .collect::<Vec<G::ScalarField>>();
+        let s_r = (0..nm)
+            .map(|_| G::ScalarField::rand(rng))
+            .collect::<Vec<G::ScalarField>>();
+        let alpha1 = G::ScalarField::rand(rng);
+        let alpha2 = G::ScalarField::rand(rng);
+        let rho1 = G::ScalarField::rand(rng);
+        let rho2 = G::ScalarField::rand(rng);
+
+        let a1 = PedersenCommitment::<G>::commit(gamma1, &a_l)? + mul_helper(&pk.h, &alpha1);
+        let a2 = PedersenCommitment::<G>::commit(gamma2, &a_r)? + mul_helper(&pk.h, &alpha2);
+        let s1 = PedersenCommitment::<G>::commit(gamma1, &s_l)? + mul_helper(&pk.h, &rho1);
+        let s2 = PedersenCommitment::<G>::commit(gamma2, &s_r)? + mul_helper(&pk.h, &rho2);
+        end_timer!(bits);
+
+        let (y, z) = Self::_compute_yz(n_bits, &coms, (&a1, &a2, &s1, &s2))?;
+        let y_vec = powers(&y, nm);
+        let two_vec = powers(&(one + one), n_bits);
+        let z_vec = powers(&z, m + 3); // z^{2+j} weights the j-th value
+
+        // l(X) = l0 + l1 * X, r(X) = r0 + r1 * X
+        let poly = start_timer!(|| "Compute t(X)");
+        let l0 = cfg_iter!(a_l)
+            .map(|a| *a - z)
+            .collect::<Vec<G::ScalarField>>();
+        let l1 = s_l;
+        let r0 = cfg_iter!(a_r)
+            .zip(&y_vec)
+            .enumerate()
+            .map(|(i, (a, y_i))| *y_i * (*a + z) + z_vec[2 + i / n_bits] * two_vec[i % n_bits])
+            .collect::<Vec<G::ScalarField>>();
+        let r1 = cfg_iter!(s_r)
+            .zip(&y_vec)
+            .map(|(s, y_i)| *y_i * s)
+            .collect::<Vec<G::ScalarField>>();
+
+        let t1_scalar = ScalarInnerProduct::<G::ScalarField>::inner_product(&l0, &r1)?
+            + ScalarInnerProduct::<G::ScalarField>::inner_product(&l1, &r0)?;
+        let t2_scalar = ScalarInnerProduct::<G::ScalarField>::inner_product(&l1, &r1)?;
+        let tau1 = G::ScalarField::rand(rng);
+        let tau2 = G::ScalarField::rand(rng);
+        let t1 = PedersenCommitment::<G>::commit(&[pk.g, pk.h], &[t1_scalar, tau1])?;
+        let t2 = PedersenCommitment::<G>::commit(&[pk.g, pk.h], &[t2_scalar, tau2])?;
+        end_timer!(poly);
+
+        let x = Self::_compute_x(&z, (&t1, &t2))?;
+
+        let l = cfg_iter!(l0)
+            .zip(&l1)
+            .map(|(a, b)| *a + *b * x)
+            .collect::<Vec<G::ScalarField>>();
+        let r = cfg_iter!(r0)
+            .zip(&r1)
+            .map(|(a, b)| *a + *b * x)
+            .collect::<Vec<G::ScalarField>>();
+        let t_hat = ScalarInnerProduct::<G::ScalarField>::inner_product(&l, &r)?;
+        let tau_x = witnesses
+            .iter()
+            .enumerate()
+            .fold(tau2 * x * x + tau1 * x, |acc, (j, (_, gamma))| {
+                acc + z_vec[2 + j] * gamma
+            });
+        let mu1 = alpha1 + rho1 * x;
+        let mu2 = alpha2 + rho2 * x;
+
+        let seed = Self::_compute_seed(&x, (&tau_x, &mu1, &mu2, &t_hat))?;
+        let gamma2_prime = Self::_gamma2_prime(gamma2, &y)?;
+        let (r_commitment_steps, e1, e2) =
+            Self::_prove_reduction(l, r, gamma1.to_vec(), gamma2_prime, &seed)?;
+
+        Ok(RangeProof {
+            a1,
+            a2,
+            s1,
+            s2,
+            t1,
+            t2,
+            tau_x,
+            mu1,
+            mu2,
+            t_hat,
+            r_commitment_steps,
+            e1,
+            e2,
+            _range: PhantomData,
+        })
+    }
+
+    pub fn verify_aggregated(
+        pk: &RangeProofKey<G>,
+        n_bits: usize,
+        coms: &[G],
+        proof: &RangeProof<G, D>,
+    ) -> Result<bool, Error> {
+        let m = Self::_check_bit_size(pk, n_bits, coms.len())?;
+        let nm = n_bits * m;
+        if proof.r_commitment_steps.len() != nm.trailing_zeros() as usize {
+            return Ok(false);
+        }
+        let one = G::ScalarField::one();
+        let zero = G::ScalarField::zero();
+        let gamma1 = &pk.gamma1[..nm];
+        let gamma2 = &pk.gamma2[..nm];
+        let coms = Self::_pad_commitments(coms, m);
+
+        let (y, z, x, seed) = Self::_compute_challenges(n_bits, &coms, proof)?;
+        let (delta, d2_scalars) = Self::_compute_statement_scalars(n_bits, m, &y, &z);
+        let z_vec = powers(&z, m + 3);
+
+        // t_hat * g + tau_x * h == sum_j z^{2+j} * V_j + delta * g + x * T1 + x^2 * T2
+        let t_left = PedersenCommitment::<G>::commit(&[pk.g, pk.h], &[proof.t_hat, proof.tau_x])?;
+        let t_right = PedersenCommitment::<G>::commit(&coms, &z_vec[2..2 + m])?
+            + PedersenCommitment::<G>::commit(&[pk.g, proof.t1, proof.t2], &[delta, x, x * x])?;
+        if t_left != t_right {
+            return Ok(false);
+        }
+
+        // d1 = A1 + x * S1 - z * <gamma1, 1> - mu1 * h = <gamma1, l>
+        // d2 = A2 + x * S2 + <gamma2, z + z^{2+j} * 2^i * y^{-i}> - mu2 * h = <gamma2 * y^{-i}, r>
+        let d1 = PedersenCommitment::<G>::commit(gamma1, &vec![zero - z; nm])?
+            + PedersenCommitment::<G>::commit(
+                &[proof.a1, proof.s1, pk.h],
+                &[one, x, zero - proof.mu1],
+            )?;
+        let d2 = PedersenCommitment::<G>::commit(gamma2, &d2_scalars)?
+            + PedersenCommitment::<G>::commit(
+                &[proof.a2, proof.s2, pk.h],
+                &[one, x, zero - proof.mu2],
+            )?;
+
+        let gamma2_prime = Self::_gamma2_prime(gamma2, &y)?;
+        Self::_verify_reduction(
+            (gamma1.to_vec(), gamma2_prime),
+            (&proof.t_hat, &d1, &d2),
+            (&proof.r_commitment_steps, &proof.e1, &proof.e2),
+            &seed,
+        )
+    }
+
+    // Verifies many (aggregated) range proofs at once: every group equation of every proof is
+    // weighted by a random scalar and the sum is checked with a single multi-scalar multiplication
+    pub fn batch_verify<R: Rng>(
+        pk: &RangeProofKey<G>,
+        n_bits: usize,
+        proofs: &[(&[G], &RangeProof<G, D>)], // proofs ( coms, proof )
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let one = G::ScalarField::one();
+        let zero = G::ScalarField::zero();
+
+        let mut g_scalar = zero;
+        let mut h_scalar = zero;
+        let mut gamma1_scalars: Vec<G::ScalarField> = Vec::new();
+        let mut gamma2_scalars: Vec<G::ScalarField> = Vec::new();
+        let mut bases = Vec::new();
+        let mut scalars = Vec::new();
+
+        for (coms, proof) in proofs.iter() {
+            let m = Self::_check_bit_size(pk, n_bits, coms.len())?;
+            let nm = n_bits * m;
+            if proof.r_commitment_steps.len() != nm.trailing_zeros() as usize {
+                return Ok(false);
+            }
+            let coms = Self::_pad_commitments(coms, m);
+
+            let (y, z, x, seed) = Self::_compute_challenges(n_bits, &coms, proof)?;
+            let (delta, d2_scalars) = Self::_compute_statement_scalars(n_bits, m, &y, &z);
+            let z_vec = powers(&z, m + 3);
+            let y_inv_vec = powers(&y.inverse().unwrap(), nm);
+
+            // Scalar part of the reduction needs no group operations
+            let alphas = Self::_compute_reduction_challenges(&proof.r_commitment_steps, &seed)?;
+            let c_prime = proof
+                .r_commitment_steps
+                .iter()
+                .zip(&alphas)
+                .fold(proof.t_hat, |c, (((c_l, c_x), _, _), alpha)| {
+                    c + (*alpha * alpha - one) * c_l + *alpha * c_x
+                });
+            if c_prime != proof.e1 * proof.e2 {
+                return Ok(false);
+            }
+            let s = challenge_products(&alphas);
+
+            let w_t = G::ScalarField::rand(rng);
+            let w_1 = G::ScalarField::rand(rng);
+            let w_2 = G::ScalarField::rand(rng);
+
+            if gamma1_scalars.len() < nm {
+                gamma1_scalars.resize(nm, zero);
+                gamma2_scalars.resize(nm, zero);
+            }
+
+            // w_t * (t_hat * g + tau_x * h - sum_j z^{2+j} * V_j - delta * g - x * T1 - x^2 * T2)
+            g_scalar += w_t * (proof.t_hat - delta);
+            h_scalar += w_t * proof.tau_x - w_1 * proof.mu1 - w_2 * proof.mu2;
+            for (com, z_j) in coms.iter().zip(&z_vec[2..2 + m]) {
+                bases.push(*com);
+                scalars.push(zero - w_t * z_j);
+            }
+            bases.push(proof.t1);
+            scalars.push(zero - w_t * x);
+            bases.push(proof.t2);
+            scalars.push(zero - w_t * x * x);
+
+            // w_1 * (d1' - <s, gamma1> * e1), w_2 * (d2' - <s * y^{-i}, gamma2> * e2)
+            for i in 0..nm {
+                gamma1_scalars[i] += w_1 * (zero - z - proof.e1 * s[i]);
+                gamma2_scalars[i] += w_2 * (d2_scalars[i] - proof.e2 * s[i] * y_inv_vec[i]);
+            }
+            bases.extend_from_slice(&[proof.a1, proof.s1, proof.a2, proof.s2]);
+            scalars.extend_from_slice(&[w_1, w_1 * x, w_2, w_2 * x]);
+            for ((_, (d1_l, d1_x), (d2_l, d2_x)), alpha) in
+                proof.r_commitment_steps.iter().zip(&alphas)
+            {
+                let alpha_sqr_minus_one = *alpha * alpha - one;
+                bases.extend_from_slice(&[*d1_l, *d1_x, *d2_l, *d2_x]);
+                scalars.extend_from_slice(&[
+                    w_1 * alpha_sqr_minus_one,
+                    w_1 * alpha,
+                    w_2 * alpha_sqr_minus_one,
+                    w_2 * alpha,
+                ]);
+            }
+        }
+
+        let len = gamma1_scalars.len();
+        bases.extend_from_slice(&[pk.g, pk.h]);
+        scalars.extend_from_slice(&[g_scalar, h_scalar]);
+        bases.extend_from_slice(&pk.gamma1[..len]);
+        scalars.extend_from_slice(&gamma1_scalars);
+        bases.extend_from_slice(&pk.gamma2[..len]);
+        scalars.extend_from_slice(&gamma2_scalars);
+
+        let msm = start_timer!(|| format!("Batch MSM of size {}", bases.len()));
+        let bases = G::batch_normalization_into_affine(&bases);
+        let scalars = cfg_iter!(scalars)
+            .map(|s| s.into_repr())
+            .collect::<Vec<_>>();
+        let result = VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero();
+        end_timer!(msm);
+
+        Ok(result)
+    }
+
+    // Returns the padded number of values m
+    fn _check_bit_size(pk: &RangeProofKey<G>, n_bits: usize, m: usize) -> Result<usize, Error> {
+        match n_bits {
+            8 | 16 | 32 | 64 => {}
+            _ => return Err(Box::new(RangeProofError::BitSizeInvalid(n_bits))),
+        }
+        if m == 0 {
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                0, 0,
+            )));
+        }
+        let m = m.next_power_of_two();
+        if pk.gamma1.len() < n_bits * m || pk.gamma2.len() < n_bits * m {
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                pk.gamma1.len(),
+                n_bits * m,
+            )));
+        }
+        Ok(m)
+    }
+
+    fn _pad_commitments(coms: &[G], m: usize) -> Vec<G> {
+        let mut coms = coms.to_vec();
+        coms.resize(m, G::zero());
+        coms
+    }
+
+    // Returns (delta(y, z), z + z^{2+j} * 2^i * y^{-i}) for the verifier
+    fn _compute_statement_scalars(
+        n_bits: usize,
+        m: usize,
+        y: &G::ScalarField,
+        z: &G::ScalarField,
+    ) -> (G::ScalarField, Vec<G::ScalarField>) {
+        let one = G::ScalarField::one();
+        let zero = G::ScalarField::zero();
+        let nm = n_bits * m;
+        let y_vec = powers(y, nm);
+        let y_inv_vec = powers(&y.inverse().unwrap(), nm);
+        let two_vec = powers(&(one + one), n_bits);
+        let z_vec = powers(z, m + 3);
+
+        let sum_y = y_vec.iter().fold(zero, |acc, y_i| acc + y_i);
+        let sum_two = two_vec.iter().fold(zero, |acc, two_i| acc + two_i);
+        let sum_z = z_vec[3..3 + m].iter().fold(zero, |acc, z_j| acc + z_j);
+        let delta = (*z - *z * z) * sum_y - sum_z * sum_two;
+
+        let d2_scalars = cfg_iter!(y_inv_vec)
+            .enumerate()
+            .map(|(i, y_inv_i)| *z + z_vec[2 + i / n_bits] * two_vec[i % n_bits] * y_inv_i)
+            .collect::<Vec<G::ScalarField>>();
+        (delta, d2_scalars)
+    }
+
+    // gamma2_i * y^{-i}
+    pub(crate) fn _gamma2_prime(gamma2: &[G], y: &G::ScalarField) -> Result<Vec<G>, Error> {
+        let y_inv_vec = powers(&y.inverse().unwrap(), gamma2.len());
+        Ok(cfg_iter!(gamma2)
+            .zip(&y_inv_vec)
+            .map(|(g, y_inv_i)| mul_helper(g, y_inv_i))
+            .collect::<Vec<G>>())
+    }
+
+    // Proves c = <l, r> for d1 = <gamma1, l> and d2 = <gamma2, r>, folding
+    // l' = alpha * l_l + l_r and gamma' = alpha * gamma_l + gamma_r
+    pub(crate) fn _prove_reduction(
+        l: Vec<G::ScalarField>,
+        r: Vec<G::ScalarField>,
+        gamma1: Vec<G>,
+        gamma2: Vec<G>,
+        seed: &G::ScalarField,
+    ) -> Result<
+        (
+            Vec<((G::ScalarField, G::ScalarField), (G, G), (G, G))>,
+            G::ScalarField,
+            G::ScalarField,
+        ),
+        Error,
+    > {
+        let (mut v1, mut v2) = (l, r);
+        let (mut gamma1, mut gamma2) = (gamma1, gamma2);
+        let mut r_commitment_steps = Vec::new();
+        let mut transcript = *seed;
+
+        while v1.len() > 1 {
+            let recurse = start_timer!(|| format!("Recurse round size {}", v1.len()));
+            let split = v1.len() / 2;
+            let (v1_l, v1_r) = v1.split_at(split);
+            let (v2_l, v2_r) = v2.split_at(split);
+            let (gamma1_l, gamma1_r) = gamma1.split_at(split);
+            let (gamma2_l, gamma2_r) = gamma2.split_at(split);
+
+            let c_l = ScalarInnerProduct::<G::ScalarField>::inner_product(v1_l, v2_l)?;
+            let c_x = ScalarInnerProduct::<G::ScalarField>::inner_product(v1_l, v2_r)?
+                + ScalarInnerProduct::<G::ScalarField>::inner_product(v1_r, v2_l)?;
+            let d1_l = PedersenCommitment::<G>::commit(gamma1_l, v1_l)?;
+            let d1_x = PedersenCommitment::<G>::commit(gamma1_l, v1_r)?
+                + PedersenCommitment::<G>::commit(gamma1_r, v1_l)?;
+            let d2_l = PedersenCommitment::<G>::commit(gamma2_l, v2_l)?;
+            let d2_x = PedersenCommitment::<G>::commit(gamma2_l, v2_r)?
+                + PedersenCommitment::<G>::commit(gamma2_r, v2_l)?;
+
+            let alpha = Self::_compute_round_challenge(
+                &transcript,
+                (&c_l, &c_x),
+                (&d1_l, &d1_x),
+                (&d2_l, &d2_x),
+            )?;
+
+            let next_v1 = fold(v1_l, v1_r, &alpha);
+            let next_v2 = fold(v2_l, v2_r, &alpha);
+            let next_gamma1 = fold(gamma1_l, gamma1_r, &alpha);
+            let next_gamma2 = fold(gamma2_l, gamma2_r, &alpha);
+            v1 = next_v1;
+            v2 = next_v2;
+            gamma1 = next_gamma1;
+            gamma2 = next_gamma2;
+
+            r_commitment_steps.push(((c_l, c_x), (d1_l, d1_x), (d2_l, d2_x)));
+            transcript = alpha;
+            end_timer!(recurse);
+        }
+
+        Ok((r_commitment_steps, v1[0], v2[0]))
+    }
+
+    pub(crate) fn _verify_reduction(
+        ck_message: (Vec<G>, Vec<G>),
+        com: (&G::ScalarField, &G, &G), // com ( c, d1, d2 )
+        proof: (
+            &Vec<((G::ScalarField, G::ScalarField), (G, G), (G, G))>,
+            &G::ScalarField,
+            &G::ScalarField,
+        ),
+        seed: &G::ScalarField,
+    ) -> Result<bool, Error> {
+        let (mut gamma1, mut gamma2) = ck_message;
+        let (mut c_prime, mut d1_prime, mut d2_prime) = (*com.0, *com.1, *com.2);
+        let (r_commitment_steps, e1, e2) = proof;
+        if gamma1.len() != 1 << r_commitment_steps.len() || gamma1.len() != gamma2.len() {
+            return Ok(false);
+        }
+        let one = G::ScalarField::one();
+        let mut transcript = *seed;
+
+        for ((c_l, c_x), (d1_l, d1_x), (d2_l, d2_x)) in r_commitment_steps.iter() {
+            let alpha = Self::_compute_round_challenge(
+                &transcript,
+                (c_l, c_x),
+                (d1_l, d1_x),
+                (d2_l, d2_x),
+            )?;
+            let alpha_sqr_minus_one = alpha * alpha - one;
+
+            // Right halves are (total - left): alpha^2 * l + alpha * x + (total - l)
+            c_prime = c_prime + alpha_sqr_minus_one * c_l + alpha * c_x;
+            d1_prime = d1_prime + mul_helper(d1_l, &alpha_sqr_minus_one) + mul_helper(d1_x, &alpha);
+            d2_prime = d2_prime + mul_helper(d2_l, &alpha_sqr_minus_one) + mul_helper(d2_x, &alpha);
+
+            let split = gamma1.len() / 2;
+            let next_gamma1 = fold(&gamma1[..split], &gamma1[split..], &alpha);
+            let next_gamma2 = fold(&gamma2[..split], &gamma2[split..], &alpha);
+            gamma1 = next_gamma1;
+            gamma2 = next_gamma2;
+            transcript = alpha;
+        }
+
+        Ok(c_prime == *e1 * *e2
+            && d1_prime == mul_helper(&gamma1[0], e1)
+            && d2_prime == mul_helper(&gamma2[0], e2))
+    }
+
+    fn _compute_yz(
+        n_bits: usize,
+        coms: &[G],
+        com_bits: (&G, &G, &G, &G), // com_bits ( A1, A2, S1, S2 )
+    ) -> Result<(G::ScalarField, G::ScalarField), Error> {
+        let mut hash_input = Vec::new();
+        (n_bits as u64).serialize(&mut hash_input)?;
+        (coms.len() as u64).serialize(&mut hash_input)?;
+        for com in coms.iter() {
+            com.serialize(&mut hash_input)?;
+        }
+        com_bits.0.serialize(&mut hash_input)?;
+        com_bits.1.serialize(&mut hash_input)?;
+        com_bits.2.serialize(&mut hash_input)?;
+        com_bits.3.serialize(&mut hash_input)?;
+        let y = Self::_compute_challenge(&hash_input)?;
+
+        let mut hash_input = Vec::new();
+        y.serialize(&mut hash_input)?;
+        let z = Self::_compute_challenge(&hash_input)?;
+        Ok((y, z))
+    }
+
+    fn _compute_x(z: &G::ScalarField, com_t: (&G, &G)) -> Result<G::ScalarField, Error> {
+        let mut hash_input = Vec::new();
+        z.serialize(&mut hash_input)?;
+        com_t.0.serialize(&mut hash_input)?;
+        com_t.1.serialize(&mut hash_input)?;
+        Self::_compute_challenge(&hash_input)
+    }
+
+    // Returns (y, z, x, seed) for a proof over the padded commitments
+    fn _compute_challenges(
+        n_bits: usize,
+        coms: &[G],
+        proof: &RangeProof<G, D>,
+    ) -> Result<
+        (
+            G::ScalarField,
+            G::ScalarField,
+            G::ScalarField,
+            G::ScalarField,
+        ),
+        Error,
+    > {
+        let (y, z) = Self::_compute_yz(n_bits, coms, (&proof.a1, &proof.a2, &proof.s1, &proof.s2))?;
+        let x = Self::_compute_x(&z, (&proof.t1, &proof.t2))?;
+        let seed = Self::_compute_seed(&x, (&proof.tau_x, &proof.mu1, &proof.mu2, &proof.t_hat))?;
+        Ok((y, z, x, seed))
+    }
+
+    fn _compute_reduction_challenges(
+        r_commitment_steps: &[((G::ScalarField, G::ScalarField), (G, G), (G, G))],
+        seed: &G::ScalarField,
+    ) -> Result<Vec<G::ScalarField>, Error> {
+        let mut alphas = Vec::new();
+        let mut transcript = *seed;
+        for ((c_l, c_x), (d1_l, d1_x), (d2_l, d2_x)) in r_commitment_steps.iter() {
+            transcript = Self::_compute_round_challenge(
+                &transcript,
+                (c_l, c_x),
+                (d1_l, d1_x),
+                (d2_l, d2_x),
+            )?;
+            alphas.push(transcript);
+        }
+        Ok(alphas)
+    }
+
+    fn _compute_seed(
+        x: &G::ScalarField,
+        openings: (
+            &G::ScalarField,
+            &G::ScalarField,
+            &G::ScalarField,
+            &G::ScalarField,
+        ), // openings ( tau_x, mu1, mu2, t_hat )
+    ) -> Result<G::ScalarField, Error> {
+        let mut hash_input = Vec::new();
+        x.serialize(&mut hash_input)?;
+        openings.0.serialize(&mut hash_input)?;
+        openings.1.serialize(&mut hash_input)?;
+        openings.2.serialize(&mut hash_input)?;
+        openings.3.serialize(&mut hash_input)?;
+        Self::_compute_challenge(&hash_input)
+    }
+
+    fn _compute_round_challenge(
+        transcript: &G::ScalarField,
+        com_c: (&G::ScalarField, &G::ScalarField),
+        com_d1: (&G, &G),
+        com_d2: (&G, &G),
+    ) -> Result<G::ScalarField, Error> {
+        let mut hash_input = Vec::new();
+        transcript.serialize(&mut hash_input)?;
+        com_c.0.serialize(&mut hash_input)?;
+        com_c.1.serialize(&mut hash_input)?;
+        com_d1.0.serialize(&mut hash_input)?;
+        com_d1.1.serialize(&mut hash_input)?;
+        com_d2.0.serialize(&mut hash_input)?;
+        com_d2.1.serialize(&mut hash_input)?;
+        Self::_compute_challenge(&hash_input)
+    }
+
+    // Fiat-Shamir challenge over a serialized transcript
+    pub(crate) fn _compute_challenge(transcript: &[u8]) -> Result<G::ScalarField, Error> {
+        let mut counter_nonce: usize = 0;
+        let challenge = 'challenge: loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            hash_input.extend_from_slice(transcript);
+            let c: G::ScalarField =
+                u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                    .into();
+            if !c.is_zero() {
+                break 'challenge c;
+            }
+            counter_nonce += 1;
+        };
+        Ok(challenge)
+    }
+}
+
+impl<G, D> Clone for RangeProof<G, D>
+where
+    G: ProjectiveCurve,
+    D: Digest,
+{
+    fn clone(&self) -> Self {
+        RangeProof {
+            a1: self.a1,
+            a2: self.a2,
+            s1: self.s1,
+            s2: self.s2,
+            t1: self.t1,
+            t2: self.t2,
+            tau_x: self.tau_x,
+            mu1: self.mu1,
+            mu2: self.mu2,
+            t_hat: self.t_hat,
+            r_commitment_steps: self.r_commitment_steps.clone(),
+            e1: self.e1,
+            e2: self.e2,
+            _range: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate ark_ed_on_bls12_381;
+    extern crate blake2;
+
+    use self::ark_ed_on_bls12_381::EdwardsProjective as JubJub;
+    use self::blake2::Blake2b;
+    use super::ark_std::rand::{rngs::StdRng, SeedableRng};
+    use super::*;
+
+    type JubJubRange = RangeArgument<JubJub, Blake2b>;
+    const MAX_BITS: usize = 64;
+
+    #[test]
+    fn jubjub_range_proof_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, MAX_BITS).unwrap();
+
+        for n_bits in [8, 16, 32, 64].iter() {
+            let n_bits = *n_bits;
+            let max = if n_bits == 64 {
+                u64::MAX
+            } else {
+                (1u64 << n_bits) - 1
+            };
+            for value in [0u64, 1, max / 3, max].iter() {
+                let (com, gamma) = JubJubRange::commit(&pk, *value, &mut rng).unwrap();
+                let proof =
+                    JubJubRange::prove(&pk, n_bits, &com, (*value, &gamma), &mut rng).unwrap();
+                assert!(JubJubRange::verify(&pk, n_bits, &com, &proof).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn jubjub_range_proof_out_of_range_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, MAX_BITS).unwrap();
+
+        let (com, gamma) = JubJubRange::commit(&pk, 1 << 8, &mut rng).unwrap();
+        assert!(JubJubRange::prove(&pk, 8, &com, (1 << 8, &gamma), &mut rng).is_err());
+
+        // An honest 16-bit proof does not pass as an 8-bit proof
+        let proof = JubJubRange::prove(&pk, 16, &com, (1 << 8, &gamma), &mut rng).unwrap();
+        assert!(JubJubRange::verify(&pk, 16, &com, &proof).unwrap());
+        assert!(!JubJubRange::verify(&pk, 8, &com, &proof).unwrap());
+
+        // Nor does it verify for a commitment to another value
+        let (other_com, _) = JubJubRange::commit(&pk, 1 << 8, &mut rng).unwrap();
+        assert!(!JubJubRange::verify(&pk, 16, &other_com, &proof).unwrap());
+    }
+
+    #[test]
+    fn jubjub_range_proof_tampered_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, MAX_BITS).unwrap();
+        let (com, gamma) = JubJubRange::commit(&pk, 12345, &mut rng).unwrap();
+        let proof = JubJubRange::prove(&pk, 32, &com, (12345, &gamma), &mut rng).unwrap();
+        assert!(JubJubRange::verify(&pk, 32, &com, &proof).unwrap());
+
+        let one = <JubJub as ProjectiveCurve>::ScalarField::one();
+        let mut tampered = proof.clone();
+        tampered.t_hat += one;
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.tau_x += one;
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.a1 += pk.g;
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        (tampered.r_commitment_steps[0].1).0 += pk.g;
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+
+        let mut tampered = proof.clone();
+        tampered.e2 += one;
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+
+        let mut tampered = proof;
+        tampered.r_commitment_steps.pop();
+        assert!(!JubJubRange::verify(&pk, 32, &com, &tampered).unwrap());
+    }
+
+    #[test]
+    fn jubjub_range_proof_serialization_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, MAX_BITS).unwrap();
+        let (com, gamma) = JubJubRange::commit(&pk, 42, &mut rng).unwrap();
+        let proof = JubJubRange::prove(&pk, 16, &com, (42, &gamma), &mut rng).unwrap();
+
+        let mut proof_bytes = Vec::new();
+        proof.serialize(&mut proof_bytes).unwrap();
+        assert_eq!(proof_bytes.len(), proof.serialized_size());
+        let mut pk_bytes = Vec::new();
+        pk.serialize(&mut pk_bytes).unwrap();
+
+        let proof = RangeProof::<JubJub, Blake2b>::deserialize(&proof_bytes[..]).unwrap();
+        let pk = RangeProofKey::<JubJub>::deserialize(&pk_bytes[..]).unwrap();
+        assert!(JubJubRange::verify(&pk, 16, &com, &proof).unwrap());
+
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes, proof_bytes);
+        assert!(
+            RangeProof::<JubJub, Blake2b>::deserialize(&proof_bytes[..proof_bytes.len() - 1])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn jubjub_aggregated_range_proof_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, 4 * 16).unwrap();
+
+        // m = 3 is padded with a zero commitment
+        let values = [7u64, 0, u16::MAX as u64];
+        let mut coms = Vec::new();
+        let mut witnesses = Vec::new();
+        for value in values.iter() {
+            let (com, gamma) = JubJubRange::commit(&pk, *value, &mut rng).unwrap();
+            coms.push(com);
+            witnesses.push((*value, gamma));
+        }
+        let proof = JubJubRange::prove_aggregated(&pk, 16, &coms, &witnesses, &mut rng).unwrap();
+        assert!(JubJubRange::verify_aggregated(&pk, 16, &coms, &proof).unwrap());
+        assert!(!JubJubRange::verify_aggregated(&pk, 16, &coms[..2], &proof).unwrap());
+
+        // One commitment replaced by a commitment to another value
+        let (bad_com, _) = JubJubRange::commit(&pk, 8, &mut rng).unwrap();
+        let mut bad_coms = coms.clone();
+        bad_coms[1] = bad_com;
+        assert!(!JubJubRange::verify_aggregated(&pk, 16, &bad_coms, &proof).unwrap());
+
+        // The padding commitment is not a free slot for a fourth value
+        let mut padded_coms = coms.clone();
+        padded_coms.push(bad_com);
+        assert!(!JubJubRange::verify_aggregated(&pk, 16, &padded_coms, &proof).unwrap());
+    }
+
+    #[test]
+    fn jubjub_batch_verify_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pk = JubJubRange::setup(&mut rng, 2 * 32).unwrap();
+
+        let mut statements = Vec::new();
+        for (j, m) in [1usize, 2, 1].iter().enumerate() {
+            let mut coms = Vec::new();
+            let mut witnesses = Vec::new();
+            for i in 0..*m {
+                let value = (1000 * j + i) as u64;
+                let (com, gamma) = JubJubRange::commit(&pk, value, &mut rng).unwrap();
+                coms.push(com);
+                witnesses.push((value, gamma));
+            }
+            let proof =
+                JubJubRange::prove_aggregated(&pk, 32, &coms, &witnesses, &mut rng).unwrap();
+            statements.push((coms, proof));
+        }
+        let batch = statements
+            .iter()
+            .map(|(coms, proof)| (&coms[..], proof))
+            .collect::<Vec<_>>();
+        assert!(JubJubRange::batch_verify(&pk, 32, &batch, &mut rng).unwrap());
+
+        // One bad proof fails the whole batch
+        let mut bad_proof = statements[1].1.clone();
+        bad_proof.mu2 += <JubJub as ProjectiveCurve>::ScalarField::one();
+        let mut bad_batch = batch.clone();
+        bad_batch[1] = (&statements[1].0[..], &bad_proof);
+        assert!(!JubJubRange::batch_verify(&pk, 32, &bad_batch, &mut rng).unwrap());
+
+        // As does a proof checked against another statement
+        let mut bad_batch = batch;
+        bad_batch[2] = (&statements[0].0[..], &statements[2].1);
+        assert!(!JubJubRange::batch_verify(&pk, 32, &bad_batch, &mut rng).unwrap());
+    }
+}
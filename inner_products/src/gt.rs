@@ -0,0 +1,313 @@
+// Exponentiation in the order r target group GT of Fqk.
+//
+// The p-power Frobenius acts on GT as exponentiation by lambda = p mod r. When lambda or -lambda
+// is a small integer mu (for BLS12 curves p = x mod r, with x the 64-bit curve parameter), an
+// exponent e < r is written in base mu, e = sum_i e_i mu^i, and
+//     g^e = prod_i psi^i(g)^(e_i)  with  psi(g) = g^mu,
+// where psi is one Frobenius map, followed by a conjugation (the inverse on GT) when
+// mu = -lambda. The digits have the bit length of mu, so the exponentiation takes a quarter of
+// the squarings for BLS12 (Galbraith-Scott).
+//
+// Exponentiations of several elements share their squarings (Straus): the products of all
+// subsets of the psi^i(g) of one element are precomputed, and each bit of the digits costs one
+// multiplication per element. Without a small Frobenius eigenvalue the exponents are used whole.
+//
+// The squarings are those of the cyclotomic subgroup (Granger-Scott for Fp12). Both shortcuts are
+// only used on elements for which they are checked to hold: the cyclotomic subgroup membership
+// by the Frobenius maps, and psi(g) = g^mu by one exponentiation by mu. Other elements, such as
+// forged proof elements outside of GT, are exponentiated with `pow`.
+
+use ark_ff::{
+    fields::{
+        fp6_2over3::{Fp6, Fp6Parameters},
+        Fp12, Fp12Parameters, Fp4, Fp4Parameters,
+    },
+    Field, PrimeField, Zero,
+};
+use ark_std::cfg_iter;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// Elements sharing a table of subset products
+const GROUP_SIZE: usize = 4;
+
+// Target group field Fqk, with a squaring specialized to its cyclotomic subgroup
+pub trait CyclotomicField: Field {
+    // Whether `cyclotomic_square_in_place` squares `self`
+    fn is_cyclotomic(&self) -> bool {
+        true
+    }
+
+    fn cyclotomic_square_in_place(&mut self) -> &mut Self {
+        self.square_in_place()
+    }
+}
+
+// The cyclotomic subgroup of Fp12 has order p^4 - p^2 + 1
+impl<P: Fp12Parameters> CyclotomicField for Fp12<P> {
+    fn is_cyclotomic(&self) -> bool {
+        let mut g2 = *self;
+        g2.frobenius_map(2);
+        let mut g4 = *self;
+        g4.frobenius_map(4);
+        !self.is_zero() && g4 * self == g2
+    }
+
+    // Granger-Scott squaring over Fp12 = Fp4^3
+    fn cyclotomic_square_in_place(&mut self) -> &mut Self {
+        Fp12::<P>::cyclotomic_square_in_place(self);
+        self
+    }
+}
+
+impl<P: Fp6Parameters> CyclotomicField for Fp6<P> {}
+
+impl<P: Fp4Parameters> CyclotomicField for Fp4<P> {}
+
+#[derive(Clone, Copy)]
+struct Endomorphism {
+    mu: u64,
+    conjugate: bool,
+}
+
+impl Endomorphism {
+    // psi = (g -> g^mu) on GT, if mu = p or -p mod r fits a machine word
+    fn new<F: Field, S: PrimeField>() -> Option<Self> {
+        if F::extension_degree() % 2 != 0 {
+            return None;
+        }
+        let p_bytes = F::characteristic()
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let lambda = S::from_le_bytes_mod_order(&p_bytes);
+        let endomorphism = |x: S, conjugate| {
+            let repr = x.into_repr();
+            let limbs = repr.as_ref();
+            if limbs[0] > 1 && limbs[1..].iter().all(|limb| *limb == 0) {
+                Some(Endomorphism {
+                    mu: limbs[0],
+                    conjugate,
+                })
+            } else {
+                None
+            }
+        };
+        endomorphism(lambda, false).or_else(|| endomorphism(-lambda, true))
+    }
+
+    fn apply<F: Field>(&self, g: &F) -> F {
+        let mut h = *g;
+        h.frobenius_map(1);
+        if self.conjugate {
+            h.frobenius_map(F::extension_degree() as usize / 2);
+        }
+        h
+    }
+
+    // psi(g) = g^mu, for g in the cyclotomic subgroup
+    fn is_eigenvector<F: CyclotomicField>(&self, g: &F) -> bool {
+        let mut g_mu = *g;
+        for i in (0..63 - self.mu.leading_zeros()).rev() {
+            g_mu.cyclotomic_square_in_place();
+            if (self.mu >> i) & 1 == 1 {
+                g_mu *= g;
+            }
+        }
+        self.apply(g) == g_mu
+    }
+}
+
+// Digits of the little endian integer `limbs` in base `mu`
+fn base_digits(limbs: &[u64], mu: u64) -> Vec<u64> {
+    let mut n = limbs.to_vec();
+    let mut digits = Vec::new();
+    while n.iter().any(|limb| *limb != 0) {
+        let mut rem = 0u128;
+        for limb in n.iter_mut().rev() {
+            let cur = (rem << 64) | *limb as u128;
+            *limb = (cur / mu as u128) as u64;
+            rem = cur % mu as u128;
+        }
+        digits.push(rem as u64);
+    }
+    digits
+}
+
+fn bit_length(limbs: &[u64]) -> usize {
+    limbs
+        .iter()
+        .rposition(|limb| *limb != 0)
+        .map_or(0, |i| 64 * (i + 1) - limbs[i].leading_zeros() as usize)
+}
+
+fn bit(limbs: &[u64], i: usize) -> bool {
+    i / 64 < limbs.len() && (limbs[i / 64] >> (i % 64)) & 1 == 1
+}
+
+// Products of all subsets of `elements`, indexed by bitmask
+fn subset_products<F: Field>(elements: &[F]) -> Vec<F> {
+    let mut table = vec![F::one(); 1 << elements.len()];
+    for index in 1..table.len() {
+        let low = index.trailing_zeros() as usize;
+        let rest = index & (index - 1);
+        table[index] = if rest == 0 {
+            elements[low]
+        } else {
+            table[rest] * elements[low]
+        };
+    }
+    table
+}
+
+// prod_i bases[i]^(scalars[i])
+pub(crate) fn multi_exp<F: CyclotomicField, S: PrimeField>(bases: &[F], scalars: &[S]) -> F {
+    let endomorphism = Endomorphism::new::<F, S>();
+    let is_fast = |g: &F| {
+        g.is_cyclotomic()
+            && match endomorphism {
+                Some(psi) => psi.is_eigenvector(g),
+                None => true,
+            }
+    };
+    let (fast, slow): (Vec<_>, Vec<_>) =
+        cfg_iter!(bases).zip(scalars).partition(|(g, _)| is_fast(g));
+
+    let terms = cfg_iter!(fast)
+        .flat_map(|&(g, e)| {
+            let limbs = e.into_repr().as_ref().to_vec();
+            let (elements, exponents) = match endomorphism {
+                Some(psi) => {
+                    let digits = base_digits(&limbs, psi.mu);
+                    let mut elements = Vec::with_capacity(digits.len());
+                    for _ in 0..digits.len() {
+                        elements.push(match elements.last() {
+                            Some(h) => psi.apply(h),
+                            None => *g,
+                        });
+                    }
+                    let exponents = digits.into_iter().map(|d| vec![d]).collect();
+                    (elements, exponents)
+                }
+                None => (vec![*g], vec![limbs]),
+            };
+            elements
+                .chunks(GROUP_SIZE)
+                .zip(exponents.chunks(GROUP_SIZE))
+                .map(|(elements, exponents)| (subset_products(elements), exponents.to_vec()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let num_bits = terms
+        .iter()
+        .flat_map(|(_, exponents)| exponents)
+        .map(|exponent| bit_length(exponent))
+        .max()
+        .unwrap_or(0);
+    let mut result = F::one();
+    for i in (0..num_bits).rev() {
+        result.cyclotomic_square_in_place();
+        for (table, exponents) in terms.iter() {
+            let index = exponents
+                .iter()
+                .enumerate()
+                .fold(0, |index, (j, exponent)| {
+                    index | (bit(exponent, i) as usize) << j
+                });
+            if index != 0 {
+                result *= &table[index];
+            }
+        }
+    }
+    slow.iter()
+        .fold(result, |result, (g, e)| result * g.pow(e.into_repr()))
+}
+
+// g^e
+pub(crate) fn exp<F: CyclotomicField, S: PrimeField>(g: &F, e: &S) -> F {
+    multi_exp(&[*g], &[*e])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::PairingEngine;
+    use ark_ff::One;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn target_group_exponentiation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        assert!(Endomorphism::new::<<Bls12_381 as PairingEngine>::Fqk, Fr>().is_some());
+
+        let bases = (0..5)
+            .map(|_| {
+                Bls12_381::pairing(
+                    <Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng),
+                    <Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut scalars = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        scalars[1] = Fr::from(0u64);
+        scalars[2] = -Fr::from(1u64);
+        for (g, e) in bases.iter().zip(&scalars) {
+            assert_eq!(multi_exp(&[*g], &[*e]), g.pow(e.into_repr()));
+        }
+
+        let expected = bases
+            .iter()
+            .zip(&scalars)
+            .map(|(g, e)| g.pow(e.into_repr()))
+            .product::<<Bls12_381 as PairingEngine>::Fqk>();
+        assert_eq!(multi_exp(&bases, &scalars), expected);
+        assert_eq!(
+            multi_exp::<<Bls12_381 as PairingEngine>::Fqk, Fr>(&[], &[]),
+            <Bls12_381 as PairingEngine>::Fqk::one()
+        );
+    }
+
+    #[test]
+    fn non_target_group_exponentiation_test() {
+        type Fqk = <Bls12_381 as PairingEngine>::Fqk;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let psi = Endomorphism::new::<Fqk, Fr>().unwrap();
+
+        let g = Bls12_381::pairing(
+            <Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng),
+            <Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng),
+        );
+        assert!(g.is_cyclotomic() && psi.is_eigenvector(&g));
+        assert_eq!(
+            *CyclotomicField::cyclotomic_square_in_place(&mut g.clone()),
+            g.square()
+        );
+
+        // Outside of the cyclotomic subgroup, and in it but outside of GT
+        let x = Fqk::rand(&mut rng);
+        assert!(!x.is_cyclotomic());
+        let mut u = x;
+        u.frobenius_map(6);
+        u *= x.inverse().unwrap();
+        let mut y = u;
+        y.frobenius_map(2);
+        y *= u;
+        assert!(y.is_cyclotomic() && !psi.is_eigenvector(&y));
+
+        let bases = vec![x, y, g, Fqk::zero()];
+        let scalars = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let expected = bases
+            .iter()
+            .zip(&scalars)
+            .map(|(g, e)| g.pow(e.into_repr()))
+            .product::<Fqk>();
+        assert_eq!(multi_exp(&bases, &scalars), expected);
+        for (g, e) in bases.iter().zip(&scalars) {
+            assert_eq!(exp(g, e), g.pow(e.into_repr()));
+        }
+    }
+}
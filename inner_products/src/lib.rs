@@ -1,6 +1,7 @@
 use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
-use ark_ff::{bytes::ToBytes, Field, PrimeField};
+use ark_ff::{bytes::ToBytes, Field, One, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError};
+use ark_std::rand::Rng;
 use ark_std::{cfg_into_iter, cfg_iter};
 use std::{
     error::Error as ErrorTrait,
@@ -13,6 +14,11 @@ use std::{
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+mod gt;
+mod torus;
+
+pub use gt::CyclotomicField;
+
 pub type Error = Box<dyn ErrorTrait>;
 
 #[derive(Debug)]
@@ -46,6 +52,82 @@ pub trait InnerProduct: Copy {
         left: &[Self::LeftMessage],
         right: &[Self::RightMessage],
     ) -> Result<Self::Output, Error>;
+
+    // Inner products of each of `lefts` with the same `right`, sharing the work on `right`
+    fn multi_inner_product(
+        lefts: &[&[Self::LeftMessage]],
+        right: &[Self::RightMessage],
+    ) -> Result<Vec<Self::Output>, Error> {
+        lefts
+            .iter()
+            .map(|left| Self::inner_product(left, right))
+            .collect()
+    }
+
+    // Inner products of the same `left` with each of `rights`, sharing the work on `left`
+    fn multi_inner_product_shared_left(
+        left: &[Self::LeftMessage],
+        rights: &[&[Self::RightMessage]],
+    ) -> Result<Vec<Self::Output>, Error> {
+        rights
+            .iter()
+            .map(|right| Self::inner_product(left, right))
+            .collect()
+    }
+}
+
+/// Argument that committed vectors `left` and `right` satisfy `<left, right> = T`, with `T`
+/// bound into the commitment.
+///
+/// Implemented by GIPA, TIPA, DORY and SIPP so that applications (e.g. Groth16 aggregation)
+/// can be written once and switch backend by changing a type parameter.
+pub trait InnerProductArgument {
+    type InnerProduct: InnerProduct;
+    type ProverKey;
+    type VerifierKey;
+    type Commitment;
+    type Proof;
+
+    /// Samples keys for vectors of length `size` (a power of two).
+    fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error>;
+
+    /// Commits to `left`, `right` and their inner product.
+    fn commit(
+        pk: &Self::ProverKey,
+        left: &[<Self::InnerProduct as InnerProduct>::LeftMessage],
+        right: &[<Self::InnerProduct as InnerProduct>::RightMessage],
+    ) -> Result<Self::Commitment, Error>;
+
+    fn prove(
+        pk: &Self::ProverKey,
+        left: &[<Self::InnerProduct as InnerProduct>::LeftMessage],
+        right: &[<Self::InnerProduct as InnerProduct>::RightMessage],
+        com: &Self::Commitment,
+    ) -> Result<Self::Proof, Error>;
+
+    fn verify(
+        vk: &Self::VerifierKey,
+        com: &Self::Commitment,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error>;
+}
+
+// Linear combination `sum_i scalars[i] * bases[i]` in the output group of an inner product,
+// computed jointly rather than as separate scalar multiplications
+pub trait MultiScalarMul<F>: Sized {
+    fn multi_scalar_mul(bases: &[Self], scalars: &[F]) -> Self;
+}
+
+impl<G: ProjectiveCurve> MultiScalarMul<G::ScalarField> for G {
+    fn multi_scalar_mul(bases: &[Self], scalars: &[G::ScalarField]) -> Self {
+        let scalars = cfg_iter!(scalars)
+            .map(|b| b.into_repr())
+            .collect::<Vec<_>>();
+        VariableBaseMSM::multi_scalar_mul(&G::batch_normalization_into_affine(bases), &scalars)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -76,6 +158,68 @@ impl<P: PairingEngine> InnerProduct for PairingInnerProduct<P> {
             .collect::<Vec<_>>();
         Ok(ExtensionFieldElement(P::product_of_pairings(&aff_pairs)))
     }
+
+    // The line coefficients of `right` are computed once and shared by the Miller loops of all
+    // of `lefts`, which only replace the G1 half of the pairs; all of `lefts` are normalized with
+    // a single batch inversion
+    fn multi_inner_product(
+        lefts: &[&[Self::LeftMessage]],
+        right: &[Self::RightMessage],
+    ) -> Result<Vec<Self::Output>, Error> {
+        if let Some(left) = lefts.iter().find(|left| left.len() != right.len()) {
+            return Err(Box::new(InnerProductError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        };
+        let aff_right = P::G2Projective::batch_normalization_into_affine(right);
+        let mut pairs = cfg_into_iter!(aff_right)
+            .map(|b| (P::G1Prepared::default(), P::G2Prepared::from(b)))
+            .collect::<Vec<_>>();
+        let aff_lefts = P::G1Projective::batch_normalization_into_affine(&lefts.concat());
+        let miller_loops = (0..lefts.len())
+            .map(|i| {
+                let aff_left = &aff_lefts[i * right.len()..(i + 1) * right.len()];
+                for (pair, a) in pairs.iter_mut().zip(aff_left) {
+                    pair.0 = P::G1Prepared::from(*a);
+                }
+                P::miller_loop(&pairs)
+            })
+            .collect::<Vec<_>>();
+        Ok(cfg_into_iter!(miller_loops)
+            .map(|f| ExtensionFieldElement(P::final_exponentiation(&f).unwrap()))
+            .collect())
+    }
+
+    // `left` is normalized and prepared once for the Miller loops of all of `rights`, which are
+    // normalized with a single batch inversion
+    fn multi_inner_product_shared_left(
+        left: &[Self::LeftMessage],
+        rights: &[&[Self::RightMessage]],
+    ) -> Result<Vec<Self::Output>, Error> {
+        if let Some(right) = rights.iter().find(|right| right.len() != left.len()) {
+            return Err(Box::new(InnerProductError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        };
+        let aff_left = P::G1Projective::batch_normalization_into_affine(left);
+        let prep_left = cfg_into_iter!(aff_left)
+            .map(P::G1Prepared::from)
+            .collect::<Vec<_>>();
+        let aff_rights = P::G2Projective::batch_normalization_into_affine(&rights.concat());
+        Ok(cfg_into_iter!(0..rights.len())
+            .map(|i| {
+                let aff_right = &aff_rights[i * left.len()..(i + 1) * left.len()];
+                let pairs = prep_left
+                    .iter()
+                    .cloned()
+                    .zip(aff_right.iter().map(|b| P::G2Prepared::from(*b)))
+                    .collect::<Vec<_>>();
+                ExtensionFieldElement(P::product_of_pairings(&pairs))
+            })
+            .collect())
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -104,6 +248,48 @@ impl<G: ProjectiveCurve> InnerProduct for MultiexponentiationInnerProduct<G> {
             &right_bigints,
         ))
     }
+
+    fn multi_inner_product(
+        lefts: &[&[Self::LeftMessage]],
+        right: &[Self::RightMessage],
+    ) -> Result<Vec<Self::Output>, Error> {
+        if let Some(left) = lefts.iter().find(|left| left.len() != right.len()) {
+            return Err(Box::new(InnerProductError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        };
+        let right_bigints = cfg_iter!(right).map(|b| b.into_repr()).collect::<Vec<_>>();
+        Ok(lefts
+            .iter()
+            .map(|left| {
+                VariableBaseMSM::multi_scalar_mul(
+                    &G::batch_normalization_into_affine(left),
+                    &right_bigints,
+                )
+            })
+            .collect())
+    }
+
+    fn multi_inner_product_shared_left(
+        left: &[Self::LeftMessage],
+        rights: &[&[Self::RightMessage]],
+    ) -> Result<Vec<Self::Output>, Error> {
+        if let Some(right) = rights.iter().find(|right| right.len() != left.len()) {
+            return Err(Box::new(InnerProductError::MessageLengthInvalid(
+                left.len(),
+                right.len(),
+            )));
+        };
+        let aff_left = G::batch_normalization_into_affine(left);
+        Ok(rights
+            .iter()
+            .map(|right| {
+                let right_bigints = cfg_iter!(right).map(|b| b.into_repr()).collect::<Vec<_>>();
+                VariableBaseMSM::multi_scalar_mul(&aff_left, &right_bigints)
+            })
+            .collect())
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -133,9 +319,104 @@ impl<F: Field> InnerProduct for ScalarInnerProduct<F> {
 // Helper wrapper type around target group commitment output in order to implement MulAssign (needed for dh_commitments)
 //TODO: PairingEngine provides target group GT implementing Group for prime order P::Fr
 
-#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct ExtensionFieldElement<P: PairingEngine>(pub P::Fqk);
 
+// Serialized as the full Fqk element, except for `serialize` inside `serialize_compressed_gt`
+impl<P: PairingEngine> CanonicalSerialize for ExtensionFieldElement<P> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        if torus::is_compressed_gt() {
+            torus::serialize_compressed(&self.0, writer)
+        } else {
+            self.0.serialize(writer)
+        }
+    }
+
+    fn serialized_size(&self) -> usize {
+        if torus::is_compressed_gt() {
+            torus::compressed_size(&self.0)
+        } else {
+            self.0.serialized_size()
+        }
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.0.serialize_uncompressed(writer)
+    }
+
+    fn serialize_unchecked<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.0.serialize_unchecked(writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.0.uncompressed_size()
+    }
+}
+
+impl<P: PairingEngine> ExtensionFieldElement<P> {
+    // Whether the element lies in the order r target group rather than anywhere in Fqk
+    pub fn is_in_correct_subgroup(&self) -> bool {
+        self.0.pow(P::Fr::characteristic()).is_one()
+    }
+
+    fn check(self) -> Result<Self, SerializationError> {
+        if self.is_in_correct_subgroup() {
+            Ok(self)
+        } else {
+            Err(SerializationError::InvalidData)
+        }
+    }
+}
+
+// As for curve points, `deserialize` and `deserialize_uncompressed` only accept target group
+// elements, while `deserialize_unchecked` skips the check for trusted data
+impl<P: PairingEngine> CanonicalDeserialize for ExtensionFieldElement<P> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        if torus::is_compressed_gt() {
+            ExtensionFieldElement(torus::deserialize_compressed(reader)?).check()
+        } else {
+            ExtensionFieldElement(P::Fqk::deserialize(reader)?).check()
+        }
+    }
+
+    fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        ExtensionFieldElement(P::Fqk::deserialize_uncompressed(reader)?).check()
+    }
+
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(ExtensionFieldElement(P::Fqk::deserialize_unchecked(
+            reader,
+        )?))
+    }
+}
+
+// Opt-in serialization of proofs, and of anything else holding `ExtensionFieldElement`s, with the
+// target group elements in the torus-compressed encoding: a third of the size for pairings of
+// embedding degree divisible by 6. The other fields are written as by `serialize`
+pub trait CompressedGTSerialize: CanonicalSerialize {
+    fn serialize_compressed_gt<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        let _scope = torus::CompressedGT::enter();
+        self.serialize(writer)
+    }
+
+    fn compressed_gt_size(&self) -> usize {
+        let _scope = torus::CompressedGT::enter();
+        self.serialized_size()
+    }
+}
+
+impl<T: CanonicalSerialize> CompressedGTSerialize for T {}
+
+// Inverse of `serialize_compressed_gt`, checking that the target group elements are in GT
+pub trait CompressedGTDeserialize: CanonicalDeserialize {
+    fn deserialize_compressed_gt<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let _scope = torus::CompressedGT::enter();
+        Self::deserialize(reader)
+    }
+}
+
+impl<T: CanonicalDeserialize> CompressedGTDeserialize for T {}
+
 impl<P: PairingEngine> Default for ExtensionFieldElement<P> {
     fn default() -> Self {
         ExtensionFieldElement(<P::Fqk>::default())
@@ -150,9 +431,24 @@ impl<P: PairingEngine> PartialEq for ExtensionFieldElement<P> {
 
 impl<P: PairingEngine> Eq for ExtensionFieldElement<P> {}
 
-impl<P: PairingEngine> MulAssign<P::Fr> for ExtensionFieldElement<P> {
+// Target group elements take the cyclotomic squaring and Frobenius shortcuts of `gt`, other Fqk
+// elements fall back to `pow`
+impl<P: PairingEngine> MulAssign<P::Fr> for ExtensionFieldElement<P>
+where
+    P::Fqk: CyclotomicField,
+{
     fn mul_assign(&mut self, rhs: P::Fr) {
-        *self = ExtensionFieldElement(self.0.pow(rhs.into_repr()))
+        *self = ExtensionFieldElement(gt::exp(&self.0, &rhs))
+    }
+}
+
+impl<P: PairingEngine> MultiScalarMul<P::Fr> for ExtensionFieldElement<P>
+where
+    P::Fqk: CyclotomicField,
+{
+    fn multi_scalar_mul(bases: &[Self], scalars: &[P::Fr]) -> Self {
+        let bases = bases.iter().map(|b| b.0).collect::<Vec<_>>();
+        ExtensionFieldElement(gt::multi_exp(&bases, scalars))
     }
 }
 
@@ -175,3 +471,99 @@ impl<P: PairingEngine> ToBytes for ExtensionFieldElement<P> {
         self.0.write(&mut writer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn multi_inner_product_test() {
+        const LEN: usize = 4;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let lefts = (0..3)
+            .map(|_| {
+                (0..LEN)
+                    .map(|_| G1Projective::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let lefts = lefts.iter().map(|left| &left[..]).collect::<Vec<_>>();
+
+        let right = (0..LEN)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let ips = PairingInnerProduct::<Bls12_381>::multi_inner_product(&lefts, &right).unwrap();
+        assert_eq!(ips.len(), lefts.len());
+        for (left, ip) in lefts.iter().zip(&ips) {
+            assert_eq!(
+                PairingInnerProduct::<Bls12_381>::inner_product(left, &right).unwrap(),
+                *ip
+            );
+        }
+        assert!(
+            PairingInnerProduct::<Bls12_381>::multi_inner_product(&lefts, &right[1..]).is_err()
+        );
+
+        let scalars = (0..LEN).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let ips =
+            MultiexponentiationInnerProduct::<G1Projective>::multi_inner_product(&lefts, &scalars)
+                .unwrap();
+        for (left, ip) in lefts.iter().zip(&ips) {
+            assert_eq!(
+                MultiexponentiationInnerProduct::<G1Projective>::inner_product(left, &scalars)
+                    .unwrap(),
+                *ip
+            );
+        }
+    }
+
+    #[test]
+    fn multi_inner_product_shared_left_test() {
+        const LEN: usize = 4;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let left = (0..LEN)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let rights = (0..3)
+            .map(|_| {
+                (0..LEN)
+                    .map(|_| G2Projective::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let rights = rights.iter().map(|right| &right[..]).collect::<Vec<_>>();
+
+        let ips = PairingInnerProduct::<Bls12_381>::multi_inner_product_shared_left(&left, &rights)
+            .unwrap();
+        assert_eq!(ips.len(), rights.len());
+        for (right, ip) in rights.iter().zip(&ips) {
+            assert_eq!(
+                PairingInnerProduct::<Bls12_381>::inner_product(&left, right).unwrap(),
+                *ip
+            );
+        }
+        assert!(
+            PairingInnerProduct::<Bls12_381>::multi_inner_product_shared_left(&left[1..], &rights)
+                .is_err()
+        );
+
+        let scalars = (0..3)
+            .map(|_| (0..LEN).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let scalars = scalars.iter().map(|right| &right[..]).collect::<Vec<_>>();
+        let ips = MultiexponentiationInnerProduct::<G1Projective>::multi_inner_product_shared_left(
+            &left, &scalars,
+        )
+        .unwrap();
+        for (right, ip) in scalars.iter().zip(&ips) {
+            assert_eq!(
+                MultiexponentiationInnerProduct::<G1Projective>::inner_product(&left, right)
+                    .unwrap(),
+                *ip
+            );
+        }
+    }
+}
@@ -0,0 +1,434 @@
+// Compressed encoding of target group elements by the algebraic torus T6.
+//
+// With q^6 = p^k, the target group lies in the cyclotomic subgroup T6(Fq) of Fqk, which is
+// birational to the plane Fq^2 (Rubin-Silverberg), so an element is encoded by two Fq elements:
+// a third of the size of its Fqk encoding.
+//
+// Writing Fqk = Fq3[w] with w^2 = v, an element g = c0 + c1 w of T6 is first mapped to
+// theta = (1 + c0) / c1 in Fq3 (the T2 parametrization), under which T6 is a quadric surface.
+// The surface is in turn parametrized by the lines through a fixed point theta0 on it, and theta
+// is encoded by the slopes (d1 / d0, d2 / d0) of d = theta - theta0 over the Fq-basis of Fq3.
+//
+// Elements outside of the torus parametrization (e.g. the zero element of `Default`) fall back
+// to the uncompressed encoding.
+//
+// The encoding is used by `ExtensionFieldElement::serialize` within a `CompressedGT` scope, which
+// `serialize_compressed_gt` opens around the serialization of a whole proof.
+
+use ark_ff::{Field, One, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Flags, Read, SerializationError, Write,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::Cell,
+    sync::Mutex,
+};
+
+thread_local! {
+    static COMPRESSED_GT: Cell<bool> = const { Cell::new(false) };
+}
+
+// Target group elements are (de)serialized compressed while a `CompressedGT` is alive
+pub(crate) struct CompressedGT(bool);
+
+impl CompressedGT {
+    pub(crate) fn enter() -> Self {
+        CompressedGT(COMPRESSED_GT.with(|compressed| compressed.replace(true)))
+    }
+}
+
+impl Drop for CompressedGT {
+    fn drop(&mut self) {
+        let outer = self.0;
+        COMPRESSED_GT.with(|compressed| compressed.set(outer));
+    }
+}
+
+pub(crate) fn is_compressed_gt() -> bool {
+    COMPRESSED_GT.with(|compressed| compressed.get())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum TorusFlags {
+    #[default]
+    Compressed,
+    Identity,
+    Uncompressed,
+}
+
+impl Flags for TorusFlags {
+    const BIT_SIZE: usize = 2;
+
+    fn u8_bitmask(&self) -> u8 {
+        match self {
+            TorusFlags::Compressed => 0,
+            TorusFlags::Identity => 1 << 7,
+            TorusFlags::Uncompressed => 1 << 6,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value >> 6 {
+            0b00 => Some(TorusFlags::Compressed),
+            0b10 => Some(TorusFlags::Identity),
+            0b01 => Some(TorusFlags::Uncompressed),
+            _ => None,
+        }
+    }
+}
+
+// Fqk is a degree 6 extension of Fq = F_{p^m}
+fn fq_degree<F: Field>() -> Option<usize> {
+    let k = F::extension_degree() as usize;
+    if 6 * (k / 6) == k {
+        Some(k / 6)
+    } else {
+        None
+    }
+}
+
+// Coordinates over the base prime field, in the order of the extension tower
+fn coordinates<F: Field>(x: &F) -> Vec<F::BasePrimeField> {
+    let mut bytes = Vec::new();
+    x.serialize_uncompressed(&mut bytes)
+        .expect("serializing to a vector does not fail");
+    let mut reader = &bytes[..];
+    (0..F::extension_degree())
+        .map(|_| {
+            F::BasePrimeField::deserialize_uncompressed(&mut reader)
+                .expect("field element holds its coordinates")
+        })
+        .collect()
+}
+
+// Inverse of `coordinates`, with the missing trailing coordinates set to zero
+fn from_coordinates<F: Field>(coordinates: &[F::BasePrimeField]) -> F {
+    let mut elems = coordinates.to_vec();
+    elems.resize(F::extension_degree() as usize, F::BasePrimeField::zero());
+    F::from_base_prime_field_elems(&elems).expect("number of coordinates is the extension degree")
+}
+
+fn unit<F: Field>(index: usize) -> F {
+    let mut elems = vec![F::BasePrimeField::zero(); index + 1];
+    elems[index] = F::BasePrimeField::one();
+    from_coordinates(&elems)
+}
+
+#[derive(Clone, Copy)]
+struct Torus<F: Field> {
+    m: usize,
+    w: F,
+    v: F,
+    gamma_inv: F,
+    theta0: F,
+}
+
+// Torus constants of each field, computed once
+static TORI: Mutex<Vec<(TypeId, Box<dyn Any + Send + Sync>)>> = Mutex::new(Vec::new());
+
+impl<F: Field> Torus<F> {
+    fn cached(m: usize) -> Self {
+        let mut tori = TORI.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, torus)) = tori.iter().find(|(id, _)| *id == TypeId::of::<F>()) {
+            if let Some(torus) = torus.downcast_ref::<Self>() {
+                return *torus;
+            }
+        }
+        let torus = Self::new(m);
+        tori.push((TypeId::of::<F>(), Box::new(torus)));
+        torus
+    }
+
+    fn new(m: usize) -> Self {
+        // Generator of Fqk over Fq3, the last step of the extension tower
+        let w = unit::<F>(3 * m);
+        let v = w.square();
+        let mut torus = Torus {
+            m,
+            w,
+            v,
+            gamma_inv: F::one(),
+            theta0: F::zero(),
+        };
+        // w^q = gamma w for some gamma in Fq
+        torus.gamma_inv = w * torus.frobenius(&w, 1).inverse().unwrap();
+
+        // Base point: image of 1 + w under the projection x -> x^((q^3 - 1)(q + 1)) onto T6
+        let h = F::one() + w;
+        let u = torus.frobenius(&h, 3) * h.inverse().unwrap();
+        let g0 = torus.frobenius(&u, 1) * u;
+        torus.theta0 = torus.theta(&g0).unwrap();
+        torus
+    }
+
+    // x^(q^power)
+    fn frobenius(&self, x: &F, power: usize) -> F {
+        let mut y = *x;
+        y.frobenius_map(power * self.m);
+        y
+    }
+
+    // Action of the q-power Frobenius on T2 coordinates
+    fn phi(&self, theta: &F) -> F {
+        self.frobenius(theta, 1) * self.gamma_inv
+    }
+
+    // g^(q^2) g = g^q in T2 coordinates, whose group law is
+    // (theta1, theta2) -> (theta1 theta2 + v) / (theta1 + theta2); vanishes exactly on T6
+    fn quadric(&self, theta: &F) -> F {
+        let theta_q = self.phi(theta);
+        let theta_q2 = self.phi(&theta_q);
+        *theta * theta_q + (theta_q * theta_q2) - (*theta * theta_q2) - self.v
+    }
+
+    fn in_torus(&self, g: &F) -> bool {
+        self.frobenius(g, 2) * g == self.frobenius(g, 1)
+    }
+
+    // (1 + c0) / c1 for g = c0 + c1 w, undefined for g in Fq3
+    fn theta(&self, g: &F) -> Option<F> {
+        let conjugate = self.frobenius(g, 3);
+        let c1w = *g - conjugate;
+        if c1w.is_zero() {
+            return None;
+        }
+        Some(self.w * (F::one().double() + g + conjugate) * c1w.inverse()?)
+    }
+
+    // Direction d = theta - theta0 of the line through theta0 encoding g, if g is compressible
+    fn line(&self, g: &F) -> Option<F> {
+        if !self.in_torus(g) {
+            return None;
+        }
+        let d = self.theta(g)? - self.theta0;
+        // The slopes are taken relative to d0
+        if coordinates(&d)[..self.m].iter().all(|c| c.is_zero()) {
+            return None;
+        }
+        // quadric(theta0 + t d) = a t^2 + b t vanishes at t = 1, so a = quadric(theta0 - d) / 2.
+        // Lines with a = 0 lie in the surface and do not determine theta
+        if self.quadric(&(self.theta0 - d)).is_zero() {
+            return None;
+        }
+        Some(d)
+    }
+
+    fn is_compressible(&self, g: &F) -> bool {
+        self.line(g).is_some()
+    }
+
+    // Slopes (d1 / d0, d2 / d0), concatenated
+    fn compress(&self, g: &F) -> Option<Vec<F::BasePrimeField>> {
+        let d = coordinates(&self.line(g)?);
+        let m = self.m;
+        let d0_inv = from_coordinates::<F>(&d[..m]).inverse()?;
+        let s1 = coordinates(&(from_coordinates::<F>(&d[m..2 * m]) * d0_inv))[..m].to_vec();
+        let s2 = coordinates(&(from_coordinates::<F>(&d[2 * m..3 * m]) * d0_inv))[..m].to_vec();
+        Some([s1, s2].concat())
+    }
+
+    fn decompress(&self, slopes: &[F::BasePrimeField]) -> Option<F> {
+        let m = self.m;
+        let d = F::one()
+            + (from_coordinates::<F>(&slopes[..m]) * unit::<F>(m))
+            + (from_coordinates::<F>(&slopes[m..]) * unit::<F>(2 * m));
+
+        // quadric(theta0 + t d) = a t^2 + b t, so the line meets the surface again at t = -b / a
+        let plus = self.quadric(&(self.theta0 + d));
+        let minus = self.quadric(&(self.theta0 - d));
+        let t = (minus - plus) * (plus + minus).inverse()?;
+        if coordinates(&t)[m..].iter().any(|c| !c.is_zero()) {
+            return None;
+        }
+
+        let theta = self.theta0 + (t * d);
+        Some((theta + self.w) * (theta - self.w).inverse()?)
+    }
+}
+
+pub(crate) fn serialize_compressed<F: Field, W: Write>(
+    x: &F,
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    let m = match fq_degree::<F>() {
+        Some(m) => m,
+        None => return x.serialize_uncompressed(writer),
+    };
+    let (flags, mut slopes) = if x.is_one() {
+        (TorusFlags::Identity, vec![])
+    } else {
+        match Torus::cached(m).compress(x) {
+            Some(slopes) => (TorusFlags::Compressed, slopes),
+            None => (TorusFlags::Uncompressed, vec![]),
+        }
+    };
+
+    slopes.resize(2 * m, F::BasePrimeField::zero());
+    let (last, rest) = slopes.split_last().unwrap();
+    for c in rest {
+        c.serialize(&mut writer)?;
+    }
+    last.serialize_with_flags(&mut writer, flags)?;
+    if flags == TorusFlags::Uncompressed {
+        x.serialize_uncompressed(&mut writer)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn compressed_size<F: Field>(x: &F) -> usize {
+    let m = match fq_degree::<F>() {
+        Some(m) => m,
+        None => return x.uncompressed_size(),
+    };
+    let zero = F::BasePrimeField::zero();
+    let size =
+        (2 * m - 1) * zero.serialized_size() + zero.serialized_size_with_flags::<TorusFlags>();
+    if x.is_one() || Torus::cached(m).is_compressible(x) {
+        size
+    } else {
+        size + x.uncompressed_size()
+    }
+}
+
+pub(crate) fn deserialize_compressed<F: Field, R: Read>(
+    mut reader: R,
+) -> Result<F, SerializationError> {
+    let m = match fq_degree::<F>() {
+        Some(m) => m,
+        None => return F::deserialize_uncompressed(reader),
+    };
+    let mut slopes = (0..2 * m - 1)
+        .map(|_| F::BasePrimeField::deserialize(&mut reader))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (last, flags) = F::BasePrimeField::deserialize_with_flags::<_, TorusFlags>(&mut reader)?;
+    slopes.push(last);
+
+    match flags {
+        TorusFlags::Compressed => Torus::<F>::cached(m)
+            .decompress(&slopes)
+            .ok_or(SerializationError::InvalidData),
+        _ if slopes.iter().any(|c| !c.is_zero()) => Err(SerializationError::InvalidData),
+        TorusFlags::Identity => Ok(F::one()),
+        TorusFlags::Uncompressed => F::deserialize_uncompressed(reader),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompressedGTDeserialize, CompressedGTSerialize, ExtensionFieldElement};
+    use ark_bls12_381::{Bls12_381, Fq12};
+    use ark_ec::PairingEngine;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn torus_compression_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for _ in 0..8 {
+            let gt = ExtensionFieldElement::<Bls12_381>(Bls12_381::pairing(
+                <Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng),
+                <Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng),
+            ));
+            let mut bytes = Vec::new();
+            gt.serialize_compressed_gt(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), gt.compressed_gt_size());
+            assert_eq!(3 * bytes.len(), gt.serialized_size());
+            assert_eq!(
+                gt,
+                ExtensionFieldElement::deserialize_compressed_gt(&bytes[..]).unwrap()
+            );
+
+            // Outside of the scope, `serialize` keeps the full Fqk encoding
+            let mut uncompressed_bytes = Vec::new();
+            gt.serialize_uncompressed(&mut uncompressed_bytes).unwrap();
+            let mut plain_bytes = Vec::new();
+            gt.serialize(&mut plain_bytes).unwrap();
+            assert_eq!(plain_bytes, uncompressed_bytes);
+            assert_eq!(
+                gt,
+                ExtensionFieldElement::deserialize(&plain_bytes[..]).unwrap()
+            );
+
+            // Tampered slopes decode to another element or not at all
+            bytes[0] ^= 1;
+            if let Ok(other) =
+                ExtensionFieldElement::<Bls12_381>::deserialize_compressed_gt(&bytes[..])
+            {
+                assert!(other != gt);
+            }
+        }
+
+        let one = ExtensionFieldElement::<Bls12_381>(Fq12::one());
+        let mut bytes = Vec::new();
+        one.serialize_compressed_gt(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), one.compressed_gt_size());
+        assert_eq!(
+            one,
+            ExtensionFieldElement::deserialize_compressed_gt(&bytes[..]).unwrap()
+        );
+
+        // Elements outside of the torus fall back to the uncompressed encoding
+        let torus = Torus::<Fq12>::cached(2);
+        for x in [Fq12::zero(), Fq12::rand(&mut rng)].iter() {
+            let mut bytes = Vec::new();
+            ExtensionFieldElement::<Bls12_381>(*x)
+                .serialize_compressed_gt(&mut bytes)
+                .unwrap();
+            assert_eq!(
+                bytes.len(),
+                ExtensionFieldElement::<Bls12_381>(*x).compressed_gt_size()
+            );
+            let mut reader = &bytes[..];
+            assert_eq!(*x, deserialize_compressed::<Fq12, _>(&mut reader).unwrap());
+            assert!(reader.is_empty());
+            assert!(!torus.is_compressible(x));
+            assert!(torus.compress(x).is_none());
+        }
+        assert!(!is_compressed_gt());
+    }
+
+    #[test]
+    fn target_group_validation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let gt = ExtensionFieldElement::<Bls12_381>(Bls12_381::pairing(
+            <Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng),
+            <Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng),
+        ));
+        assert!(gt.is_in_correct_subgroup());
+
+        // Fq12 elements outside of GT, in and out of the cyclotomic subgroup
+        let torus = Torus::<Fq12>::new(2);
+        let h = Fq12::rand(&mut rng);
+        let u = torus.frobenius(&h, 3) * h.inverse().unwrap();
+        let cyclotomic = torus.frobenius(&u, 1) * u;
+        for x in [Fq12::zero(), h, cyclotomic].iter() {
+            let x = ExtensionFieldElement::<Bls12_381>(*x);
+            assert!(!x.is_in_correct_subgroup());
+            for compressed in [true, false].iter() {
+                let mut bytes = Vec::new();
+                if *compressed {
+                    x.serialize_compressed_gt(&mut bytes).unwrap();
+                    assert!(
+                        ExtensionFieldElement::<Bls12_381>::deserialize_compressed_gt(&bytes[..])
+                            .is_err()
+                    );
+                } else {
+                    x.serialize_uncompressed(&mut bytes).unwrap();
+                    assert!(
+                        ExtensionFieldElement::<Bls12_381>::deserialize_uncompressed(&bytes[..])
+                            .is_err()
+                    );
+                }
+            }
+            let mut bytes = Vec::new();
+            x.serialize_unchecked(&mut bytes).unwrap();
+            assert_eq!(
+                x,
+                ExtensionFieldElement::deserialize_unchecked(&bytes[..]).unwrap()
+            );
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use ark_ec::{group::Group, AffineCurve, PairingEngine};
-use ark_ff::{to_bytes, Field, One};
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
-use std::ops::AddAssign;
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::AddAssign,
+};
 
 use ark_std::rand::Rng;
 use digest::Digest;
@@ -19,8 +24,8 @@ use ark_dh_commitments::{
     identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
 };
 use ark_inner_products::{
-    ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct,
-    ScalarInnerProduct,
+    CyclotomicField, ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct,
+    PairingInnerProduct, ScalarInnerProduct,
 };
 
 type PairingInnerProductAB<P, D> = TIPA<
@@ -57,19 +62,161 @@ type MultiExpInnerProductCProof<P, D> = TIPAWithSSMProof<
     D,
 >;
 
-pub struct AggregateProof<P: PairingEngine, D: Digest> {
-    com_a: ExtensionFieldElement<P>,
-    com_b: ExtensionFieldElement<P>,
-    com_c: ExtensionFieldElement<P>,
-    ip_ab: ExtensionFieldElement<P>,
-    agg_c: P::G1Projective,
-    tipa_proof_ab: PairingInnerProductABProof<P, D>,
-    tipa_proof_c: MultiExpInnerProductCProof<P, D>,
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
+    pub com_a: ExtensionFieldElement<P>,
+    pub com_b: ExtensionFieldElement<P>,
+    pub com_c: ExtensionFieldElement<P>,
+    pub ip_ab: ExtensionFieldElement<P>,
+    pub agg_c: P::G1Projective,
+    pub tipa_proof_ab: PairingInnerProductABProof<P, D>,
+    pub tipa_proof_c: MultiExpInnerProductCProof<P, D>,
+}
+
+impl<P: PairingEngine, D: Digest> Clone for AggregateProof<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
+    fn clone(&self) -> Self {
+        Self {
+            com_a: self.com_a.clone(),
+            com_b: self.com_b.clone(),
+            com_c: self.com_c.clone(),
+            ip_ab: self.ip_ab.clone(),
+            agg_c: self.agg_c,
+            tipa_proof_ab: self.tipa_proof_ab.clone(),
+            tipa_proof_c: self.tipa_proof_c.clone(),
+        }
+    }
+}
+
+// Aggregate of proofs from several circuits, one `AggregateProof` per verifying key
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct GroupedAggregateProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
+    pub groups: Vec<AggregateProof<P, D>>,
+}
+
+#[derive(Debug)]
+pub enum AggregationError {
+    NoProofs,
+    // (proofs, public input vectors)
+    ProofCountMismatch(usize, usize),
+    // (proofs, maximum supported by the SRS)
+    ProofCountInvalid(usize, usize),
+    // (expected, found)
+    PublicInputLengthInvalid(usize, usize),
+    EmptyVerifyingKey,
+}
+
+impl ErrorTrait for AggregationError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for AggregationError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            AggregationError::NoProofs => "no proofs to aggregate".to_string(),
+            AggregationError::ProofCountMismatch(proofs, inputs) => {
+                format!("proofs, public inputs: {}, {}", proofs, inputs)
+            }
+            AggregationError::ProofCountInvalid(proofs, max) => {
+                format!("proofs, maximum proofs: {}, {}", proofs, max)
+            }
+            AggregationError::PublicInputLengthInvalid(expected, found) => {
+                format!("expected public inputs, found: {}, {}", expected, found)
+            }
+            AggregationError::EmptyVerifyingKey => "verifying key has no gamma_abc_g1".to_string(),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+fn check_public_inputs<P: PairingEngine>(
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::Fr>],
+) -> Result<(), Error> {
+    if public_inputs.is_empty() {
+        return Err(Box::new(AggregationError::NoProofs));
+    }
+    let num_inputs = match vk.gamma_abc_g1.len().checked_sub(1) {
+        Some(num_inputs) => num_inputs,
+        None => return Err(Box::new(AggregationError::EmptyVerifyingKey)),
+    };
+    for inputs in public_inputs {
+        if inputs.len() != num_inputs {
+            return Err(Box::new(AggregationError::PublicInputLengthInvalid(
+                num_inputs,
+                inputs.len(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Number of proofs a group is padded to: a power of two, and at least one TIPA round
+fn padded_len(num_proofs: usize) -> usize {
+    std::cmp::max(num_proofs.next_power_of_two(), 2)
+}
+
+fn check_proof_count<P: PairingEngine>(ip_srs: &SRS<P>, num_proofs: usize) -> Result<(), Error> {
+    let max_proofs = (ip_srs.g_alpha_powers.len() + 1) / 2;
+    if padded_len(num_proofs) > max_proofs {
+        return Err(Box::new(AggregationError::ProofCountInvalid(
+            num_proofs, max_proofs,
+        )));
+    }
+    Ok(())
+}
+
+// Challenge for the random linear combination of proofs, binding the verifying keys, the public
+// inputs and the commitments to the proofs of every group
+fn compute_aggregation_challenge<P: PairingEngine, D: Digest>(
+    groups: &[(&VerifyingKey<P>, &[Vec<P::Fr>])],
+    coms: &[(
+        &ExtensionFieldElement<P>,
+        &ExtensionFieldElement<P>,
+        &ExtensionFieldElement<P>,
+    )],
+) -> Result<P::Fr, Error> {
+    let mut transcript = Vec::new();
+    for ((vk, public_inputs), (com_a, com_b, com_c)) in groups.iter().zip(coms) {
+        transcript.extend_from_slice(&canonical_bytes![
+            *vk,
+            *public_inputs,
+            *com_a,
+            *com_b,
+            *com_c
+        ]);
+    }
+
+    let mut counter_nonce: usize = 0;
+    let r = loop {
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+        hash_input.extend_from_slice(&transcript);
+        if let Some(r) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
+            // r is inverted by the prover
+            if !r.is_zero() {
+                break r;
+            }
+        };
+        counter_nonce += 1;
+    };
+    Ok(r)
 }
 
 pub fn setup_inner_product<P, D, R: Rng>(rng: &mut R, size: usize) -> Result<SRS<P>, Error>
 where
     P: PairingEngine,
+    P::Fqk: CyclotomicField,
     D: Digest,
 {
     let (srs, _) = PairingInnerProductAB::<P, D>::setup(rng, size)?;
@@ -78,155 +225,560 @@ where
 
 pub fn aggregate_proofs<P, D>(
     ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::Fr>],
     proofs: &[Proof<P>],
 ) -> Result<AggregateProof<P, D>, Error>
 where
     P: PairingEngine,
+    P::Fqk: CyclotomicField,
     D: Digest,
 {
-    let a = proofs
-        .iter()
-        .map(|proof| proof.a.into_projective())
-        .collect::<Vec<P::G1Projective>>();
-    let b = proofs
-        .iter()
-        .map(|proof| proof.b.into_projective())
-        .collect::<Vec<P::G2Projective>>();
-    let c = proofs
-        .iter()
-        .map(|proof| proof.c.into_projective())
-        .collect::<Vec<P::G1Projective>>();
-
-    let (ck_1, ck_2) = ip_srs.get_commitment_keys();
-
-    let com_a = PairingInnerProduct::<P>::inner_product(&a, &ck_1)?;
-    let com_b = PairingInnerProduct::<P>::inner_product(&ck_2, &b)?;
-    let com_c = PairingInnerProduct::<P>::inner_product(&c, &ck_1)?;
+    let mut groups = aggregate_groups::<P, D>(ip_srs, &[(vk, public_inputs, proofs)])?;
+    Ok(groups.pop().unwrap())
+}
 
-    // Random linear combination of proofs
-    let mut counter_nonce: usize = 0;
-    let r = loop {
-        let mut hash_input = Vec::new();
-        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-        //TODO: Should use CanonicalSerialize instead of ToBytes
-        hash_input.extend_from_slice(&to_bytes![com_a, com_b, com_c]?);
-        if let Some(r) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
-            break r;
-        };
-        counter_nonce += 1;
-    };
+pub fn verify_aggregate_proof<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::Fr>], //TODO: Should use ToConstraintField instead
+    proof: &AggregateProof<P, D>,
+) -> Result<bool, Error>
+where
+    P: PairingEngine,
+    P::Fqk: CyclotomicField,
+    D: Digest,
+{
+    verify_groups(
+        ip_verifier_srs,
+        &[(vk, public_inputs)],
+        std::slice::from_ref(proof),
+    )
+}
 
-    let r_vec = structured_scalar_power(proofs.len(), &r);
-    let a_r = a
-        .iter()
-        .zip(&r_vec)
-        .map(|(a, r)| a.mul(r))
-        .collect::<Vec<P::G1Projective>>();
-    let ip_ab = PairingInnerProduct::<P>::inner_product(&a_r, &b)?;
-    let agg_c = MultiexponentiationInnerProduct::<P::G1Projective>::inner_product(&c, &r_vec)?;
-
-    let ck_1_r = ck_1
-        .iter()
-        .zip(&r_vec)
-        .map(|(ck, r)| ck.mul(&r.inverse().unwrap()))
-        .collect::<Vec<P::G2Projective>>();
-
-    assert_eq!(
-        com_a,
-        PairingInnerProduct::<P>::inner_product(&a_r, &ck_1_r)?
-    );
-
-    let tipa_proof_ab = PairingInnerProductAB::<P, D>::prove_with_srs_shift(
-        &ip_srs,
-        (&a_r, &b),
-        (&ck_1_r, &ck_2, &HomomorphicPlaceholderValue),
-        &r,
-    )?;
+// Aggregates proofs from several circuits. Each group holds the proofs for one verifying key and
+// is padded to a power of two no larger than the SRS.
+pub fn aggregate_proofs_by_vk<P, D>(
+    ip_srs: &SRS<P>,
+    groups: &[(&VerifyingKey<P>, &[Vec<P::Fr>], &[Proof<P>])],
+) -> Result<GroupedAggregateProof<P, D>, Error>
+where
+    P: PairingEngine,
+    P::Fqk: CyclotomicField,
+    D: Digest,
+{
+    Ok(GroupedAggregateProof {
+        groups: aggregate_groups(ip_srs, groups)?,
+    })
+}
 
-    let tipa_proof_c = MultiExpInnerProductC::<P, D>::prove_with_structured_scalar_message(
-        &ip_srs,
-        (&c, &r_vec),
-        (&ck_1, &HomomorphicPlaceholderValue),
+pub fn verify_aggregate_proof_by_vk<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    groups: &[(&VerifyingKey<P>, &[Vec<P::Fr>])],
+    proof: &GroupedAggregateProof<P, D>,
+) -> Result<bool, Error>
+where
+    P: PairingEngine,
+    P::Fqk: CyclotomicField,
+    D: Digest,
+{
+    verify_groups(ip_verifier_srs, groups, &proof.groups)
+}
+
+fn aggregate_groups<P, D>(
+    ip_srs: &SRS<P>,
+    groups: &[(&VerifyingKey<P>, &[Vec<P::Fr>], &[Proof<P>])],
+) -> Result<Vec<AggregateProof<P, D>>, Error>
+where
+    P: PairingEngine,
+    P::Fqk: CyclotomicField,
+    D: Digest,
+{
+    if groups.is_empty() {
+        return Err(Box::new(AggregationError::NoProofs));
+    }
+
+    let mut group_srs = Vec::new();
+    let mut group_proofs = Vec::new();
+    let mut group_coms = Vec::new();
+    for (vk, public_inputs, proofs) in groups {
+        check_public_inputs(vk, public_inputs)?;
+        if proofs.len() != public_inputs.len() {
+            return Err(Box::new(AggregationError::ProofCountMismatch(
+                proofs.len(),
+                public_inputs.len(),
+            )));
+        }
+        check_proof_count(ip_srs, proofs.len())?;
+
+        // Pad with zero proofs, which the verifier leaves out of the pairing product equation
+        let padded_len = padded_len(proofs.len());
+        let mut a = proofs
+            .iter()
+            .map(|proof| proof.a.into_projective())
+            .collect::<Vec<P::G1Projective>>();
+        let mut b = proofs
+            .iter()
+            .map(|proof| proof.b.into_projective())
+            .collect::<Vec<P::G2Projective>>();
+        let mut c = proofs
+            .iter()
+            .map(|proof| proof.c.into_projective())
+            .collect::<Vec<P::G1Projective>>();
+        a.resize(padded_len, P::G1Projective::zero());
+        b.resize(padded_len, P::G2Projective::zero());
+        c.resize(padded_len, P::G1Projective::zero());
+
+        let srs = ip_srs.truncate(padded_len);
+        let (ck_1, ck_2) = srs.get_commitment_keys();
+
+        let com_a = PairingInnerProduct::<P>::inner_product(&a, &ck_1)?;
+        let com_b = PairingInnerProduct::<P>::inner_product(&ck_2, &b)?;
+        let com_c = PairingInnerProduct::<P>::inner_product(&c, &ck_1)?;
+
+        group_srs.push(srs);
+        group_proofs.push((a, b, c));
+        group_coms.push((com_a, com_b, com_c));
+    }
+
+    // Random linear combination of proofs
+    let r = compute_aggregation_challenge::<P, D>(
+        &groups
+            .iter()
+            .map(|(vk, public_inputs, _)| (*vk, *public_inputs))
+            .collect::<Vec<_>>(),
+        &group_coms
+            .iter()
+            .map(|(com_a, com_b, com_c)| (com_a, com_b, com_c))
+            .collect::<Vec<_>>(),
     )?;
 
-    Ok(AggregateProof {
-        com_a,
-        com_b,
-        com_c,
-        ip_ab,
-        agg_c,
-        tipa_proof_ab,
-        tipa_proof_c,
-    })
+    let mut aggregates = Vec::new();
+    for ((srs, (a, b, c)), (com_a, com_b, com_c)) in
+        group_srs.iter().zip(group_proofs).zip(group_coms)
+    {
+        let (ck_1, ck_2) = srs.get_commitment_keys();
+
+        let r_vec = structured_scalar_power(a.len(), &r);
+        let a_r = a
+            .iter()
+            .zip(&r_vec)
+            .map(|(a, r)| a.mul(r))
+            .collect::<Vec<P::G1Projective>>();
+        let ip_ab = PairingInnerProduct::<P>::inner_product(&a_r, &b)?;
+        let agg_c = MultiexponentiationInnerProduct::<P::G1Projective>::inner_product(&c, &r_vec)?;
+
+        let ck_1_r = ck_1
+            .iter()
+            .zip(&r_vec)
+            .map(|(ck, r)| ck.mul(&r.inverse().unwrap()))
+            .collect::<Vec<P::G2Projective>>();
+
+        debug_assert_eq!(
+            com_a,
+            PairingInnerProduct::<P>::inner_product(&a_r, &ck_1_r)?
+        );
+
+        let tipa_proof_ab = PairingInnerProductAB::<P, D>::prove_with_srs_shift(
+            srs,
+            (&a_r, &b),
+            (&ck_1_r, &ck_2, &HomomorphicPlaceholderValue),
+            &r,
+        )?;
+
+        let tipa_proof_c = MultiExpInnerProductC::<P, D>::prove_with_structured_scalar_message(
+            srs,
+            (&c, &r_vec),
+            (&ck_1, &HomomorphicPlaceholderValue),
+        )?;
+
+        aggregates.push(AggregateProof {
+            com_a,
+            com_b,
+            com_c,
+            ip_ab,
+            agg_c,
+            tipa_proof_ab,
+            tipa_proof_c,
+        });
+    }
+    Ok(aggregates)
 }
 
-pub fn verify_aggregate_proof<P, D>(
+// Proofs across all groups are combined with consecutive powers of r, group k starting at
+// r^offset_k, so that the pairing product equations of every verifying key fold into a single
+// multi-pairing. Padding proofs get powers of r of their own and are left out of the equation, so
+// they cannot offset the real ones as long as every group has its padded length.
+fn verify_groups<P, D>(
     ip_verifier_srs: &VerifierSRS<P>,
-    vk: &VerifyingKey<P>,
-    public_inputs: &Vec<Vec<P::Fr>>, //TODO: Should use ToConstraintField instead
-    proof: &AggregateProof<P, D>,
+    groups: &[(&VerifyingKey<P>, &[Vec<P::Fr>])],
+    proofs: &[AggregateProof<P, D>],
 ) -> Result<bool, Error>
 where
     P: PairingEngine,
+    P::Fqk: CyclotomicField,
     D: Digest,
 {
-    // Random linear combination of proofs
-    let mut counter_nonce: usize = 0;
-    let r = loop {
-        let mut hash_input = Vec::new();
-        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-        //TODO: Should use CanonicalSerialize instead of ToBytes
-        hash_input.extend_from_slice(&to_bytes![proof.com_a, proof.com_b, proof.com_c]?);
-        if let Some(r) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
-            break r;
-        };
-        counter_nonce += 1;
-    };
+    if groups.is_empty() {
+        return Err(Box::new(AggregationError::NoProofs));
+    }
+    for (vk, public_inputs) in groups {
+        check_public_inputs(vk, public_inputs)?;
+    }
+    if proofs.len() != groups.len() {
+        return Ok(false);
+    }
 
-    // Check TIPA proofs
-    let tipa_proof_ab_valid = PairingInnerProductAB::<P, D>::verify_with_srs_shift(
-        ip_verifier_srs,
-        &HomomorphicPlaceholderValue,
-        (
-            &proof.com_a,
-            &proof.com_b,
-            &IdentityOutput(vec![proof.ip_ab.clone()]),
-        ),
-        &proof.tipa_proof_ab,
-        &r,
-    )?;
-    let tipa_proof_c_valid = MultiExpInnerProductC::<P, D>::verify_with_structured_scalar_message(
-        ip_verifier_srs,
-        &HomomorphicPlaceholderValue,
-        (&proof.com_c, &IdentityOutput(vec![proof.agg_c.clone()])),
-        &r,
-        &proof.tipa_proof_c,
+    // Random linear combination of proofs
+    let r = compute_aggregation_challenge::<P, D>(
+        groups,
+        &proofs
+            .iter()
+            .map(|proof| (&proof.com_a, &proof.com_b, &proof.com_c))
+            .collect::<Vec<_>>(),
     )?;
 
+    let mut tipa_proofs_valid = true;
+    let mut ip_ab = <P::Fqk>::one();
+    let mut pairs = Vec::new();
+    let mut r_offset = <P::Fr>::one();
+    for ((vk, public_inputs), proof) in groups.iter().zip(proofs) {
+        let padded_len = padded_len(public_inputs.len());
+        let num_rounds = padded_len.trailing_zeros() as usize;
+        if proof.tipa_proof_ab.gipa_proof.r_commitment_steps.len() != num_rounds
+            || proof.tipa_proof_c.gipa_proof.r_commitment_steps.len() != num_rounds
+        {
+            return Ok(false);
+        }
+
+        // Check TIPA proofs
+        tipa_proofs_valid &= PairingInnerProductAB::<P, D>::verify_with_srs_shift(
+            ip_verifier_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &proof.com_a,
+                &proof.com_b,
+                &IdentityOutput(vec![proof.ip_ab.clone()]),
+            ),
+            &proof.tipa_proof_ab,
+            &r,
+        )?;
+        tipa_proofs_valid &= MultiExpInnerProductC::<P, D>::verify_with_structured_scalar_message(
+            ip_verifier_srs,
+            &HomomorphicPlaceholderValue,
+            (&proof.com_c, &IdentityOutput(vec![proof.agg_c.clone()])),
+            &r,
+            &proof.tipa_proof_c,
+        )?;
+
+        // Pairing product equation terms of this group, shifted by r^offset
+        let r_vec = structured_scalar_power(public_inputs.len(), &r)
+            .iter()
+            .map(|r_i| r_offset * r_i)
+            .collect::<Vec<P::Fr>>();
+        let r_sum: P::Fr = r_vec.iter().sum();
+
+        let mut g_ic = vk.gamma_abc_g1[0].into_projective().mul(&r_sum);
+        for (i, b) in vk.gamma_abc_g1.iter().skip(1).enumerate() {
+            g_ic.add_assign(
+                &b.into_projective().mul(&ScalarInnerProduct::inner_product(
+                    &public_inputs
+                        .iter()
+                        .map(|inputs| inputs[i].clone())
+                        .collect::<Vec<P::Fr>>(),
+                    &r_vec,
+                )?),
+            );
+        }
+
+        ip_ab *= &proof.ip_ab.0.pow(r_offset.into_repr());
+        pairs.push((
+            P::G1Affine::from(vk.alpha_g1.into_projective().mul(&r_sum)).into(),
+            vk.beta_g2.into(),
+        ));
+        pairs.push((P::G1Affine::from(g_ic).into(), vk.gamma_g2.into()));
+        pairs.push((
+            P::G1Affine::from(proof.agg_c.mul(&r_offset)).into(),
+            vk.delta_g2.into(),
+        ));
+
+        r_offset *= &r.pow(&[padded_len as u64]);
+    }
+
     // Check aggregate pairing product equation
+    let ppe_valid = ip_ab == P::product_of_pairings(&pairs);
+
+    Ok(tipa_proofs_valid && ppe_valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_groth16::{create_random_proof, generate_random_parameters, ProvingKey};
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    const NUM_PUBLIC_INPUTS: usize = 2;
+    const NUM_PROOFS: usize = 8;
+
+    #[derive(Clone)]
+    struct SumCircuit {
+        public_inputs: Vec<Fr>,
+        witness_input: Fr,
+        public_sum: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SumCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let mut computed_sum = lc!() + cs.new_witness_variable(|| Ok(self.witness_input))?;
+            for x in &self.public_inputs {
+                computed_sum = computed_sum + cs.new_input_variable(|| Ok(*x))?;
+            }
+            let sum = cs.new_input_variable(|| Ok(self.public_sum))?;
+            cs.enforce_constraint(computed_sum, lc!() + Variable::One, lc!() + sum)?;
+            Ok(())
+        }
+    }
+
+    fn generate_proofs<R: Rng>(
+        num_public_inputs: usize,
+        num_proofs: usize,
+        rng: &mut R,
+    ) -> (ProvingKey<Bls12_381>, Vec<Vec<Fr>>, Vec<Proof<Bls12_381>>) {
+        let parameters = generate_random_parameters::<Bls12_381, _, _>(
+            SumCircuit {
+                public_inputs: vec![Default::default(); num_public_inputs],
+                public_sum: Default::default(),
+                witness_input: Default::default(),
+            },
+            rng,
+        )
+        .unwrap();
 
-    let r_sum =
-        (r.pow(&[public_inputs.len() as u64]) - &<P::Fr>::one()) / &(r.clone() - &<P::Fr>::one());
-    let p1 = P::pairing(vk.alpha_g1.into_projective().mul(&r_sum), vk.beta_g2);
-
-    assert_eq!(vk.gamma_abc_g1.len(), public_inputs[0].len() + 1);
-    let r_vec = structured_scalar_power(public_inputs.len(), &r);
-    let mut g_ic = vk.gamma_abc_g1[0].into_projective().mul(&r_sum);
-    for (i, b) in vk.gamma_abc_g1.iter().skip(1).enumerate() {
-        g_ic.add_assign(
-            &b.into_projective().mul(&ScalarInnerProduct::inner_product(
-                &public_inputs
-                    .iter()
-                    .map(|inputs| inputs[i].clone())
-                    .collect::<Vec<P::Fr>>(),
-                &r_vec,
-            )?),
+        let mut proofs = Vec::new();
+        let mut statements = Vec::new();
+        for _ in 0..num_proofs {
+            let public_inputs = (0..num_public_inputs)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<Fr>>();
+            let w = Fr::rand(rng);
+            let sum: Fr = w + &public_inputs.iter().sum();
+            let mut statement = public_inputs.clone();
+            statement.push(sum);
+            let circuit = SumCircuit {
+                public_inputs,
+                public_sum: sum,
+                witness_input: w,
+            };
+            proofs.push(create_random_proof(circuit, &parameters, rng).unwrap());
+            statements.push(statement);
+        }
+        (parameters, statements, proofs)
+    }
+
+    #[test]
+    fn groth16_aggregation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (parameters, statements, proofs) =
+            generate_proofs(NUM_PUBLIC_INPUTS, NUM_PROOFS, &mut rng);
+        let srs = setup_inner_product::<_, Blake2b, _>(&mut rng, NUM_PROOFS).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b>(&srs, &parameters.vk, &statements, &proofs)
+                .unwrap();
+        assert!(
+            verify_aggregate_proof(&v_srs, &parameters.vk, &statements, &aggregate_proof).unwrap()
+        );
+
+        // Round trip through canonical serialization
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize(&mut bytes).unwrap();
+        let deserialized = AggregateProof::<Bls12_381, Blake2b>::deserialize(&bytes[..]).unwrap();
+        assert!(
+            verify_aggregate_proof(&v_srs, &parameters.vk, &statements, &deserialized).unwrap()
         );
+
+        // Public inputs are bound to the aggregate
+        let mut wrong_statements = statements.clone();
+        wrong_statements[3][0] += &Fr::one();
+        assert!(!verify_aggregate_proof(
+            &v_srs,
+            &parameters.vk,
+            &wrong_statements,
+            &aggregate_proof
+        )
+        .unwrap());
+
+        // Malformed public inputs are rejected without panicking
+        let mut short_statements = statements.clone();
+        short_statements[0].pop();
+        assert!(verify_aggregate_proof(
+            &v_srs,
+            &parameters.vk,
+            &short_statements,
+            &aggregate_proof
+        )
+        .is_err());
+        assert!(verify_aggregate_proof(&v_srs, &parameters.vk, &[], &aggregate_proof).is_err());
+        assert!(aggregate_proofs::<Bls12_381, Blake2b>(
+            &srs,
+            &parameters.vk,
+            &statements[1..],
+            &proofs
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn groth16_aggregation_by_vk_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (parameters_1, statements_1, proofs_1) =
+            generate_proofs(NUM_PUBLIC_INPUTS, NUM_PROOFS / 2, &mut rng);
+        let (parameters_2, statements_2, proofs_2) =
+            generate_proofs(NUM_PUBLIC_INPUTS + 1, NUM_PROOFS / 4, &mut rng);
+        let (parameters_3, statements_3, proofs_3) =
+            generate_proofs(NUM_PUBLIC_INPUTS, NUM_PROOFS / 2, &mut rng);
+        let srs = setup_inner_product::<_, Blake2b, _>(&mut rng, NUM_PROOFS).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof = aggregate_proofs_by_vk::<Bls12_381, Blake2b>(
+            &srs,
+            &[
+                (&parameters_1.vk, &statements_1, &proofs_1),
+                (&parameters_2.vk, &statements_2, &proofs_2),
+                (&parameters_3.vk, &statements_3, &proofs_3),
+            ],
+        )
+        .unwrap();
+        assert!(verify_aggregate_proof_by_vk(
+            &v_srs,
+            &[
+                (&parameters_1.vk, &statements_1),
+                (&parameters_2.vk, &statements_2),
+                (&parameters_3.vk, &statements_3),
+            ],
+            &aggregate_proof,
+        )
+        .unwrap());
+
+        // Round trip through canonical serialization
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize(&mut bytes).unwrap();
+        let deserialized =
+            GroupedAggregateProof::<Bls12_381, Blake2b>::deserialize(&bytes[..]).unwrap();
+        assert!(verify_aggregate_proof_by_vk(
+            &v_srs,
+            &[
+                (&parameters_1.vk, &statements_1),
+                (&parameters_2.vk, &statements_2),
+                (&parameters_3.vk, &statements_3),
+            ],
+            &deserialized,
+        )
+        .unwrap());
+
+        // Proofs are checked against the verifying key of their own group
+        assert!(!verify_aggregate_proof_by_vk(
+            &v_srs,
+            &[
+                (&parameters_3.vk, &statements_1),
+                (&parameters_2.vk, &statements_2),
+                (&parameters_1.vk, &statements_3),
+            ],
+            &aggregate_proof,
+        )
+        .unwrap());
+
+        // Groups cannot be dropped
+        assert!(!verify_aggregate_proof_by_vk(
+            &v_srs,
+            &[
+                (&parameters_1.vk, &statements_1),
+                (&parameters_2.vk, &statements_2),
+            ],
+            &aggregate_proof,
+        )
+        .unwrap());
+
+        // Malformed verifying key
+        let mut empty_vk = parameters_1.vk.clone();
+        empty_vk.gamma_abc_g1.clear();
+        assert!(aggregate_proofs_by_vk::<Bls12_381, Blake2b>(
+            &srs,
+            &[(&empty_vk, &statements_1, &proofs_1)],
+        )
+        .is_err());
     }
-    let p2 = P::pairing(g_ic, vk.gamma_g2);
-    let p3 = P::pairing(proof.agg_c, vk.delta_g2);
 
-    let ppe_valid = proof.ip_ab.0 == (p1 * &p2) * &p3;
+    #[test]
+    fn groth16_aggregation_padding_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (parameters, statements, proofs) =
+            generate_proofs(NUM_PUBLIC_INPUTS, NUM_PROOFS - 3, &mut rng);
+        let srs = setup_inner_product::<_, Blake2b, _>(&mut rng, NUM_PROOFS).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b>(&srs, &parameters.vk, &statements, &proofs)
+                .unwrap();
+        assert!(
+            verify_aggregate_proof(&v_srs, &parameters.vk, &statements, &aggregate_proof).unwrap()
+        );
+
+        // The verifier takes the real number of proofs
+        assert!(!verify_aggregate_proof(
+            &v_srs,
+            &parameters.vk,
+            &statements[..statements.len() - 1],
+            &aggregate_proof
+        )
+        .unwrap());
+        let mut extra_statements = statements.clone();
+        extra_statements.push(statements[0].clone());
+        assert!(!verify_aggregate_proof(
+            &v_srs,
+            &parameters.vk,
+            &extra_statements,
+            &aggregate_proof
+        )
+        .unwrap());
 
-    Ok(tipa_proof_ab_valid && tipa_proof_c_valid && ppe_valid)
+        // More proofs than the SRS supports
+        let (parameters, statements, proofs) =
+            generate_proofs(NUM_PUBLIC_INPUTS, NUM_PROOFS + 1, &mut rng);
+        assert!(
+            aggregate_proofs::<Bls12_381, Blake2b>(&srs, &parameters.vk, &statements, &proofs)
+                .is_err()
+        );
+
+        // Groups are padded separately
+        let (parameters_1, statements_1, proofs_1) =
+            generate_proofs(NUM_PUBLIC_INPUTS, 3, &mut rng);
+        let (parameters_2, statements_2, proofs_2) =
+            generate_proofs(NUM_PUBLIC_INPUTS + 1, 1, &mut rng);
+        let aggregate_proof = aggregate_proofs_by_vk::<Bls12_381, Blake2b>(
+            &srs,
+            &[
+                (&parameters_1.vk, &statements_1, &proofs_1),
+                (&parameters_2.vk, &statements_2, &proofs_2),
+            ],
+        )
+        .unwrap();
+        assert!(verify_aggregate_proof_by_vk(
+            &v_srs,
+            &[
+                (&parameters_1.vk, &statements_1),
+                (&parameters_2.vk, &statements_2),
+            ],
+            &aggregate_proof,
+        )
+        .unwrap());
+    }
 }
@@ -1,19 +1,25 @@
-use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::polynomial::{
     univariate::DensePolynomial as UnivariatePolynomial, Polynomial, UVPolynomial,
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
 use ark_std::{end_timer, start_timer};
-use std::marker::PhantomData;
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{Display, Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
 
 use ark_std::rand::Rng;
 use digest::Digest;
 
 use crate::{
     tipa::{
+        powers_of_tau::{verify_powers_of_tau, PowersOfTau},
         structured_generators_scalar_power,
-        structured_scalar_message::{TIPAWithSSM, TIPAWithSSMProof},
+        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
         VerifierSRS, SRS,
     },
     Error,
@@ -23,7 +29,7 @@ use ark_dh_commitments::{
     identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
     DoublyHomomorphicCommitment,
 };
-use ark_inner_products::{ExtensionFieldElement, MultiexponentiationInnerProduct};
+use ark_inner_products::{CyclotomicField, ExtensionFieldElement, MultiexponentiationInnerProduct};
 
 pub mod transparent;
 
@@ -47,6 +53,60 @@ pub struct KZG<P: PairingEngine> {
     _pairing: PhantomData<P>,
 }
 
+// Powers of alpha over a second generator gamma_g, used to blind commitments with random
+// polynomials of degree `gamma_g_powers.len() - 1`. Commitments stay hiding for that many openings.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingKZGKey<P: PairingEngine> {
+    pub gamma_g_powers: Vec<P::G1Affine>,
+}
+
+// h^(alpha^(max_degree - d)) for each supported degree bound d
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DegreeBoundKZGKey<P: PairingEngine> {
+    pub max_degree: usize,
+    pub shifted_h: Vec<(usize, P::G2Projective)>,
+}
+
+impl<P: PairingEngine> DegreeBoundKZGKey<P> {
+    fn shifted_h(&self, degree_bound: usize) -> Result<&P::G2Projective, Error> {
+        match self.shifted_h.iter().find(|(d, _)| *d == degree_bound) {
+            Some((_, shifted_h)) => Ok(shifted_h),
+            None => Err(Box::new(DegreeBoundError::Unsupported(
+                degree_bound,
+                self.max_degree,
+            ))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DegreeBoundError {
+    // (degree bound, maximum degree)
+    Unsupported(usize, usize),
+    Exceeded,
+}
+
+impl ErrorTrait for DegreeBoundError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for DegreeBoundError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            DegreeBoundError::Unsupported(bound, max) => {
+                format!(
+                    "unsupported degree bound, maximum degree: {}, {}",
+                    bound, max
+                )
+            }
+            DegreeBoundError::Exceeded => "polynomial exceeds degree bound".to_string(),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 // Simple implementation of KZG polynomial commitment scheme
 impl<P: PairingEngine> KZG<P> {
     pub fn setup<R: Rng>(
@@ -55,10 +115,56 @@ impl<P: PairingEngine> KZG<P> {
     ) -> Result<(Vec<P::G1Affine>, VerifierSRS<P>), Error> {
         let alpha = <P::Fr>::rand(rng);
         let beta = <P::Fr>::rand(rng);
+        Ok(Self::setup_from_secrets(&alpha, &beta, degree))
+    }
+
+    // Same SRS as `setup` together with a hiding key for blinding polynomials of degree
+    // `hiding_bound` and a key for each of `degree_bounds`
+    pub fn setup_with_keys<R: Rng>(
+        rng: &mut R,
+        degree: usize,
+        hiding_bound: usize,
+        degree_bounds: &[usize],
+    ) -> Result<
+        (
+            Vec<P::G1Affine>,
+            VerifierSRS<P>,
+            HidingKZGKey<P>,
+            DegreeBoundKZGKey<P>,
+        ),
+        Error,
+    > {
+        let alpha = <P::Fr>::rand(rng);
+        let beta = <P::Fr>::rand(rng);
+        let (powers, v_srs) = Self::setup_from_secrets(&alpha, &beta, degree);
+        let hiding_key = Self::hiding_key(rng, &alpha, hiding_bound);
+        let mut shifted_h = Vec::new();
+        for degree_bound in degree_bounds {
+            if *degree_bound > degree {
+                return Err(Box::new(DegreeBoundError::Unsupported(
+                    *degree_bound,
+                    degree,
+                )));
+            }
+            let shift = alpha.pow(&[(degree - degree_bound) as u64]);
+            shifted_h.push((*degree_bound, v_srs.h.mul(shift.into_repr())));
+        }
+        let degree_key = DegreeBoundKZGKey {
+            max_degree: degree,
+            shifted_h,
+        };
+        Ok((powers, v_srs, hiding_key, degree_key))
+    }
+
+    fn setup_from_secrets(
+        alpha: &P::Fr,
+        beta: &P::Fr,
+        degree: usize,
+    ) -> (Vec<P::G1Affine>, VerifierSRS<P>) {
         let g = <P::G1Projective>::prime_subgroup_generator();
         let h = <P::G2Projective>::prime_subgroup_generator();
-        let g_alpha_powers = structured_generators_scalar_power(degree + 1, &g, &alpha);
-        Ok((
+        let g_alpha_powers = structured_generators_scalar_power(degree + 1, &g, alpha);
+        (
             <P as PairingEngine>::G1Projective::batch_normalization_into_affine(&g_alpha_powers),
             VerifierSRS {
                 g: g.clone(),
@@ -66,7 +172,17 @@ impl<P: PairingEngine> KZG<P> {
                 g_beta: g.mul(beta.into_repr()),
                 h_alpha: h.mul(alpha.into_repr()),
             },
-        ))
+        )
+    }
+
+    fn hiding_key<R: Rng>(rng: &mut R, alpha: &P::Fr, hiding_bound: usize) -> HidingKZGKey<P> {
+        let gamma_g =
+            <P::G1Projective>::prime_subgroup_generator().mul(<P::Fr>::rand(rng).into_repr());
+        HidingKZGKey {
+            gamma_g_powers: <P as PairingEngine>::G1Projective::batch_normalization_into_affine(
+                &structured_generators_scalar_power(hiding_bound + 1, &gamma_g, alpha),
+            ),
+        }
     }
 
     pub fn commit(
@@ -119,6 +235,76 @@ impl<P: PairingEngine> KZG<P> {
             v_srs.h_alpha.clone() - &v_srs.h.mul(point.into_repr()),
         ))
     }
+
+    // Commitment blinded by a random polynomial, which is returned for opening
+    pub fn commit_hiding<R: Rng>(
+        powers: &[P::G1Affine],
+        hiding_key: &HidingKZGKey<P>,
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        rng: &mut R,
+    ) -> Result<(P::G1Projective, UnivariatePolynomial<P::Fr>), Error> {
+        let blinding = UnivariatePolynomial::rand(hiding_key.gamma_g_powers.len() - 1, rng);
+        let com = Self::commit(powers, polynomial)?
+            + &Self::commit(&hiding_key.gamma_g_powers, &blinding)?;
+        Ok((com, blinding))
+    }
+
+    // Opening proof of a hiding commitment together with the evaluation of the blinding polynomial
+    pub fn open_hiding(
+        powers: &[P::G1Affine],
+        hiding_key: &HidingKZGKey<P>,
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        blinding: &UnivariatePolynomial<P::Fr>,
+        point: &P::Fr,
+    ) -> Result<(P::G1Projective, P::Fr), Error> {
+        let proof = Self::open(powers, polynomial, point)?
+            + &Self::open(&hiding_key.gamma_g_powers, blinding, point)?;
+        Ok((proof, blinding.evaluate(point)))
+    }
+
+    pub fn verify_hiding(
+        v_srs: &VerifierSRS<P>,
+        hiding_key: &HidingKZGKey<P>,
+        com: &P::G1Projective,
+        point: &P::Fr,
+        eval: &P::Fr,
+        proof: &(P::G1Projective, P::Fr),
+    ) -> Result<bool, Error> {
+        let (proof, blinding_eval) = proof;
+        let unblinded_com =
+            com.clone() - &hiding_key.gamma_g_powers[0].mul(blinding_eval.into_repr());
+        Self::verify(v_srs, &unblinded_com, point, eval, proof)
+    }
+
+    // Commitment together with a commitment to the polynomial shifted to the top of the SRS,
+    // which only exists if the degree is at most `degree_bound`
+    pub fn commit_with_degree_bound(
+        powers: &[P::G1Affine],
+        degree_key: &DegreeBoundKZGKey<P>,
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        degree_bound: usize,
+    ) -> Result<(P::G1Projective, P::G1Projective), Error> {
+        degree_key.shifted_h(degree_bound)?;
+        if polynomial.degree() > degree_bound {
+            return Err(Box::new(DegreeBoundError::Exceeded));
+        }
+        let com = Self::commit(powers, polynomial)?;
+        let shifted_com =
+            Self::commit(&powers[degree_key.max_degree - degree_bound..], polynomial)?;
+        Ok((com, shifted_com))
+    }
+
+    pub fn verify_degree_bound(
+        v_srs: &VerifierSRS<P>,
+        degree_key: &DegreeBoundKZGKey<P>,
+        com: &P::G1Projective,
+        shifted_com: &P::G1Projective,
+        degree_bound: usize,
+    ) -> Result<bool, Error> {
+        let shifted_h = degree_key.shifted_h(degree_bound)?;
+        Ok(P::pairing(shifted_com.clone(), v_srs.h.clone())
+            == P::pairing(com.clone(), shifted_h.clone()))
+    }
 }
 
 pub struct BivariatePolynomial<F: Field> {
@@ -142,18 +328,88 @@ impl<F: Field> BivariatePolynomial<F> {
     }
 }
 
-pub struct OpeningProof<P: PairingEngine, D: Digest> {
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct OpeningProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
     ip_proof: PolynomialEvaluationSecondTierIPAProof<P, D>,
     y_eval_comm: P::G1Projective,
     kzg_proof: P::G1Projective,
 }
 
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingOpeningProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
+    proof: OpeningProof<P, D>,
+    kzg_blinding_eval: P::Fr,
+}
+
+// Openings of a polynomial and of its shifted form at the same point
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct DegreeBoundedOpeningProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
+    proof: OpeningProof<P, D>,
+    shifted_proof: OpeningProof<P, D>,
+}
+
+// Openings of several polynomials at several points combined into a single opening proof
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchOpeningProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
+    quotient_comm: ExtensionFieldElement<P>,
+    opening_proof: OpeningProof<P, D>,
+}
+
+#[derive(Debug)]
+pub enum BatchOpeningError {
+    NoPolynomials,
+    NoPoints,
+    PointsNotDistinct,
+    // (polynomials, commitments)
+    CommitmentCountMismatch(usize, usize),
+    // (expected, found)
+    EvaluationCountInvalid(usize, usize),
+}
+
+impl ErrorTrait for BatchOpeningError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for BatchOpeningError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            BatchOpeningError::NoPolynomials => "no polynomials to open".to_string(),
+            BatchOpeningError::NoPoints => "no points to open at".to_string(),
+            BatchOpeningError::PointsNotDistinct => "opening points are not distinct".to_string(),
+            BatchOpeningError::CommitmentCountMismatch(polynomials, coms) => {
+                format!("polynomials, commitments: {}, {}", polynomials, coms)
+            }
+            BatchOpeningError::EvaluationCountInvalid(expected, found) => {
+                format!("expected evaluations, found: {}, {}", expected, found)
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 pub struct BivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
     _pairing: PhantomData<P>,
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
+impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
     pub fn setup<R: Rng>(
         rng: &mut R,
         x_degree: usize,
@@ -161,17 +417,65 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
     ) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
         let alpha = <P::Fr>::rand(rng);
         let beta = <P::Fr>::rand(rng);
+        Ok(Self::setup_from_secrets(&alpha, &beta, x_degree, y_degree))
+    }
+
+    // Same SRS as `setup` together with a KZG hiding key for the Y polynomials
+    pub fn setup_hiding<R: Rng>(
+        rng: &mut R,
+        x_degree: usize,
+        y_degree: usize,
+        hiding_bound: usize,
+    ) -> Result<(SRS<P>, Vec<P::G1Affine>, HidingKZGKey<P>), Error> {
+        let alpha = <P::Fr>::rand(rng);
+        let beta = <P::Fr>::rand(rng);
+        let (srs, kzg_srs) = Self::setup_from_secrets(&alpha, &beta, x_degree, y_degree);
+        Ok((
+            srs,
+            kzg_srs,
+            KZG::<P>::hiding_key(rng, &alpha, hiding_bound),
+        ))
+    }
+
+    fn setup_from_secrets(
+        alpha: &P::Fr,
+        beta: &P::Fr,
+        x_degree: usize,
+        y_degree: usize,
+    ) -> (SRS<P>, Vec<P::G1Affine>) {
         let g = <P::G1Projective>::prime_subgroup_generator();
         let h = <P::G2Projective>::prime_subgroup_generator();
         let kzg_srs = <P as PairingEngine>::G1Projective::batch_normalization_into_affine(
-            &structured_generators_scalar_power(y_degree + 1, &g, &alpha),
+            &structured_generators_scalar_power(y_degree + 1, &g, alpha),
         );
         let srs = SRS {
             g_alpha_powers: vec![g.clone()],
-            h_beta_powers: structured_generators_scalar_power(2 * x_degree + 1, &h, &beta),
+            h_beta_powers: structured_generators_scalar_power(2 * x_degree + 1, &h, beta),
             g_beta: g.mul(beta.into_repr()),
             h_alpha: h.mul(alpha.into_repr()),
         };
+        (srs, kzg_srs)
+    }
+
+    // Same SRS as `setup` with alpha and beta taken from powers-of-tau transcripts
+    pub fn setup_from_powers_of_tau<R: Rng>(
+        alpha: &PowersOfTau<P>,
+        beta: &PowersOfTau<P>,
+        x_degree: usize,
+        y_degree: usize,
+        rng: &mut R,
+    ) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
+        verify_powers_of_tau(alpha, beta, (y_degree + 1, 2 * x_degree + 1), rng)?;
+        let kzg_srs = alpha.g_powers[..y_degree + 1].to_vec();
+        let srs = SRS {
+            g_alpha_powers: vec![alpha.g_powers[0].into_projective()],
+            h_beta_powers: beta.h_powers[..2 * x_degree + 1]
+                .iter()
+                .map(|h| h.into_projective())
+                .collect(),
+            g_beta: beta.g_powers[1].into_projective(),
+            h_alpha: alpha.h_powers[1].into_projective(),
+        };
         Ok((srs, kzg_srs))
     }
 
@@ -199,12 +503,84 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
         ))
     }
 
+    // Same as `commit` with hiding KZG commitments to the Y polynomials. The blinding polynomials
+    // are returned for opening.
+    pub fn commit_hiding<R: Rng>(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        hiding_key: &HidingKZGKey<P>,
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        rng: &mut R,
+    ) -> Result<
+        (
+            ExtensionFieldElement<P>,
+            Vec<P::G1Projective>,
+            Vec<UnivariatePolynomial<P::Fr>>,
+        ),
+        Error,
+    > {
+        let (ip_srs, kzg_srs) = srs;
+        let (ck, _) = ip_srs.get_commitment_keys();
+        assert!(ck.len() >= bivariate_polynomial.y_polynomials.len());
+
+        let mut y_polynomial_coms = Vec::new();
+        let mut y_polynomial_blindings = Vec::new();
+        for y_polynomial in bivariate_polynomial
+            .y_polynomials
+            .iter()
+            .chain(vec![UnivariatePolynomial::zero()].iter().cycle())
+            .take(ck.len())
+        {
+            let (com, blinding) = KZG::<P>::commit_hiding(kzg_srs, hiding_key, y_polynomial, rng)?;
+            y_polynomial_coms.push(com);
+            y_polynomial_blindings.push(blinding);
+        }
+
+        Ok((
+            AFGHOCommitmentG1::<P>::commit(&ck, &y_polynomial_coms)?,
+            y_polynomial_coms,
+            y_polynomial_blindings,
+        ))
+    }
+
     pub fn open(
         srs: &(SRS<P>, Vec<P::G1Affine>),
         bivariate_polynomial: &BivariatePolynomial<P::Fr>,
         y_polynomial_comms: &Vec<P::G1Projective>,
         point: &(P::Fr, P::Fr),
     ) -> Result<OpeningProof<P, D>, Error> {
+        let (proof, _) =
+            Self::open_with_blinding(srs, None, bivariate_polynomial, y_polynomial_comms, point)?;
+        Ok(proof)
+    }
+
+    pub fn open_hiding(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        hiding_key: &HidingKZGKey<P>,
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        y_polynomial_comms: &Vec<P::G1Projective>,
+        y_polynomial_blindings: &[UnivariatePolynomial<P::Fr>],
+        point: &(P::Fr, P::Fr),
+    ) -> Result<HidingOpeningProof<P, D>, Error> {
+        let (proof, kzg_blinding_eval) = Self::open_with_blinding(
+            srs,
+            Some((hiding_key, y_polynomial_blindings)),
+            bivariate_polynomial,
+            y_polynomial_comms,
+            point,
+        )?;
+        Ok(HidingOpeningProof {
+            proof,
+            kzg_blinding_eval: kzg_blinding_eval.unwrap(),
+        })
+    }
+
+    fn open_with_blinding(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        hiding: Option<(&HidingKZGKey<P>, &[UnivariatePolynomial<P::Fr>])>,
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        y_polynomial_comms: &Vec<P::G1Projective>,
+        point: &(P::Fr, P::Fr),
+    ) -> Result<(OpeningProof<P, D>, Option<P::Fr>), Error> {
         let (x, y) = point;
         let (ip_srs, kzg_srs) = srs;
         let (ck_1, _) = ip_srs.get_commitment_keys();
@@ -236,13 +612,26 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
                     .sum()
             })
             .collect::<Vec<P::Fr>>();
-        let y_eval_comm = VariableBaseMSM::multi_scalar_mul(
+        let mut y_eval_comm = VariableBaseMSM::multi_scalar_mul(
             kzg_srs,
             &y_eval_coeffs
                 .iter()
                 .map(|b| b.into_repr())
                 .collect::<Vec<_>>(),
         );
+        // Blinding of the Y evaluation commitment is the same combination of the Y blindings
+        let y_eval_blinding = hiding.map(|(hiding_key, y_polynomial_blindings)| {
+            let blinding = y_polynomial_blindings
+                .iter()
+                .zip(&powers_of_x)
+                .fold(UnivariatePolynomial::zero(), |acc, (blinding, x_power)| {
+                    &acc + &(blinding * *x_power)
+                });
+            (hiding_key, blinding)
+        });
+        if let Some((hiding_key, blinding)) = &y_eval_blinding {
+            y_eval_comm += &KZG::<P>::commit(&hiding_key.gamma_g_powers, blinding)?;
+        }
         end_timer!(precomp_time);
 
         let ipa_time = start_timer!(|| "Computing IPA proof");
@@ -254,18 +643,25 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
             )?;
         end_timer!(ipa_time);
         let kzg_time = start_timer!(|| "Computing KZG opening proof");
-        let kzg_proof = KZG::<P>::open(
-            kzg_srs,
-            &UnivariatePolynomial::from_coefficients_slice(&y_eval_coeffs),
-            y,
-        )?;
+        let y_eval_polynomial = UnivariatePolynomial::from_coefficients_slice(&y_eval_coeffs);
+        let (kzg_proof, kzg_blinding_eval) = match &y_eval_blinding {
+            Some((hiding_key, blinding)) => {
+                let (kzg_proof, blinding_eval) =
+                    KZG::<P>::open_hiding(kzg_srs, hiding_key, &y_eval_polynomial, blinding, y)?;
+                (kzg_proof, Some(blinding_eval))
+            }
+            None => (KZG::<P>::open(kzg_srs, &y_eval_polynomial, y)?, None),
+        };
         end_timer!(kzg_time);
 
-        Ok(OpeningProof {
-            ip_proof,
-            y_eval_comm,
-            kzg_proof,
-        })
+        Ok((
+            OpeningProof {
+                ip_proof,
+                y_eval_comm,
+                kzg_proof,
+            },
+            kzg_blinding_eval,
+        ))
     }
 
     pub fn verify(
@@ -276,18 +672,154 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
         proof: &OpeningProof<P, D>,
     ) -> Result<bool, Error> {
         let (x, y) = point;
-        let ip_proof_valid =
-            PolynomialEvaluationSecondTierIPA::<P, D>::verify_with_structured_scalar_message(
-                v_srs,
-                &HomomorphicPlaceholderValue,
-                (com, &IdentityOutput(vec![proof.y_eval_comm.clone()])),
-                x,
-                &proof.ip_proof,
-            )?;
+        let ip_proof_valid = Self::verify_ip_proof(v_srs, com, x, proof)?;
         let kzg_proof_valid =
             KZG::<P>::verify(v_srs, &proof.y_eval_comm, y, eval, &proof.kzg_proof)?;
         Ok(ip_proof_valid && kzg_proof_valid)
     }
+
+    pub fn verify_hiding(
+        v_srs: &VerifierSRS<P>,
+        hiding_key: &HidingKZGKey<P>,
+        com: &ExtensionFieldElement<P>,
+        point: &(P::Fr, P::Fr),
+        eval: &P::Fr,
+        proof: &HidingOpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (x, y) = point;
+        let ip_proof_valid = Self::verify_ip_proof(v_srs, com, x, &proof.proof)?;
+        let kzg_proof_valid = KZG::<P>::verify_hiding(
+            v_srs,
+            hiding_key,
+            &proof.proof.y_eval_comm,
+            y,
+            eval,
+            &(proof.proof.kzg_proof, proof.kzg_blinding_eval),
+        )?;
+        Ok(ip_proof_valid && kzg_proof_valid)
+    }
+
+    fn verify_ip_proof(
+        v_srs: &VerifierSRS<P>,
+        com: &ExtensionFieldElement<P>,
+        x: &P::Fr,
+        proof: &OpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        PolynomialEvaluationSecondTierIPA::<P, D>::verify_with_structured_scalar_message(
+            v_srs,
+            &HomomorphicPlaceholderValue,
+            (com, &IdentityOutput(vec![proof.y_eval_comm.clone()])),
+            x,
+            &proof.ip_proof,
+        )
+    }
+
+    // Commitments to the polynomial and to X^(x_degree - x_bound) Y^(y_degree - y_bound) times it,
+    // where (x_degree, y_degree) are the degrees of the SRS. Opening both at a point chosen after
+    // the commitments shows the polynomial respects the degree bounds.
+    pub fn commit_with_degree_bounds(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        degree_bounds: (usize, usize),
+    ) -> Result<
+        (
+            (ExtensionFieldElement<P>, Vec<P::G1Projective>),
+            (ExtensionFieldElement<P>, Vec<P::G1Projective>),
+        ),
+        Error,
+    > {
+        let shifted_polynomial =
+            Self::shifted_polynomial(srs, bivariate_polynomial, degree_bounds)?;
+        Ok((
+            Self::commit(srs, bivariate_polynomial)?,
+            Self::commit(srs, &shifted_polynomial)?,
+        ))
+    }
+
+    pub fn open_with_degree_bounds(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        degree_bounds: (usize, usize),
+        y_polynomial_comms: &Vec<P::G1Projective>,
+        shifted_y_polynomial_comms: &Vec<P::G1Projective>,
+        point: &(P::Fr, P::Fr),
+    ) -> Result<DegreeBoundedOpeningProof<P, D>, Error> {
+        let shifted_polynomial =
+            Self::shifted_polynomial(srs, bivariate_polynomial, degree_bounds)?;
+        Ok(DegreeBoundedOpeningProof {
+            proof: Self::open(srs, bivariate_polynomial, y_polynomial_comms, point)?,
+            shifted_proof: Self::open(srs, &shifted_polynomial, shifted_y_polynomial_comms, point)?,
+        })
+    }
+
+    pub fn verify_with_degree_bounds(
+        v_srs: &VerifierSRS<P>,
+        degrees: (usize, usize),
+        degree_bounds: (usize, usize),
+        com: &ExtensionFieldElement<P>,
+        shifted_com: &ExtensionFieldElement<P>,
+        point: &(P::Fr, P::Fr),
+        eval: &P::Fr,
+        proof: &DegreeBoundedOpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (x_shift, y_shift) = Self::degree_shifts(degrees, degree_bounds)?;
+        let (x, y) = point;
+        let shifted_eval = x.pow(&[x_shift as u64]) * &y.pow(&[y_shift as u64]) * eval;
+        Ok(Self::verify(v_srs, com, point, eval, &proof.proof)?
+            && Self::verify(
+                v_srs,
+                shifted_com,
+                point,
+                &shifted_eval,
+                &proof.shifted_proof,
+            )?)
+    }
+
+    fn degree_shifts(
+        degrees: (usize, usize),
+        degree_bounds: (usize, usize),
+    ) -> Result<(usize, usize), Error> {
+        let (x_degree, y_degree) = degrees;
+        let (x_bound, y_bound) = degree_bounds;
+        if x_bound > x_degree {
+            return Err(Box::new(DegreeBoundError::Unsupported(x_bound, x_degree)));
+        }
+        if y_bound > y_degree {
+            return Err(Box::new(DegreeBoundError::Unsupported(y_bound, y_degree)));
+        }
+        Ok((x_degree - x_bound, y_degree - y_bound))
+    }
+
+    fn shifted_polynomial(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
+        degree_bounds: (usize, usize),
+    ) -> Result<BivariatePolynomial<P::Fr>, Error> {
+        let degrees = UnivariatePolynomialCommitment::<P, D>::parse_bivariate_degrees_from_srs(srs);
+        let (x_shift, y_shift) = Self::degree_shifts(degrees, degree_bounds)?;
+        let (x_bound, y_bound) = degree_bounds;
+        for (i, y_polynomial) in bivariate_polynomial.y_polynomials.iter().enumerate() {
+            if !y_polynomial.is_zero() && (i > x_bound || y_polynomial.degree() > y_bound) {
+                return Err(Box::new(DegreeBoundError::Exceeded));
+            }
+        }
+
+        let y_polynomials = vec![UnivariatePolynomial::zero(); x_shift]
+            .into_iter()
+            .chain(
+                bivariate_polynomial
+                    .y_polynomials
+                    .iter()
+                    .take(x_bound + 1)
+                    .map(|y_polynomial| {
+                        UnivariatePolynomial::from_coefficients_vec(
+                            [vec![P::Fr::zero(); y_shift], y_polynomial.coeffs.to_vec()].concat(),
+                        )
+                    }),
+            )
+            .collect();
+        Ok(BivariatePolynomial { y_polynomials })
+    }
 }
 
 pub struct UnivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
@@ -295,7 +827,10 @@ pub struct UnivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
+impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
     fn bivariate_degrees(univariate_degree: usize) -> (usize, usize) {
         //(((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two() - 1;
         let sqrt = (((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two();
@@ -340,6 +875,18 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
         BivariatePolynomialCommitment::<P, D>::setup(rng, x_degree, y_degree)
     }
 
+    pub fn setup_from_powers_of_tau<R: Rng>(
+        alpha: &PowersOfTau<P>,
+        beta: &PowersOfTau<P>,
+        degree: usize,
+        rng: &mut R,
+    ) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
+        let (x_degree, y_degree) = Self::bivariate_degrees(degree);
+        BivariatePolynomialCommitment::<P, D>::setup_from_powers_of_tau(
+            alpha, beta, x_degree, y_degree, rng,
+        )
+    }
+
     pub fn commit(
         srs: &(SRS<P>, Vec<P::G1Affine>),
         polynomial: &UnivariatePolynomial<P::Fr>,
@@ -381,6 +928,362 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
         let x = y.pow(&vec![(y_degree + 1) as u64]);
         BivariatePolynomialCommitment::verify(v_srs, com, &(x, y), eval, proof)
     }
+
+    pub fn setup_hiding<R: Rng>(
+        rng: &mut R,
+        degree: usize,
+        hiding_bound: usize,
+    ) -> Result<(SRS<P>, Vec<P::G1Affine>, HidingKZGKey<P>), Error> {
+        let (x_degree, y_degree) = Self::bivariate_degrees(degree);
+        BivariatePolynomialCommitment::<P, D>::setup_hiding(rng, x_degree, y_degree, hiding_bound)
+    }
+
+    pub fn commit_hiding<R: Rng>(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        hiding_key: &HidingKZGKey<P>,
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        rng: &mut R,
+    ) -> Result<
+        (
+            ExtensionFieldElement<P>,
+            Vec<P::G1Projective>,
+            Vec<UnivariatePolynomial<P::Fr>>,
+        ),
+        Error,
+    > {
+        let bivariate_degrees = Self::parse_bivariate_degrees_from_srs(srs);
+        BivariatePolynomialCommitment::<P, D>::commit_hiding(
+            srs,
+            hiding_key,
+            &Self::bivariate_form(bivariate_degrees, polynomial),
+            rng,
+        )
+    }
+
+    pub fn open_hiding(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        hiding_key: &HidingKZGKey<P>,
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        y_polynomial_comms: &Vec<P::G1Projective>,
+        y_polynomial_blindings: &[UnivariatePolynomial<P::Fr>],
+        point: &P::Fr,
+    ) -> Result<HidingOpeningProof<P, D>, Error> {
+        let (x_degree, y_degree) = Self::parse_bivariate_degrees_from_srs(srs);
+        let y = point.clone();
+        let x = point.pow(&vec![(y_degree + 1) as u64]);
+        BivariatePolynomialCommitment::open_hiding(
+            srs,
+            hiding_key,
+            &Self::bivariate_form((x_degree, y_degree), polynomial),
+            y_polynomial_comms,
+            y_polynomial_blindings,
+            &(x, y),
+        )
+    }
+
+    pub fn verify_hiding(
+        v_srs: &VerifierSRS<P>,
+        hiding_key: &HidingKZGKey<P>,
+        max_degree: usize,
+        com: &ExtensionFieldElement<P>,
+        point: &P::Fr,
+        eval: &P::Fr,
+        proof: &HidingOpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (_, y_degree) = Self::bivariate_degrees(max_degree);
+        let y = point.clone();
+        let x = y.pow(&vec![(y_degree + 1) as u64]);
+        BivariatePolynomialCommitment::verify_hiding(v_srs, hiding_key, com, &(x, y), eval, proof)
+    }
+
+    // Commitments to the polynomial and to X^(n - degree_bound) times it, where n is the largest
+    // degree supported by the SRS. Opening both at a point chosen after the commitments shows the
+    // polynomial has degree at most `degree_bound`.
+    pub fn commit_with_degree_bound(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        degree_bound: usize,
+    ) -> Result<
+        (
+            (ExtensionFieldElement<P>, Vec<P::G1Projective>),
+            (ExtensionFieldElement<P>, Vec<P::G1Projective>),
+        ),
+        Error,
+    > {
+        let bivariate_degrees = Self::parse_bivariate_degrees_from_srs(srs);
+        let shifted_polynomial =
+            Self::shifted_polynomial(bivariate_degrees, polynomial, degree_bound)?;
+        Ok((
+            Self::commit(srs, polynomial)?,
+            Self::commit(srs, &shifted_polynomial)?,
+        ))
+    }
+
+    pub fn open_with_degree_bound(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        degree_bound: usize,
+        y_polynomial_comms: &Vec<P::G1Projective>,
+        shifted_y_polynomial_comms: &Vec<P::G1Projective>,
+        point: &P::Fr,
+    ) -> Result<DegreeBoundedOpeningProof<P, D>, Error> {
+        let bivariate_degrees = Self::parse_bivariate_degrees_from_srs(srs);
+        let shifted_polynomial =
+            Self::shifted_polynomial(bivariate_degrees, polynomial, degree_bound)?;
+        Ok(DegreeBoundedOpeningProof {
+            proof: Self::open(srs, polynomial, y_polynomial_comms, point)?,
+            shifted_proof: Self::open(srs, &shifted_polynomial, shifted_y_polynomial_comms, point)?,
+        })
+    }
+
+    pub fn verify_with_degree_bound(
+        v_srs: &VerifierSRS<P>,
+        max_degree: usize,
+        degree_bound: usize,
+        com: &ExtensionFieldElement<P>,
+        shifted_com: &ExtensionFieldElement<P>,
+        point: &P::Fr,
+        eval: &P::Fr,
+        proof: &DegreeBoundedOpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        let shift = Self::degree_shift(Self::bivariate_degrees(max_degree), degree_bound)?;
+        let shifted_eval = point.pow(&[shift as u64]) * eval;
+        Ok(
+            Self::verify(v_srs, max_degree, com, point, eval, &proof.proof)?
+                && Self::verify(
+                    v_srs,
+                    max_degree,
+                    shifted_com,
+                    point,
+                    &shifted_eval,
+                    &proof.shifted_proof,
+                )?,
+        )
+    }
+
+    fn degree_shift(
+        bivariate_degrees: (usize, usize),
+        degree_bound: usize,
+    ) -> Result<usize, Error> {
+        let (x_degree, y_degree) = bivariate_degrees;
+        let degree = (x_degree + 1) * (y_degree + 1) - 1;
+        if degree_bound > degree {
+            return Err(Box::new(DegreeBoundError::Unsupported(
+                degree_bound,
+                degree,
+            )));
+        }
+        Ok(degree - degree_bound)
+    }
+
+    fn shifted_polynomial(
+        bivariate_degrees: (usize, usize),
+        polynomial: &UnivariatePolynomial<P::Fr>,
+        degree_bound: usize,
+    ) -> Result<UnivariatePolynomial<P::Fr>, Error> {
+        let shift = Self::degree_shift(bivariate_degrees, degree_bound)?;
+        if polynomial.degree() > degree_bound {
+            return Err(Box::new(DegreeBoundError::Exceeded));
+        }
+        Ok(UnivariatePolynomial::from_coefficients_vec(
+            [vec![P::Fr::zero(); shift], polynomial.coeffs.to_vec()].concat(),
+        ))
+    }
+
+    // Opens every polynomial at every point. With f = sum_i gamma^i f_i and Z vanishing on the
+    // points, the prover commits to q = f / Z and opens L = f - r(rho) - Z(rho) q to zero at rho,
+    // where r is the interpolation of f on the points. The verifier derives the commitment to L
+    // from the homomorphic commitments to f_i and q.
+    pub fn batch_open(
+        srs: &(SRS<P>, Vec<P::G1Affine>),
+        polynomials: &[UnivariatePolynomial<P::Fr>],
+        coms: &[ExtensionFieldElement<P>],
+        y_polynomial_comms: &[Vec<P::G1Projective>],
+        points: &[P::Fr],
+    ) -> Result<BatchOpeningProof<P, D>, Error> {
+        check_batch_inputs(polynomials.len(), coms.len(), points)?;
+        if y_polynomial_comms.len() != polynomials.len() {
+            return Err(Box::new(BatchOpeningError::CommitmentCountMismatch(
+                polynomials.len(),
+                y_polynomial_comms.len(),
+            )));
+        }
+        let evals = polynomials
+            .iter()
+            .map(|polynomial| points.iter().map(|z| polynomial.evaluate(z)).collect())
+            .collect::<Vec<Vec<P::Fr>>>();
+
+        let gamma = batch_opening_challenge::<P, D>(&canonical_bytes![coms, points, evals], &[])?;
+        let powers_of_gamma = structured_scalar_power(polynomials.len(), &gamma);
+        let combined = polynomials.iter().zip(&powers_of_gamma).fold(
+            UnivariatePolynomial::zero(),
+            |acc, (polynomial, gamma_i)| &acc + &(polynomial * *gamma_i),
+        );
+
+        let vanishing = vanishing_polynomial(points);
+        let quotient = &combined / &vanishing;
+        let (quotient_comm, quotient_y_comms) = Self::commit(srs, &quotient)?;
+        let rho = batch_opening_challenge::<P, D>(&canonical_bytes![gamma, quotient_comm], points)?;
+
+        let vanishing_eval = vanishing.evaluate(&rho);
+        let remainder_eval = combined.evaluate(&rho) - &(vanishing_eval * &quotient.evaluate(&rho));
+        let linearized = &(&combined - &(&quotient * vanishing_eval))
+            - &UnivariatePolynomial::from_coefficients_vec(vec![remainder_eval]);
+
+        // KZG commitments to the Y polynomials of L, the constant term committed as r(rho) g
+        let mut linearized_y_comms = quotient_y_comms
+            .iter()
+            .map(|comm| -comm.mul(vanishing_eval.into_repr()))
+            .collect::<Vec<P::G1Projective>>();
+        for (comms, gamma_i) in y_polynomial_comms.iter().zip(&powers_of_gamma) {
+            for (linearized_comm, comm) in linearized_y_comms.iter_mut().zip(comms) {
+                *linearized_comm += &comm.mul(gamma_i.into_repr());
+            }
+        }
+        linearized_y_comms[0] -= &srs.1[0].mul(remainder_eval.into_repr());
+
+        let opening_proof = Self::open(srs, &linearized, &linearized_y_comms, &rho)?;
+        Ok(BatchOpeningProof {
+            quotient_comm,
+            opening_proof,
+        })
+    }
+
+    // `evals[i][j]` is the evaluation of the i-th committed polynomial at `points[j]`
+    pub fn batch_verify(
+        v_srs: &VerifierSRS<P>,
+        max_degree: usize,
+        coms: &[ExtensionFieldElement<P>],
+        points: &[P::Fr],
+        evals: &[Vec<P::Fr>],
+        proof: &BatchOpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        check_batch_inputs(coms.len(), evals.len(), points)?;
+        for poly_evals in evals {
+            if poly_evals.len() != points.len() {
+                return Err(Box::new(BatchOpeningError::EvaluationCountInvalid(
+                    points.len(),
+                    poly_evals.len(),
+                )));
+            }
+        }
+
+        let gamma = batch_opening_challenge::<P, D>(&canonical_bytes![coms, points, evals], &[])?;
+        let rho =
+            batch_opening_challenge::<P, D>(&canonical_bytes![gamma, proof.quotient_comm], points)?;
+        let powers_of_gamma = structured_scalar_power(coms.len(), &gamma);
+
+        let combined_evals = (0..points.len())
+            .map(|j| {
+                evals
+                    .iter()
+                    .zip(&powers_of_gamma)
+                    .map(|(poly_evals, gamma_i)| poly_evals[j] * gamma_i)
+                    .sum()
+            })
+            .collect::<Vec<P::Fr>>();
+        let remainder_eval = interpolate_at(points, &combined_evals, &rho);
+        let vanishing_eval = vanishing_polynomial(points).evaluate(&rho);
+
+        // Commitment to L; a constant c is committed to as e(c g, h)
+        let mut linearized_com = ExtensionFieldElement::<P>(P::pairing(
+            v_srs.g.mul((-remainder_eval).into_repr()),
+            v_srs.h.clone(),
+        ));
+        for (com, gamma_i) in coms.iter().zip(&powers_of_gamma) {
+            let mut scaled_com = com.clone();
+            scaled_com *= *gamma_i;
+            linearized_com += scaled_com;
+        }
+        let mut scaled_quotient_comm = proof.quotient_comm.clone();
+        scaled_quotient_comm *= -vanishing_eval;
+        linearized_com += scaled_quotient_comm;
+
+        Self::verify(
+            v_srs,
+            max_degree,
+            &linearized_com,
+            &rho,
+            &P::Fr::zero(),
+            &proof.opening_proof,
+        )
+    }
+}
+
+fn check_batch_inputs<F: Field>(
+    polynomials: usize,
+    coms: usize,
+    points: &[F],
+) -> Result<(), Error> {
+    if polynomials == 0 {
+        return Err(Box::new(BatchOpeningError::NoPolynomials));
+    }
+    if coms != polynomials {
+        return Err(Box::new(BatchOpeningError::CommitmentCountMismatch(
+            polynomials,
+            coms,
+        )));
+    }
+    if points.is_empty() {
+        return Err(Box::new(BatchOpeningError::NoPoints));
+    }
+    for (j, z) in points.iter().enumerate() {
+        if points[..j].contains(z) {
+            return Err(Box::new(BatchOpeningError::PointsNotDistinct));
+        }
+    }
+    Ok(())
+}
+
+fn vanishing_polynomial<F: Field>(points: &[F]) -> UnivariatePolynomial<F> {
+    let mut coeffs = vec![F::one()];
+    for z in points {
+        // Multiply by (X - z)
+        coeffs.insert(0, F::zero());
+        for i in 0..coeffs.len() - 1 {
+            let shifted = coeffs[i + 1] * z;
+            coeffs[i] -= &shifted;
+        }
+    }
+    UnivariatePolynomial::from_coefficients_vec(coeffs)
+}
+
+// Evaluates at `x` the polynomial interpolating `evals` on the distinct `points`
+fn interpolate_at<F: Field>(points: &[F], evals: &[F], x: &F) -> F {
+    points
+        .iter()
+        .zip(evals)
+        .enumerate()
+        .map(|(j, (z_j, eval))| {
+            let (num, denom) = points
+                .iter()
+                .enumerate()
+                .filter(|(l, _)| *l != j)
+                .fold((F::one(), F::one()), |(num, denom), (_, z_l)| {
+                    (num * &(*x - z_l), denom * &(*z_j - z_l))
+                });
+            *eval * &num * &denom.inverse().unwrap()
+        })
+        .sum()
+}
+
+fn batch_opening_challenge<P: PairingEngine, D: Digest>(
+    transcript: &[u8],
+    excluded: &[P::Fr],
+) -> Result<P::Fr, Error> {
+    let mut counter_nonce: usize = 0;
+    let c = loop {
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+        hash_input.extend_from_slice(transcript);
+        if let Some(c) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
+            if !c.is_zero() && !excluded.contains(&c) {
+                break c;
+            }
+        };
+        counter_nonce += 1;
+    };
+    Ok(c)
 }
 
 #[cfg(test)]
@@ -395,6 +1298,12 @@ mod tests {
     //const UNIVARIATE_DEGREE: usize = 56;
     const UNIVARIATE_DEGREE: usize = 65535;
     //const UNIVARIATE_DEGREE: usize = 1048575;
+    const BATCH_DEGREE: usize = 63;
+    const KZG_DEGREE: usize = 15;
+    const HIDING_BOUND: usize = 1;
+    const DEGREE_BOUND: usize = 40;
+    const BATCH_POLYNOMIALS: usize = 4;
+    const BATCH_POINTS: usize = 3;
 
     type TestBivariatePolyCommitment = BivariatePolynomialCommitment<Bls12_381, Blake2b>;
     type TestUnivariatePolyCommitment = UnivariatePolynomialCommitment<Bls12_381, Blake2b>;
@@ -440,6 +1349,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bivariate_poly_commit_from_powers_of_tau_test() {
+        type G1 = <Bls12_381 as PairingEngine>::G1Projective;
+        type G2 = <Bls12_381 as PairingEngine>::G2Projective;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g = G1::prime_subgroup_generator();
+        let h = G2::prime_subgroup_generator();
+        let (alpha, beta) = (
+            <Bls12_381 as PairingEngine>::Fr::rand(&mut rng),
+            <Bls12_381 as PairingEngine>::Fr::rand(&mut rng),
+        );
+        let alpha_transcript = PowersOfTau::<Bls12_381> {
+            g_powers: G1::batch_normalization_into_affine(&structured_generators_scalar_power(
+                BIVARIATE_Y_DEGREE + 1,
+                &g,
+                &alpha,
+            )),
+            h_powers: G2::batch_normalization_into_affine(&structured_generators_scalar_power(
+                2, &h, &alpha,
+            )),
+        };
+        let beta_transcript = PowersOfTau::<Bls12_381> {
+            g_powers: G1::batch_normalization_into_affine(&structured_generators_scalar_power(
+                2, &g, &beta,
+            )),
+            h_powers: G2::batch_normalization_into_affine(&structured_generators_scalar_power(
+                2 * BIVARIATE_X_DEGREE + 1,
+                &h,
+                &beta,
+            )),
+        };
+        let srs = TestBivariatePolyCommitment::setup_from_powers_of_tau(
+            &alpha_transcript,
+            &beta_transcript,
+            BIVARIATE_X_DEGREE,
+            BIVARIATE_Y_DEGREE,
+            &mut rng,
+        )
+        .unwrap();
+        let v_srs = srs.0.get_verifier_key();
+
+        let mut y_polynomials = Vec::new();
+        for _ in 0..BIVARIATE_X_DEGREE + 1 {
+            let mut y_polynomial_coeffs = vec![];
+            for _ in 0..BIVARIATE_Y_DEGREE + 1 {
+                y_polynomial_coeffs.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            }
+            y_polynomials.push(UnivariatePolynomial::from_coefficients_slice(
+                &y_polynomial_coeffs,
+            ));
+        }
+        let bivariate_polynomial = BivariatePolynomial { y_polynomials };
+
+        let (com, y_polynomial_comms) =
+            TestBivariatePolyCommitment::commit(&srs, &bivariate_polynomial).unwrap();
+        let point = (UniformRand::rand(&mut rng), UniformRand::rand(&mut rng));
+        let eval_proof = TestBivariatePolyCommitment::open(
+            &srs,
+            &bivariate_polynomial,
+            &y_polynomial_comms,
+            &point,
+        )
+        .unwrap();
+        let eval = bivariate_polynomial.evaluate(&point);
+
+        assert!(
+            TestBivariatePolyCommitment::verify(&v_srs, &com, &point, &eval, &eval_proof).unwrap()
+        );
+    }
+
     // `cargo test univariate_poly_commit_test --release --features print-trace -- --ignored --nocapture`
     #[ignore]
     #[test]
@@ -476,4 +1456,219 @@ mod tests {
         )
         .unwrap());
     }
+
+    #[test]
+    fn univariate_poly_commit_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestUnivariatePolyCommitment::setup(&mut rng, BATCH_DEGREE).unwrap();
+        let v_srs = srs.0.get_verifier_key();
+
+        let mut polynomials = Vec::new();
+        for i in 0..BATCH_POLYNOMIALS {
+            let mut polynomial_coeffs = vec![];
+            for _ in 0..BATCH_DEGREE + 1 - i {
+                polynomial_coeffs.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            }
+            polynomials.push(UnivariatePolynomial::from_coefficients_vec(
+                polynomial_coeffs,
+            ));
+        }
+        let (coms, y_polynomial_comms): (Vec<_>, Vec<_>) = polynomials
+            .iter()
+            .map(|polynomial| TestUnivariatePolyCommitment::commit(&srs, polynomial).unwrap())
+            .unzip();
+
+        let points = (0..BATCH_POINTS)
+            .map(|_| UniformRand::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let proof = TestUnivariatePolyCommitment::batch_open(
+            &srs,
+            &polynomials,
+            &coms,
+            &y_polynomial_comms,
+            &points,
+        )
+        .unwrap();
+        let mut evals = polynomials
+            .iter()
+            .map(|polynomial| points.iter().map(|z| polynomial.evaluate(z)).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert!(TestUnivariatePolyCommitment::batch_verify(
+            &v_srs,
+            BATCH_DEGREE,
+            &coms,
+            &points,
+            &evals,
+            &proof
+        )
+        .unwrap());
+
+        // Wrong evaluation
+        evals[1][2] += <Bls12_381 as PairingEngine>::Fr::one();
+        assert!(!TestUnivariatePolyCommitment::batch_verify(
+            &v_srs,
+            BATCH_DEGREE,
+            &coms,
+            &points,
+            &evals,
+            &proof
+        )
+        .unwrap());
+
+        // Repeated point
+        assert!(TestUnivariatePolyCommitment::batch_open(
+            &srs,
+            &polynomials,
+            &coms,
+            &y_polynomial_comms,
+            &[points[0], points[0]],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn kzg_hiding_and_degree_bound_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let degree_bound = KZG_DEGREE / 2;
+        let (powers, v_srs, hiding_key, degree_key) =
+            KZG::<Bls12_381>::setup_with_keys(&mut rng, KZG_DEGREE, HIDING_BOUND, &[degree_bound])
+                .unwrap();
+        let polynomial = UnivariatePolynomial::rand(degree_bound, &mut rng);
+        let point = UniformRand::rand(&mut rng);
+        let eval = polynomial.evaluate(&point);
+
+        // Hiding commitment
+        let (com, blinding) =
+            KZG::<Bls12_381>::commit_hiding(&powers, &hiding_key, &polynomial, &mut rng).unwrap();
+        assert_ne!(com, KZG::<Bls12_381>::commit(&powers, &polynomial).unwrap());
+        let proof =
+            KZG::<Bls12_381>::open_hiding(&powers, &hiding_key, &polynomial, &blinding, &point)
+                .unwrap();
+        assert!(
+            KZG::<Bls12_381>::verify_hiding(&v_srs, &hiding_key, &com, &point, &eval, &proof)
+                .unwrap()
+        );
+        assert!(!KZG::<Bls12_381>::verify_hiding(
+            &v_srs,
+            &hiding_key,
+            &com,
+            &point,
+            &(eval + <Bls12_381 as PairingEngine>::Fr::one()),
+            &proof
+        )
+        .unwrap());
+
+        // Degree-bounded commitment
+        let (com, shifted_com) = KZG::<Bls12_381>::commit_with_degree_bound(
+            &powers,
+            &degree_key,
+            &polynomial,
+            degree_bound,
+        )
+        .unwrap();
+        assert!(KZG::<Bls12_381>::verify_degree_bound(
+            &v_srs,
+            &degree_key,
+            &com,
+            &shifted_com,
+            degree_bound
+        )
+        .unwrap());
+        assert!(!KZG::<Bls12_381>::verify_degree_bound(
+            &v_srs,
+            &degree_key,
+            &com,
+            &com,
+            degree_bound
+        )
+        .unwrap());
+        let large_polynomial = UnivariatePolynomial::rand(degree_bound + 1, &mut rng);
+        assert!(KZG::<Bls12_381>::commit_with_degree_bound(
+            &powers,
+            &degree_key,
+            &large_polynomial,
+            degree_bound
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn univariate_poly_commit_hiding_and_degree_bound_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ip_srs, kzg_srs, hiding_key) =
+            TestUnivariatePolyCommitment::setup_hiding(&mut rng, BATCH_DEGREE, HIDING_BOUND)
+                .unwrap();
+        let srs = (ip_srs, kzg_srs);
+        let v_srs = srs.0.get_verifier_key();
+        let polynomial = UnivariatePolynomial::rand(DEGREE_BOUND, &mut rng);
+        let point = UniformRand::rand(&mut rng);
+        let eval = polynomial.evaluate(&point);
+
+        // Hiding commitment
+        let (com, y_polynomial_comms, y_polynomial_blindings) =
+            TestUnivariatePolyCommitment::commit_hiding(&srs, &hiding_key, &polynomial, &mut rng)
+                .unwrap();
+        let proof = TestUnivariatePolyCommitment::open_hiding(
+            &srs,
+            &hiding_key,
+            &polynomial,
+            &y_polynomial_comms,
+            &y_polynomial_blindings,
+            &point,
+        )
+        .unwrap();
+        assert!(TestUnivariatePolyCommitment::verify_hiding(
+            &v_srs,
+            &hiding_key,
+            BATCH_DEGREE,
+            &com,
+            &point,
+            &eval,
+            &proof
+        )
+        .unwrap());
+
+        // Degree-bounded commitment
+        let ((com, y_polynomial_comms), (shifted_com, shifted_y_polynomial_comms)) =
+            TestUnivariatePolyCommitment::commit_with_degree_bound(&srs, &polynomial, DEGREE_BOUND)
+                .unwrap();
+        let proof = TestUnivariatePolyCommitment::open_with_degree_bound(
+            &srs,
+            &polynomial,
+            DEGREE_BOUND,
+            &y_polynomial_comms,
+            &shifted_y_polynomial_comms,
+            &point,
+        )
+        .unwrap();
+        assert!(TestUnivariatePolyCommitment::verify_with_degree_bound(
+            &v_srs,
+            BATCH_DEGREE,
+            DEGREE_BOUND,
+            &com,
+            &shifted_com,
+            &point,
+            &eval,
+            &proof
+        )
+        .unwrap());
+        // A tighter bound does not match the shifted commitment
+        assert!(!TestUnivariatePolyCommitment::verify_with_degree_bound(
+            &v_srs,
+            BATCH_DEGREE,
+            DEGREE_BOUND - 1,
+            &com,
+            &shifted_com,
+            &point,
+            &eval,
+            &proof
+        )
+        .unwrap());
+        assert!(TestUnivariatePolyCommitment::commit_with_degree_bound(
+            &srs,
+            &polynomial,
+            DEGREE_BOUND - 1
+        )
+        .is_err());
+    }
 }
@@ -1,8 +1,10 @@
 use ark_ec::PairingEngine;
 use ark_ff::{Field, Zero};
-use ark_poly::polynomial::{
-    univariate::DensePolynomial as UnivariatePolynomial, Polynomial, UVPolynomial,
+use ark_poly::{
+    polynomial::{univariate::DensePolynomial as UnivariatePolynomial, Polynomial, UVPolynomial},
+    DenseMultilinearExtension as MultilinearPolynomial,
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
 use ark_std::{end_timer, start_timer};
 use std::marker::PhantomData;
@@ -13,7 +15,7 @@ use digest::Digest;
 use crate::{
     gipa::GIPAProof,
     tipa::structured_scalar_message::{
-        structured_scalar_power, GIPAWithSSM, SSMPlaceholderCommitment,
+        structured_scalar_power, structured_scalar_tensor, GIPAWithSSM, SSMPlaceholderCommitment,
     },
     Error,
 };
@@ -24,7 +26,7 @@ use ark_dh_commitments::{
     DoublyHomomorphicCommitment,
 };
 use ark_inner_products::{
-    ExtensionFieldElement, MultiexponentiationInnerProduct, ScalarInnerProduct,
+    CyclotomicField, ExtensionFieldElement, MultiexponentiationInnerProduct, ScalarInnerProduct,
 };
 
 type PolynomialEvaluationSecondTierIPA<P, D> = GIPAWithSSM<
@@ -78,7 +80,11 @@ impl<F: Field> BivariatePolynomial<F> {
     }
 }
 
-pub struct OpeningProof<P: PairingEngine, D: Digest> {
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct OpeningProof<P: PairingEngine, D: Digest>
+where
+    P::Fqk: CyclotomicField,
+{
     second_tier_ip_proof: PolynomialEvaluationSecondTierIPAProof<P, D>,
     y_eval_comm: P::G1Projective,
     first_tier_ip_proof: PolynomialEvaluationFirstTierIPAProof<P, D>,
@@ -89,7 +95,10 @@ pub struct BivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
+impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
     pub fn setup<R: Rng>(
         rng: &mut R,
         x_degree: usize,
@@ -224,7 +233,10 @@ pub struct UnivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
+impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
     fn bivariate_degrees(univariate_degree: usize) -> (usize, usize) {
         //(((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two() - 1;
         let sqrt = (((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two();
@@ -316,12 +328,157 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
     }
 }
 
+// Commitment to the evaluations of a multilinear polynomial over the boolean hypercube. The low
+// variables index the first-tier (Pedersen) vectors and the high variables the second-tier
+// (AFGHO) vector, so openings are inner products with tensor-structured scalar messages.
+pub struct MultilinearPolynomialCommitment<P: PairingEngine, D: Digest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: PairingEngine, D: Digest> MultilinearPolynomialCommitment<P, D>
+where
+    P::Fqk: CyclotomicField,
+{
+    fn split_num_vars(num_vars: usize) -> (usize, usize) {
+        // Skew split between tiers to account for scalar IPA being less expensive than MIPP
+        let skew = if num_vars >= 6 { 2_usize } else { num_vars / 4 };
+        let x_vars = num_vars / 2 - skew;
+        (x_vars, num_vars - x_vars)
+    }
+
+    fn parse_num_vars_from_ck(ck: &(Vec<P::G1Projective>, Vec<P::G2Projective>)) -> (usize, usize) {
+        let x_vars = ck.1.len().trailing_zeros() as usize;
+        let y_vars = ck.0.len().trailing_zeros() as usize;
+        (x_vars, y_vars)
+    }
+
+    pub fn setup<R: Rng>(
+        rng: &mut R,
+        num_vars: usize,
+    ) -> Result<(Vec<P::G1Projective>, Vec<P::G2Projective>), Error> {
+        let (x_vars, y_vars) = Self::split_num_vars(num_vars);
+        BivariatePolynomialCommitment::<P, D>::setup(rng, (1 << x_vars) - 1, (1 << y_vars) - 1)
+    }
+
+    pub fn commit(
+        ck: &(Vec<P::G1Projective>, Vec<P::G2Projective>),
+        polynomial: &MultilinearPolynomial<P::Fr>,
+    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1Projective>), Error> {
+        let (first_tier_ck, second_tier_ck) = ck;
+        let (x_vars, y_vars) = Self::parse_num_vars_from_ck(ck);
+        assert_eq!(polynomial.num_vars, x_vars + y_vars);
+
+        // Create first-tier commitments to the evaluations for each assignment of the high variables
+        let y_evals_comms = polynomial
+            .evaluations
+            .chunks(first_tier_ck.len())
+            .map(|y_evals| {
+                PedersenCommitment::<<P as PairingEngine>::G1Projective>::commit(
+                    first_tier_ck,
+                    y_evals,
+                )
+            })
+            .collect::<Result<Vec<P::G1Projective>, Error>>()?;
+
+        // Create AFGHO commitment to first-tier commitments
+        Ok((
+            AFGHOCommitmentG1::<P>::commit(&second_tier_ck, &y_evals_comms)?,
+            y_evals_comms,
+        ))
+    }
+
+    pub fn open(
+        ck: &(Vec<P::G1Projective>, Vec<P::G2Projective>),
+        polynomial: &MultilinearPolynomial<P::Fr>,
+        y_evals_comms: &Vec<P::G1Projective>,
+        point: &[P::Fr],
+    ) -> Result<OpeningProof<P, D>, Error> {
+        let (first_tier_ck, second_tier_ck) = ck;
+        let (x_vars, y_vars) = Self::parse_num_vars_from_ck(ck);
+        assert_eq!(polynomial.num_vars, x_vars + y_vars);
+        assert_eq!(point.len(), x_vars + y_vars);
+        let (y_point, x_point) = point.split_at(y_vars);
+
+        let precomp_time = start_timer!(|| "Computing partial evaluation and Pedersen commitment");
+        let x_tensor = structured_scalar_tensor(x_point);
+        let mut y_eval_coeffs = vec![<P::Fr>::zero(); first_tier_ck.len()];
+        for (x_coeff, y_evals) in x_tensor
+            .iter()
+            .zip(polynomial.evaluations.chunks(first_tier_ck.len()))
+        {
+            for (coeff, eval) in y_eval_coeffs.iter_mut().zip(y_evals) {
+                *coeff += &(*x_coeff * eval);
+            }
+        }
+        let y_eval_comm = PedersenCommitment::<<P as PairingEngine>::G1Projective>::commit(
+            first_tier_ck,
+            &y_eval_coeffs,
+        )?;
+        end_timer!(precomp_time);
+
+        let ipa_time = start_timer!(|| "Computing second tier IPA opening proof");
+        let second_tier_ip_proof =
+            PolynomialEvaluationSecondTierIPA::<P, D>::prove_with_structured_scalar_message(
+                (y_evals_comms, &x_tensor),
+                (second_tier_ck, &HomomorphicPlaceholderValue),
+            )?;
+        end_timer!(ipa_time);
+
+        let first_tier_ipa_time = start_timer!(|| "Computing first tier IPA opening proof");
+        let y_tensor = structured_scalar_tensor(y_point);
+        let first_tier_ip_proof =
+            PolynomialEvaluationFirstTierIPA::<P, D>::prove_with_structured_scalar_message(
+                (&y_eval_coeffs, &y_tensor),
+                (first_tier_ck, &HomomorphicPlaceholderValue),
+            )?;
+        end_timer!(first_tier_ipa_time);
+
+        Ok(OpeningProof {
+            second_tier_ip_proof,
+            y_eval_comm,
+            first_tier_ip_proof,
+        })
+    }
+
+    pub fn verify(
+        ck: &(Vec<P::G1Projective>, Vec<P::G2Projective>),
+        com: &ExtensionFieldElement<P>,
+        point: &[P::Fr],
+        eval: &P::Fr,
+        proof: &OpeningProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (first_tier_ck, second_tier_ck) = ck;
+        let (x_vars, y_vars) = Self::parse_num_vars_from_ck(ck);
+        if point.len() != x_vars + y_vars {
+            return Ok(false);
+        }
+        let (y_point, x_point) = point.split_at(y_vars);
+        let second_tier_ip_proof_valid =
+            PolynomialEvaluationSecondTierIPA::<P, D>::verify_with_tensor_structured_scalar_message(
+                (second_tier_ck, &HomomorphicPlaceholderValue),
+                (com, &IdentityOutput(vec![proof.y_eval_comm.clone()])),
+                x_point,
+                &proof.second_tier_ip_proof,
+            )?;
+        let first_tier_ip_proof_valid =
+            PolynomialEvaluationFirstTierIPA::<P, D>::verify_with_tensor_structured_scalar_message(
+                (first_tier_ck, &HomomorphicPlaceholderValue),
+                (&proof.y_eval_comm, &IdentityOutput(vec![eval.clone()])),
+                y_point,
+                &proof.first_tier_ip_proof,
+            )?;
+        Ok(second_tier_ip_proof_valid && first_tier_ip_proof_valid)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
     use ark_ec::PairingEngine;
-    use ark_ff::UniformRand;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::MultilinearExtension;
     use ark_std::rand::{rngs::StdRng, SeedableRng};
     use blake2::Blake2b;
 
@@ -330,9 +487,11 @@ mod tests {
     //const UNIVARIATE_DEGREE: usize = 56;
     const UNIVARIATE_DEGREE: usize = 65535;
     //const UNIVARIATE_DEGREE: usize = 1048575;
+    const MULTILINEAR_NUM_VARS: usize = 8;
 
     type TestBivariatePolyCommitment = BivariatePolynomialCommitment<Bls12_381, Blake2b>;
     type TestUnivariatePolyCommitment = UnivariatePolynomialCommitment<Bls12_381, Blake2b>;
+    type TestMultilinearPolyCommitment = MultilinearPolynomialCommitment<Bls12_381, Blake2b>;
 
     #[test]
     fn transparent_bivariate_poly_commit_test() {
@@ -403,4 +562,41 @@ mod tests {
             TestUnivariatePolyCommitment::verify(&ck, &com, &point, &eval, &eval_proof).unwrap()
         );
     }
+
+    #[test]
+    fn transparent_multilinear_poly_commit_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ck = TestMultilinearPolyCommitment::setup(&mut rng, MULTILINEAR_NUM_VARS).unwrap();
+
+        let mut evaluations = vec![];
+        for _ in 0..(1 << MULTILINEAR_NUM_VARS) {
+            evaluations.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+        }
+        let polynomial =
+            MultilinearPolynomial::from_evaluations_vec(MULTILINEAR_NUM_VARS, evaluations);
+
+        // Commit to polynomial
+        let (com, y_evals_comms) = TestMultilinearPolyCommitment::commit(&ck, &polynomial).unwrap();
+
+        // Evaluate at challenge point
+        let point = (0..MULTILINEAR_NUM_VARS)
+            .map(|_| UniformRand::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let eval_proof =
+            TestMultilinearPolyCommitment::open(&ck, &polynomial, &y_evals_comms, &point).unwrap();
+        let eval = MultilinearExtension::evaluate(&polynomial, &point).unwrap();
+
+        // Verify proof
+        assert!(
+            TestMultilinearPolyCommitment::verify(&ck, &com, &point, &eval, &eval_proof).unwrap()
+        );
+        assert!(!TestMultilinearPolyCommitment::verify(
+            &ck,
+            &com,
+            &point,
+            &(eval + <Bls12_381 as PairingEngine>::Fr::one()),
+            &eval_proof
+        )
+        .unwrap());
+    }
 }
@@ -1,4 +1,4 @@
-use ark_ff::{to_bytes, Field, One};
+use ark_ff::{Field, One};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::rand::Rng;
 use ark_std::{end_timer, start_timer};
@@ -7,7 +7,7 @@ use std::{convert::TryInto, marker::PhantomData, ops::MulAssign};
 
 use crate::{mul_helper, Error, InnerProductArgumentError};
 use ark_dh_commitments::DoublyHomomorphicCommitment;
-use ark_inner_products::InnerProduct;
+use ark_inner_products::{InnerProduct, InnerProductArgument};
 use ark_std::cfg_iter;
 
 #[cfg(feature = "parallel")]
@@ -231,15 +231,14 @@ where
 
                 // Fiat-Shamir challenge
                 let mut counter_nonce: usize = 0;
-                let default_transcript = Default::default();
+                let default_transcript: LMC::Scalar = Default::default();
                 let transcript = r_transcript.last().unwrap_or(&default_transcript);
                 let (c, c_inv) = 'challenge: loop {
                     let mut hash_input = Vec::new();
                     hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-                    //TODO: Should use CanonicalSerialize instead of ToBytes
-                    hash_input.extend_from_slice(&to_bytes![
+                    hash_input.extend_from_slice(&canonical_bytes![
                         transcript, com_1.0, com_1.1, com_1.2, com_2.0, com_2.1, com_2.2
-                    ]?);
+                    ]);
                     let c: LMC::Scalar = u128::from_be_bytes(
                         D::digest(&hash_input).as_slice()[0..16].try_into().unwrap(),
                     )
@@ -323,14 +322,14 @@ where
         for (com_1, com_2) in proof.r_commitment_steps.iter().rev() {
             // Fiat-Shamir challenge
             let mut counter_nonce: usize = 0;
-            let default_transcript = Default::default();
+            let default_transcript: LMC::Scalar = Default::default();
             let transcript = r_transcript.last().unwrap_or(&default_transcript);
             let (c, c_inv) = 'challenge: loop {
                 let mut hash_input = Vec::new();
                 hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-                hash_input.extend_from_slice(&to_bytes![
+                hash_input.extend_from_slice(&canonical_bytes![
                     transcript, com_1.0, com_1.1, com_1.2, com_2.0, com_2.1, com_2.2
-                ]?);
+                ]);
                 let c: LMC::Scalar = u128::from_be_bytes(
                     D::digest(&hash_input).as_slice()[0..16].try_into().unwrap(),
                 )
@@ -406,6 +405,74 @@ where
     }
 }
 
+impl<IP, LMC, RMC, IPC, D> InnerProductArgument for GIPA<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    type InnerProduct = IP;
+    type ProverKey = (Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key);
+    type VerifierKey = (Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key);
+    type Commitment = (LMC::Output, RMC::Output, IPC::Output);
+    type Proof = GIPAProof<IP, LMC, RMC, IPC, D>;
+
+    fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error> {
+        let ck = Self::setup(rng, size)?;
+        Ok((ck.clone(), ck))
+    }
+
+    fn commit(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+    ) -> Result<Self::Commitment, Error> {
+        let t = IP::inner_product(left, right)?;
+        Ok((
+            LMC::commit(&pk.0, left)?,
+            RMC::commit(&pk.1, right)?,
+            IPC::commit(&vec![pk.2.clone()], &vec![t])?,
+        ))
+    }
+
+    fn prove(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+        com: &Self::Commitment,
+    ) -> Result<Self::Proof, Error> {
+        let t = IP::inner_product(left, right)?;
+        Self::prove(
+            (left, right, &t),
+            (&pk.0, &pk.1, &pk.2),
+            (&com.0, &com.1, &com.2),
+        )
+    }
+
+    fn verify(
+        vk: &Self::VerifierKey,
+        com: &Self::Commitment,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        Self::verify((&vk.0, &vk.1, &vk.2), (&com.0, &com.1, &com.2), proof)
+    }
+}
+
 impl<IP, LMC, RMC, IPC, D> Clone for GIPAProof<IP, LMC, RMC, IPC, D>
 where
     D: Digest,
@@ -443,7 +510,9 @@ mod tests {
     use blake2::Blake2b;
 
     use ark_dh_commitments::{
-        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        afgho16::{
+            AFGHOCommitmentG1, AFGHOCommitmentG2, HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2,
+        },
         identity::IdentityCommitment,
         pedersen::PedersenCommitment,
         random_generators,
@@ -487,6 +556,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hiding_commitment_pairing_inner_product_test() {
+        type HGC1 = HidingAFGHOCommitmentG1<Bls12_381>;
+        type HGC2 = HidingAFGHOCommitmentG2<Bls12_381>;
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC =
+            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+        type PairingGIPA = GIPA<IP, HGC1, HGC2, IPC, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        // Plain GIPA opens the folded messages, so hiding commitments are only used unblinded
+        let com_a = HGC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = HGC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+
+        let proof = PairingGIPA::prove(
+            (&m_a, &m_b, &t[0]),
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &com_t),
+        )
+        .unwrap();
+
+        assert!(
+            PairingGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof,).unwrap()
+        );
+    }
+
     #[test]
     fn multiexponentiation_inner_product_test() {
         type IP = MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>;
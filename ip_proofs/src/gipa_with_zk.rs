@@ -0,0 +1,774 @@
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer};
+use digest::Digest;
+use std::{convert::TryInto, marker::PhantomData, ops::MulAssign};
+
+use crate::{gipa, mul_helper, Error, InnerProductArgumentError};
+use ark_dh_commitments::DoublyHomomorphicCommitment;
+use ark_inner_products::InnerProduct;
+use ark_std::cfg_iter;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// Zero-knowledge GIPA: every commitment carries a blinding term r * h for a per-group
+// blinding base h, the cross terms of each round are blinded with fresh randomness and the
+// folded messages are never opened. The last step is a sigma protocol for knowledge of
+// (a, b, r_a, r_b, r_t) with com_a = ck_a * a + r_a h_a, com_b = ck_b * b + r_b h_b and
+// com_t = ck_t * <a, b> + r_t h_t.
+// Blinding bases (h_a, h_b, h_t) of the left, right and inner product commitments
+pub type BlindingBases<LMC, RMC, IPC> = (
+    <LMC as DoublyHomomorphicCommitment>::Output,
+    <RMC as DoublyHomomorphicCommitment>::Output,
+    <IPC as DoublyHomomorphicCommitment>::Output,
+);
+
+pub struct GIPA<IP, LMC, RMC, IPC, D> {
+    _inner_product: PhantomData<IP>,
+    _left_commitment: PhantomData<LMC>,
+    _right_commitment: PhantomData<RMC>,
+    _inner_product_commitment: PhantomData<IPC>,
+    _digest: PhantomData<D>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) r_commitment_steps: Vec<(
+        (LMC::Output, RMC::Output, IPC::Output),
+        (LMC::Output, RMC::Output, IPC::Output),
+    )>,
+    // Sigma protocol: first message (com_a', com_b', com_t_1', com_t_2') and responses
+    pub(crate) sigma_com: (LMC::Output, RMC::Output, IPC::Output, IPC::Output),
+    pub(crate) z_base: (LMC::Message, RMC::Message),
+    pub(crate) tau: (LMC::Scalar, LMC::Scalar, LMC::Scalar),
+    _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+#[derive(Clone)]
+pub struct GIPAAux<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub r_transcript: Vec<LMC::Scalar>,
+    pub ck_base: (LMC::Key, RMC::Key),
+    _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+impl<IP, LMC, RMC, IPC, D> GIPA<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+    IP::LeftMessage: UniformRand,
+    IP::RightMessage: UniformRand,
+{
+    // Returns commitment keys and blinding bases (h_a, h_b, h_t)
+    pub fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<
+        (
+            (Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key),
+            (LMC::Output, RMC::Output, IPC::Output),
+        ),
+        Error,
+    > {
+        let ck_a = LMC::setup(rng, size)?;
+        let ck_b = RMC::setup(rng, size)?;
+        let ck_t = IPC::setup(rng, 1)?.pop().unwrap();
+        let h = Self::setup_blinding_bases(rng, &ck_t)?;
+        Ok(((ck_a, ck_b, ck_t), h))
+    }
+
+    // Blinding bases (h_a, h_b, h_t) for commitments under the inner product key ck_t
+    pub fn setup_blinding_bases<R: Rng>(
+        rng: &mut R,
+        ck_t: &IPC::Key,
+    ) -> Result<BlindingBases<LMC, RMC, IPC>, Error> {
+        let h1 = <IP::LeftMessage>::rand(rng);
+        let h2 = <IP::RightMessage>::rand(rng);
+        let h_a = LMC::commit(&LMC::setup(rng, 1)?, &vec![h1.clone()])?;
+        let h_b = RMC::commit(&RMC::setup(rng, 1)?, &vec![h2.clone()])?;
+        let h_t = IPC::commit(&vec![ck_t.clone()], &vec![IP::inner_product(&[h1], &[h2])?])?;
+        Ok((h_a, h_b, h_t))
+    }
+
+    // Blinded commitments to left, right and their inner product, with the blinding scalars
+    pub fn init_commit<R: Rng>(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        rng: &mut R,
+    ) -> Result<
+        (
+            (LMC::Output, RMC::Output, IPC::Output),
+            (LMC::Scalar, LMC::Scalar, LMC::Scalar),
+        ),
+        Error,
+    > {
+        let r_a = LMC::Scalar::rand(rng);
+        let r_b = LMC::Scalar::rand(rng);
+        let r_t = LMC::Scalar::rand(rng);
+        let com = Self::_commit(values, ck, h, (&r_a, &r_b, &r_t))?;
+        Ok((com, (r_a, r_b, r_t)))
+    }
+
+    pub fn prove<R: Rng>(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        randomness: (&LMC::Scalar, &LMC::Scalar, &LMC::Scalar),
+        rng: &mut R,
+    ) -> Result<GIPAProof<IP, LMC, RMC, IPC, D>, Error> {
+        if values.0.len().count_ones() != 1 {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                values.0.len(),
+                values.1.len(),
+            )));
+        }
+        if Self::_commit(values, ck, h, randomness)?
+            != (com.0.clone(), com.1.clone(), com.2.clone())
+        {
+            return Err(Box::new(InnerProductArgumentError::InnerProductInvalid));
+        }
+
+        let (proof, _) = Self::prove_with_aux(
+            values,
+            (ck.0, ck.1, &vec![ck.2.clone()]),
+            h,
+            randomness,
+            rng,
+        )?;
+        Ok(proof)
+    }
+
+    pub fn verify(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<bool, Error> {
+        if ck.0.len().count_ones() != 1 || ck.0.len() != ck.1.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                ck.0.len(),
+                ck.1.len(),
+            )));
+        }
+        // Calculate transcript
+        let (base_com, transcript) = Self::_compute_recursive_challenges(
+            (com.0.clone(), com.1.clone(), com.2.clone()),
+            proof,
+        )?;
+
+        // Calculate base commitment keys
+        let (ck_a_base, ck_b_base) =
+            gipa::GIPA::<IP, LMC, RMC, IPC, D>::_compute_final_commitment_keys(ck, &transcript)?;
+        // Verify knowledge of an opening of the base commitment
+        let default_transcript = Default::default();
+        Self::_verify_base_sigma(
+            (&ck_a_base, &ck_b_base, &vec![ck.2.clone()]),
+            h,
+            base_com,
+            transcript.first().unwrap_or(&default_transcript),
+            proof,
+        )
+    }
+
+    pub fn prove_with_aux<R: Rng>(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &[IPC::Key]),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        randomness: (&LMC::Scalar, &LMC::Scalar, &LMC::Scalar),
+        rng: &mut R,
+    ) -> Result<
+        (
+            GIPAProof<IP, LMC, RMC, IPC, D>,
+            GIPAAux<IP, LMC, RMC, IPC, D>,
+        ),
+        Error,
+    > {
+        let (m_a, m_b) = values;
+        if m_a.len().count_ones() != 1 || m_a.len() != m_b.len() {
+            // Power of 2 length
+            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
+                m_a.len(),
+                m_b.len(),
+            )));
+        }
+        let (ck_a, ck_b, ck_t) = ck;
+        Self::_prove(
+            (m_a.to_vec(), m_b.to_vec()),
+            (ck_a.to_vec(), ck_b.to_vec(), ck_t.to_vec()),
+            h,
+            (*randomness.0, *randomness.1, *randomness.2),
+            rng,
+        )
+    }
+
+    // Returns vector of recursive commitments and transcripts in reverse order
+    fn _prove<R: Rng>(
+        values: (Vec<IP::LeftMessage>, Vec<IP::RightMessage>),
+        ck: (Vec<LMC::Key>, Vec<RMC::Key>, Vec<IPC::Key>),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        randomness: (LMC::Scalar, LMC::Scalar, LMC::Scalar),
+        rng: &mut R,
+    ) -> Result<
+        (
+            GIPAProof<IP, LMC, RMC, IPC, D>,
+            GIPAAux<IP, LMC, RMC, IPC, D>,
+        ),
+        Error,
+    > {
+        let (mut m_a, mut m_b) = values;
+        let (mut ck_a, mut ck_b, ck_t) = ck;
+        let (mut r_a, mut r_b, mut r_t) = randomness;
+        let mut r_commitment_steps = Vec::new();
+        let mut r_transcript = Vec::new();
+        let (m_base, ck_base) = 'recurse: loop {
+            let recurse = start_timer!(|| format!("Recurse round size {}", m_a.len()));
+            if m_a.len() == 1 {
+                // base case
+                break 'recurse (
+                    (m_a[0].clone(), m_b[0].clone()),
+                    (ck_a[0].clone(), ck_b[0].clone()),
+                );
+            } else {
+                // recursive step
+                // Recurse with problem of half size
+                let split = m_a.len() / 2;
+
+                let m_a_1 = &m_a[split..];
+                let m_a_2 = &m_a[..split];
+                let ck_a_1 = &ck_a[..split];
+                let ck_a_2 = &ck_a[split..];
+
+                let m_b_1 = &m_b[..split];
+                let m_b_2 = &m_b[split..];
+                let ck_b_1 = &ck_b[split..];
+                let ck_b_2 = &ck_b[..split];
+
+                // Fresh blinding for both cross terms
+                let rho_1 = (
+                    LMC::Scalar::rand(rng),
+                    LMC::Scalar::rand(rng),
+                    LMC::Scalar::rand(rng),
+                );
+                let rho_2 = (
+                    LMC::Scalar::rand(rng),
+                    LMC::Scalar::rand(rng),
+                    LMC::Scalar::rand(rng),
+                );
+
+                let cl = start_timer!(|| "Commit L");
+                let com_1 = (
+                    LMC::commit(ck_a_1, m_a_1)? + mul_helper(h.0, &rho_1.0),
+                    RMC::commit(ck_b_1, m_b_1)? + mul_helper(h.1, &rho_1.1),
+                    IPC::commit(&ck_t, &vec![IP::inner_product(m_a_1, m_b_1)?])?
+                        + mul_helper(h.2, &rho_1.2),
+                );
+                end_timer!(cl);
+                let cr = start_timer!(|| "Commit R");
+                let com_2 = (
+                    LMC::commit(ck_a_2, m_a_2)? + mul_helper(h.0, &rho_2.0),
+                    RMC::commit(ck_b_2, m_b_2)? + mul_helper(h.1, &rho_2.1),
+                    IPC::commit(&ck_t, &vec![IP::inner_product(m_a_2, m_b_2)?])?
+                        + mul_helper(h.2, &rho_2.2),
+                );
+                end_timer!(cr);
+
+                // Fiat-Shamir challenge
+                let default_transcript = Default::default();
+                let transcript = r_transcript.last().unwrap_or(&default_transcript);
+                let (c, c_inv) = Self::_compute_round_challenge(transcript, (&com_1, &com_2))?;
+
+                // Set up values for next step of recursion
+                let rescale_m1 = start_timer!(|| "Rescale M1");
+                m_a = cfg_iter!(m_a_1)
+                    .map(|a| mul_helper(a, &c))
+                    .zip(m_a_2)
+                    .map(|(a_1, a_2)| a_1 + a_2.clone())
+                    .collect::<Vec<LMC::Message>>();
+                end_timer!(rescale_m1);
+
+                let rescale_m2 = start_timer!(|| "Rescale M2");
+                m_b = cfg_iter!(m_b_2)
+                    .map(|b| mul_helper(b, &c_inv))
+                    .zip(m_b_1)
+                    .map(|(b_1, b_2)| b_1 + b_2.clone())
+                    .collect::<Vec<RMC::Message>>();
+                end_timer!(rescale_m2);
+
+                let rescale_ck1 = start_timer!(|| "Rescale CK1");
+                ck_a = cfg_iter!(ck_a_2)
+                    .map(|a| mul_helper(a, &c_inv))
+                    .zip(ck_a_1)
+                    .map(|(a_1, a_2)| a_1 + a_2.clone())
+                    .collect::<Vec<LMC::Key>>();
+                end_timer!(rescale_ck1);
+
+                let rescale_ck2 = start_timer!(|| "Rescale CK2");
+                ck_b = cfg_iter!(ck_b_1)
+                    .map(|b| mul_helper(b, &c))
+                    .zip(ck_b_2)
+                    .map(|(b_1, b_2)| b_1 + b_2.clone())
+                    .collect::<Vec<RMC::Key>>();
+                end_timer!(rescale_ck2);
+
+                // Blinding of the folded commitments
+                r_a = r_a + c * rho_1.0 + c_inv * rho_2.0;
+                r_b = r_b + c * rho_1.1 + c_inv * rho_2.1;
+                r_t = r_t + c * rho_1.2 + c_inv * rho_2.2;
+
+                r_commitment_steps.push((com_1, com_2));
+                r_transcript.push(c);
+                end_timer!(recurse);
+            }
+        };
+
+        // Sigma protocol on the base commitment in place of opening it
+        let (a, b) = m_base;
+        let (ck_a_base, ck_b_base) = ck_base.clone();
+        let d_a = <IP::LeftMessage>::rand(rng);
+        let d_b = <IP::RightMessage>::rand(rng);
+        let delta_a = LMC::Scalar::rand(rng);
+        let delta_b = LMC::Scalar::rand(rng);
+        let delta_1 = LMC::Scalar::rand(rng);
+        let delta_2 = LMC::Scalar::rand(rng);
+        let cross = IP::inner_product(&[d_a.clone()], &[b.clone()])?
+            + IP::inner_product(&[a.clone()], &[d_b.clone()])?;
+        let sigma_com = (
+            LMC::commit(&[ck_a_base], &[d_a.clone()])? + mul_helper(h.0, &delta_a),
+            RMC::commit(&[ck_b_base], &[d_b.clone()])? + mul_helper(h.1, &delta_b),
+            IPC::commit(&ck_t, &vec![cross])? + mul_helper(h.2, &delta_1),
+            IPC::commit(
+                &ck_t,
+                &vec![IP::inner_product(&[d_a.clone()], &[d_b.clone()])?],
+            )? + mul_helper(h.2, &delta_2),
+        );
+        let default_transcript = Default::default();
+        let x = Self::_compute_sigma_challenge(
+            r_transcript.last().unwrap_or(&default_transcript),
+            &sigma_com,
+        )?;
+        let z_base = (d_a + mul_helper(&a, &x), d_b + mul_helper(&b, &x));
+        let tau = (
+            delta_a + x * r_a,
+            delta_b + x * r_b,
+            delta_2 + x * delta_1 + x * x * r_t,
+        );
+
+        r_transcript.reverse();
+        r_commitment_steps.reverse();
+        Ok((
+            GIPAProof {
+                r_commitment_steps,
+                sigma_com,
+                z_base,
+                tau,
+                _gipa: PhantomData,
+            },
+            GIPAAux {
+                r_transcript,
+                ck_base,
+                _gipa: PhantomData,
+            },
+        ))
+    }
+
+    // Helper function used to calculate recursive challenges from proof execution (transcript in reverse)
+    pub fn verify_recursive_challenge_transcript(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>), Error> {
+        Self::_compute_recursive_challenges((com.0.clone(), com.1.clone(), com.2.clone()), proof)
+    }
+
+    fn _commit(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        randomness: (&LMC::Scalar, &LMC::Scalar, &LMC::Scalar),
+    ) -> Result<(LMC::Output, RMC::Output, IPC::Output), Error> {
+        let t = IP::inner_product(values.0, values.1)?;
+        Ok((
+            LMC::commit(ck.0, values.0)? + mul_helper(h.0, randomness.0),
+            RMC::commit(ck.1, values.1)? + mul_helper(h.1, randomness.1),
+            IPC::commit(&vec![ck.2.clone()], &vec![t])? + mul_helper(h.2, randomness.2),
+        ))
+    }
+
+    fn _compute_round_challenge(
+        transcript: &LMC::Scalar,
+        com: (
+            &(LMC::Output, RMC::Output, IPC::Output),
+            &(LMC::Output, RMC::Output, IPC::Output),
+        ),
+    ) -> Result<(LMC::Scalar, LMC::Scalar), Error> {
+        let (com_1, com_2) = com;
+        let mut counter_nonce: usize = 0;
+        let (c, c_inv) = 'challenge: loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            hash_input.extend_from_slice(&canonical_bytes![
+                transcript, com_1.0, com_1.1, com_1.2, com_2.0, com_2.1, com_2.2
+            ]);
+            let c: LMC::Scalar =
+                u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                    .into();
+            if let Some(c_inv) = c.inverse() {
+                // Optimization for multiexponentiation to rescale G2 elements with 128-bit challenge
+                // Swap 'c' and 'c_inv' since can't control bit size of c_inv
+                break 'challenge (c_inv, c);
+            }
+            counter_nonce += 1;
+        };
+        Ok((c, c_inv))
+    }
+
+    fn _compute_sigma_challenge(
+        transcript: &LMC::Scalar,
+        sigma_com: &(LMC::Output, RMC::Output, IPC::Output, IPC::Output),
+    ) -> Result<LMC::Scalar, Error> {
+        let mut counter_nonce: usize = 0;
+        let x = 'challenge: loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            hash_input.extend_from_slice(&canonical_bytes![
+                transcript,
+                sigma_com.0,
+                sigma_com.1,
+                sigma_com.2,
+                sigma_com.3
+            ]);
+            let x: LMC::Scalar =
+                u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap())
+                    .into();
+            if !x.is_zero() {
+                break 'challenge x;
+            }
+            counter_nonce += 1;
+        };
+        Ok(x)
+    }
+
+    fn _compute_recursive_challenges(
+        com: (LMC::Output, RMC::Output, IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>), Error> {
+        let (mut com_a, mut com_b, mut com_t) = com;
+        let mut r_transcript = Vec::new();
+        for (com_1, com_2) in proof.r_commitment_steps.iter().rev() {
+            // Fiat-Shamir challenge
+            let default_transcript = Default::default();
+            let transcript = r_transcript.last().unwrap_or(&default_transcript);
+            let (c, c_inv) = Self::_compute_round_challenge(transcript, (com_1, com_2))?;
+
+            com_a = mul_helper(&com_1.0, &c) + com_a.clone() + mul_helper(&com_2.0, &c_inv);
+            com_b = mul_helper(&com_1.1, &c) + com_b.clone() + mul_helper(&com_2.1, &c_inv);
+            com_t = mul_helper(&com_1.2, &c) + com_t.clone() + mul_helper(&com_2.2, &c_inv);
+
+            r_transcript.push(c);
+        }
+        r_transcript.reverse();
+        Ok(((com_a, com_b, com_t), r_transcript))
+    }
+
+    pub(crate) fn _verify_base_sigma(
+        base_ck: (&LMC::Key, &RMC::Key, &Vec<IPC::Key>),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        base_com: (LMC::Output, RMC::Output, IPC::Output),
+        transcript: &LMC::Scalar,
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<bool, Error> {
+        let (com_a, com_b, com_t) = base_com;
+        let (ck_a_base, ck_b_base, ck_t) = base_ck;
+        let (sigma_a, sigma_b, sigma_t_1, sigma_t_2) = proof.sigma_com.clone();
+        let (z_a, z_b) = proof.z_base.clone();
+        let (tau_a, tau_b, tau_t) = proof.tau;
+        let x = Self::_compute_sigma_challenge(transcript, &proof.sigma_com)?;
+
+        let t_base = vec![IP::inner_product(&[z_a.clone()], &[z_b.clone()])?];
+        let a_valid = LMC::commit(&[ck_a_base.clone()], &[z_a])? + mul_helper(h.0, &tau_a)
+            == sigma_a + mul_helper(&com_a, &x);
+        let b_valid = RMC::commit(&[ck_b_base.clone()], &[z_b])? + mul_helper(h.1, &tau_b)
+            == sigma_b + mul_helper(&com_b, &x);
+        let t_valid = IPC::commit(&ck_t, &t_base)? + mul_helper(h.2, &tau_t)
+            == sigma_t_2 + mul_helper(&sigma_t_1, &x) + mul_helper(&com_t, &(x * x));
+
+        Ok(a_valid && b_valid && t_valid)
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> Clone for GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: Digest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    fn clone(&self) -> Self {
+        GIPAProof {
+            r_commitment_steps: self.r_commitment_steps.clone(),
+            sigma_com: self.sigma_com.clone(),
+            z_base: self.z_base.clone(),
+            tau: self.tau.clone(),
+            _gipa: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::PairingEngine;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    use ark_dh_commitments::{
+        afgho16::{
+            AFGHOCommitmentG1, AFGHOCommitmentG2, HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2,
+        },
+        identity::IdentityCommitment,
+        pedersen::PedersenCommitment,
+        random_generators,
+    };
+    use ark_inner_products::{
+        ExtensionFieldElement, MultiexponentiationInnerProduct, PairingInnerProduct,
+    };
+
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn pairing_inner_product_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC =
+            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ((ck_a, ck_b, ck_t), (h_a, h_b, h_t)) =
+            PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let ((com_a, com_b, com_t), (r_a, r_b, r_t)) = PairingGIPA::init_commit(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        let proof = PairingGIPA::prove(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            (&r_a, &r_b, &r_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(PairingGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            &proof,
+        )
+        .unwrap());
+
+        // Messages of a length other than a power of two are rejected
+        assert!(PairingGIPA::prove_with_aux(
+            (&m_a[..3], &m_b[..3]),
+            (&ck_a[..3], &ck_b[..3], &[ck_t.clone()]),
+            (&h_a, &h_b, &h_t),
+            (&r_a, &r_b, &r_t),
+            &mut rng,
+        )
+        .is_err());
+
+        // The blinded commitment to T is not the inner product itself
+        let t = IP::inner_product(&m_a, &m_b).unwrap();
+        assert!(com_t != IPC::commit(&vec![ck_t.clone()], &vec![t]).unwrap());
+
+        // A proof does not verify for a different inner product commitment
+        let com_t_other = com_t.clone() + h_t.clone();
+        assert!(!PairingGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t_other),
+            &proof,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn hiding_commitment_pairing_inner_product_test() {
+        type HGC1 = HidingAFGHOCommitmentG1<Bls12_381>;
+        type HGC2 = HidingAFGHOCommitmentG2<Bls12_381>;
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC =
+            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+        type PairingGIPA = GIPA<IP, HGC1, HGC2, IPC, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ((ck_a, ck_b, ck_t), (_, _, h_t)) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        // Blind with the generators of the hiding commitments
+        let h_a = HGC1::blinding_generator().unwrap();
+        let h_b = HGC2::blinding_generator().unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let r_a = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+        let r_b = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+        let r_t = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+        let com_a = HGC1::commit_with_randomness(&ck_a, &m_a, &r_a).unwrap();
+        let com_b = HGC2::commit_with_randomness(&ck_b, &m_b, &r_b).unwrap();
+        let t = IP::inner_product(&m_a, &m_b).unwrap();
+        let com_t = IPC::commit(&vec![ck_t.clone()], &vec![t]).unwrap() + mul_helper(&h_t, &r_t);
+
+        let proof = PairingGIPA::prove(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            (&r_a, &r_b, &r_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(PairingGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            &proof,
+        )
+        .unwrap());
+
+        // Unblinded commitments do not match the proof
+        let plain_com_a = HGC1::commit(&ck_a, &m_a).unwrap();
+        assert!(!PairingGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&plain_com_a, &com_b, &com_t),
+            &proof,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn multiexponentiation_inner_product_test() {
+        type IP = MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>;
+        type IPC = IdentityCommitment<
+            <Bls12_381 as PairingEngine>::G1Projective,
+            <Bls12_381 as PairingEngine>::Fr,
+        >;
+        type MultiExpGIPA = GIPA<IP, GC1, SC1, IPC, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ((ck_a, ck_b, ck_t), (h_a, h_b, h_t)) =
+            MultiExpGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let mut m_b = Vec::new();
+        for _ in 0..TEST_SIZE {
+            m_b.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+        }
+        let ((com_a, com_b, com_t), (r_a, r_b, r_t)) = MultiExpGIPA::init_commit(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        let proof = MultiExpGIPA::prove(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            (&r_a, &r_b, &r_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(MultiExpGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            &proof,
+        )
+        .unwrap());
+
+        // Wrong blinding scalar does not open the commitment
+        assert!(MultiExpGIPA::prove(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            (&r_a, &r_b, &(r_t + r_a)),
+            &mut rng,
+        )
+        .is_err());
+    }
+}
@@ -5,8 +5,20 @@ use std::{
     ops::MulAssign,
 };
 
+// Fiat-Shamir transcript bytes. Unlike `to_bytes!`, canonical serialization normalizes projective
+// points, so a verifier hashing a deserialized proof derives the same challenges as the prover.
+macro_rules! canonical_bytes {
+    ($($x:expr),*) => ({
+        use ark_serialize::CanonicalSerialize as _;
+        let mut buf = Vec::new();
+        $($x.serialize(&mut buf)?;)*
+        buf
+    });
+}
+
 pub mod applications;
 pub mod gipa;
+pub mod gipa_with_zk;
 pub mod tipa;
 
 pub type Error = Box<dyn ErrorTrait>;
@@ -41,3 +53,53 @@ impl Display for InnerProductArgumentError {
         write!(f, "{}", msg)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{gipa::GIPA, tipa::TIPA};
+    use ark_bls12_381::Bls12_381;
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        random_generators,
+    };
+    use ark_dory::dory::DORY;
+    use ark_ec::PairingEngine;
+    use ark_inner_products::{ExtensionFieldElement, InnerProductArgument, PairingInnerProduct};
+    use ark_sipp::SIPP;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    type IP = PairingInnerProduct<Bls12_381>;
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type IPC =
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+
+    const TEST_SIZE: usize = 8;
+
+    fn pairing_inner_product_argument<A: InnerProductArgument<InnerProduct = IP>>() {
+        // Same statement for every backend: only the key sampling depends on A
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let mut m_b_other = m_b.clone();
+        m_b_other.reverse();
+
+        let (pk, vk) = A::setup(&mut rng, TEST_SIZE).unwrap();
+        let com = A::commit(&pk, &m_a, &m_b).unwrap();
+        let proof = A::prove(&pk, &m_a, &m_b, &com).unwrap();
+        assert!(A::verify(&vk, &com, &proof).unwrap());
+
+        let com_other = A::commit(&pk, &m_a, &m_b_other).unwrap();
+        assert!(!A::verify(&vk, &com_other, &proof).unwrap());
+    }
+
+    #[test]
+    fn cross_backend_pairing_inner_product_test() {
+        pairing_inner_product_argument::<GIPA<IP, GC1, GC2, IPC, Blake2b>>();
+        pairing_inner_product_argument::<TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>>();
+        pairing_inner_product_argument::<DORY<IP, GC1, GC2, IPC, Blake2b>>();
+        pairing_inner_product_argument::<SIPP<Bls12_381, Blake2b>>();
+    }
+}
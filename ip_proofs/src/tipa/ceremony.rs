@@ -0,0 +1,280 @@
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
+use digest::Digest;
+use std::convert::TryInto;
+
+use crate::{
+    tipa::{
+        powers_of_tau::{g1_powers_consistent, g2_powers_consistent, PowersOfTauError},
+        SRS,
+    },
+    Error,
+};
+
+// Updatable ceremony for the TIPA SRS. Each participant multiplies alpha by a fresh secret `a`
+// and beta by a fresh secret `b`, publishing the new SRS together with g^a, h^b and Schnorr
+// proofs of knowledge of `a` and `b`. The SRS is sound as long as one participant discarded
+// their secrets.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<P: PairingEngine> {
+    pub g_a: P::G1Projective,
+    pub h_b: P::G2Projective,
+    // Schnorr proofs (commitment, response) of knowledge of a and b
+    pub pok_a: (P::G1Projective, P::Fr),
+    pub pok_b: (P::G2Projective, P::Fr),
+}
+
+impl<P: PairingEngine> SRS<P> {
+    // Starting point of a ceremony (alpha = beta = 1) for vectors of length `size`
+    pub fn ceremony_start(size: usize) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(Box::new(PowersOfTauError::SizeInvalid(size)));
+        }
+        let g = <P::G1Projective>::prime_subgroup_generator();
+        let h = <P::G2Projective>::prime_subgroup_generator();
+        Ok(SRS {
+            g_alpha_powers: vec![g; 2 * size - 1],
+            h_beta_powers: vec![h; 2 * size - 1],
+            g_beta: g,
+            h_alpha: h,
+        })
+    }
+
+    pub fn contribute<R: Rng, D: Digest>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self, ContributionProof<P>), Error> {
+        let a = nonzero_scalar::<P, R>(rng);
+        let b = nonzero_scalar::<P, R>(rng);
+        let g = self.g_alpha_powers[0];
+        let h = self.h_beta_powers[0];
+
+        let next = SRS {
+            g_alpha_powers: rescale_powers(&self.g_alpha_powers, &a),
+            h_beta_powers: rescale_powers(&self.h_beta_powers, &b),
+            g_beta: self.g_beta.mul(b.into_repr()),
+            h_alpha: self.h_alpha.mul(a.into_repr()),
+        };
+
+        let g_a = g.mul(a.into_repr());
+        let h_b = h.mul(b.into_repr());
+        let k_a = P::Fr::rand(rng);
+        let k_b = P::Fr::rand(rng);
+        let r_a = g.mul(k_a.into_repr());
+        let r_b = h.mul(k_b.into_repr());
+        let e = contribution_challenge::<P, D>(self, (&g_a, &h_b), (&r_a, &r_b))?;
+
+        Ok((
+            next,
+            ContributionProof {
+                g_a,
+                h_b,
+                pok_a: (r_a, k_a + e * a),
+                pok_b: (r_b, k_b + e * b),
+            },
+        ))
+    }
+
+    // Checks that `next` is `self` updated by the secrets behind `proof`
+    pub fn verify_contribution<R: Rng, D: Digest>(
+        &self,
+        next: &Self,
+        proof: &ContributionProof<P>,
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let g = self.g_alpha_powers[0];
+        let h = self.h_beta_powers[0];
+        if next.g_alpha_powers.len() != self.g_alpha_powers.len()
+            || next.h_beta_powers.len() != self.h_beta_powers.len()
+            || next.g_alpha_powers[0] != g
+            || next.h_beta_powers[0] != h
+            || proof.g_a.is_zero()
+            || proof.h_b.is_zero()
+        {
+            return Ok(false);
+        }
+
+        // Knowledge of a and b
+        let (r_a, s_a) = &proof.pok_a;
+        let (r_b, s_b) = &proof.pok_b;
+        let e = contribution_challenge::<P, D>(self, (&proof.g_a, &proof.h_b), (r_a, r_b))?;
+        let pok_valid = g.mul(s_a.into_repr()) == *r_a + proof.g_a.mul(e.into_repr())
+            && h.mul(s_b.into_repr()) == *r_b + proof.h_b.mul(e.into_repr());
+
+        // alpha and beta were multiplied by a and b
+        let update_valid = P::pairing(proof.g_a, self.h_alpha) == P::pairing(g, next.h_alpha)
+            && P::pairing(self.g_beta, proof.h_b) == P::pairing(next.g_beta, h);
+
+        // Updated powers are consecutive powers of the new alpha and beta
+        let powers_valid = g1_powers_consistent::<P, R>(
+            &<P::G1Projective>::batch_normalization_into_affine(&next.g_alpha_powers),
+            &h.into_affine(),
+            &next.h_alpha.into_affine(),
+            rng,
+        ) && g2_powers_consistent::<P, R>(
+            &<P::G2Projective>::batch_normalization_into_affine(&next.h_beta_powers),
+            &g.into_affine(),
+            &next.g_beta.into_affine(),
+            rng,
+        );
+
+        Ok(pok_valid && update_valid && powers_valid)
+    }
+
+    // Checks a chain of contributions starting from `self`
+    pub fn verify_contributions<R: Rng, D: Digest>(
+        &self,
+        contributions: &[(Self, ContributionProof<P>)],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let mut prev = self;
+        for (next, proof) in contributions {
+            if !prev.verify_contribution::<R, D>(next, proof, rng)? {
+                return Ok(false);
+            }
+            prev = next;
+        }
+        Ok(true)
+    }
+}
+
+fn rescale_powers<G: ProjectiveCurve>(powers: &[G], s: &G::ScalarField) -> Vec<G> {
+    let mut pow_s = G::ScalarField::one();
+    powers
+        .iter()
+        .map(|p| {
+            let rescaled = p.mul(pow_s.into_repr());
+            pow_s *= *s;
+            rescaled
+        })
+        .collect()
+}
+
+fn nonzero_scalar<P: PairingEngine, R: Rng>(rng: &mut R) -> P::Fr {
+    loop {
+        let s = P::Fr::rand(rng);
+        if !s.is_zero() {
+            break s;
+        }
+    }
+}
+
+fn contribution_challenge<P: PairingEngine, D: Digest>(
+    prev: &SRS<P>,
+    update: (&P::G1Projective, &P::G2Projective),
+    pok: (&P::G1Projective, &P::G2Projective),
+) -> Result<P::Fr, Error> {
+    // Canonical encoding, so that the challenge of a published contribution is reproducible
+    // from its deserialized (normalized) points
+    let mut hash_input = Vec::new();
+    prev.g_beta.serialize(&mut hash_input)?;
+    prev.h_alpha.serialize(&mut hash_input)?;
+    update.0.serialize(&mut hash_input)?;
+    update.1.serialize(&mut hash_input)?;
+    pok.0.serialize(&mut hash_input)?;
+    pok.1.serialize(&mut hash_input)?;
+    Ok(u128::from_be_bytes(D::digest(&hash_input).as_slice()[0..16].try_into().unwrap()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    use crate::tipa::TIPA;
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        random_generators, DoublyHomomorphicCommitment,
+    };
+    use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type IP = PairingInnerProduct<Bls12_381>;
+    type IPC =
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+    type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+
+    const TEST_SIZE: usize = 8;
+    const PARTICIPANTS: usize = 3;
+
+    #[test]
+    fn ceremony_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        assert!(SRS::<Bls12_381>::ceremony_start(0).is_err());
+        let start = SRS::<Bls12_381>::ceremony_start(TEST_SIZE).unwrap();
+        let mut contributions = Vec::new();
+        for _ in 0..PARTICIPANTS {
+            let prev = contributions.last().map(|(srs, _)| srs).unwrap_or(&start);
+            let (next, proof) = prev.contribute::<_, Blake2b>(&mut rng).unwrap();
+            contributions.push((next, proof));
+        }
+        assert!(start
+            .verify_contributions::<_, Blake2b>(&contributions, &mut rng)
+            .unwrap());
+
+        // The resulting SRS works for TIPA
+        let srs = contributions.last().unwrap().0.clone();
+        let ck_t = IPC::setup(&mut rng, 1).unwrap().pop().unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        assert!(PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &proof).unwrap());
+    }
+
+    #[test]
+    fn invalid_contribution_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let start = SRS::<Bls12_381>::ceremony_start(TEST_SIZE).unwrap();
+        let (next, proof) = start.contribute::<_, Blake2b>(&mut rng).unwrap();
+        assert!(start
+            .verify_contribution::<_, Blake2b>(&next, &proof, &mut rng)
+            .unwrap());
+
+        // Contributions are published serialized
+        let mut bytes = Vec::new();
+        next.serialize(&mut bytes).unwrap();
+        proof.serialize(&mut bytes).unwrap();
+        let mut reader = &bytes[..];
+        let published_next = SRS::<Bls12_381>::deserialize(&mut reader).unwrap();
+        let published_proof = ContributionProof::<Bls12_381>::deserialize(&mut reader).unwrap();
+        assert!(start
+            .verify_contribution::<_, Blake2b>(&published_next, &published_proof, &mut rng)
+            .unwrap());
+
+        // Tampered power
+        let mut bad_next = next.clone();
+        let g = bad_next.g_alpha_powers[0];
+        bad_next.g_alpha_powers[3] += &g;
+        assert!(!start
+            .verify_contribution::<_, Blake2b>(&bad_next, &proof, &mut rng)
+            .unwrap());
+
+        // Proof for a different update
+        let (other_next, other_proof) = start.contribute::<_, Blake2b>(&mut rng).unwrap();
+        assert!(!start
+            .verify_contribution::<_, Blake2b>(&next, &other_proof, &mut rng)
+            .unwrap());
+
+        // Contribution not built on the previous SRS
+        let (after_other, after_other_proof) =
+            other_next.contribute::<_, Blake2b>(&mut rng).unwrap();
+        assert!(!start
+            .verify_contributions::<_, Blake2b>(
+                &[(next, proof), (after_other, after_other_proof)],
+                &mut rng
+            )
+            .unwrap());
+    }
+}
@@ -1,5 +1,5 @@
 use ark_ec::{msm::FixedBaseMSM, PairingEngine, ProjectiveCurve};
-use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand, Zero};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::polynomial::{univariate::DensePolynomial, UVPolynomial};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::rand::Rng;
@@ -13,13 +13,18 @@ use crate::{
     Error,
 };
 use ark_dh_commitments::{
-    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
-    pedersen::PedersenCommitment,
+    afgho16::{
+        AFGHOCommitmentG1, AFGHOCommitmentG2, HidingAFGHOCommitmentG1, HidingAFGHOCommitmentG2,
+    },
+    pedersen::{HidingPedersenCommitment, PedersenCommitment},
     DoublyHomomorphicCommitment,
 };
-use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct};
+use ark_inner_products::{InnerProduct, InnerProductArgument, MultiexponentiationInnerProduct};
 
+pub mod ceremony;
+pub mod powers_of_tau;
 pub mod structured_scalar_message;
+pub mod tipa_with_zk;
 
 //TODO: Could generalize: Don't need TIPA over G1 and G2, would work with G1 and G1 or over different pairing engines
 pub trait TIPACompatibleSetup {}
@@ -27,6 +32,9 @@ pub trait TIPACompatibleSetup {}
 impl<G: ProjectiveCurve> TIPACompatibleSetup for PedersenCommitment<G> {}
 impl<P: PairingEngine> TIPACompatibleSetup for AFGHOCommitmentG1<P> {}
 impl<P: PairingEngine> TIPACompatibleSetup for AFGHOCommitmentG2<P> {}
+impl<G: ProjectiveCurve> TIPACompatibleSetup for HidingPedersenCommitment<G> {}
+impl<P: PairingEngine> TIPACompatibleSetup for HidingAFGHOCommitmentG1<P> {}
+impl<P: PairingEngine> TIPACompatibleSetup for HidingAFGHOCommitmentG2<P> {}
 
 //TODO: May need to add "reverse" MultiexponentiationInnerProduct to allow for MIP with G2 messages (because TIP hard-coded G1 left and G2 right)
 pub struct TIPA<IP, LMC, RMC, IPC, P, D> {
@@ -58,7 +66,7 @@ where
     RMC::Output: MulAssign<LMC::Scalar>,
     IPC::Output: MulAssign<LMC::Scalar>,
 {
-    gipa_proof: GIPAProof<IP, LMC, RMC, IPC, D>,
+    pub(crate) gipa_proof: GIPAProof<IP, LMC, RMC, IPC, D>,
     final_ck: (LMC::Key, RMC::Key),
     final_ck_proof: (P::G2Projective, P::G1Projective),
     _pair: PhantomData<P>,
@@ -93,7 +101,7 @@ where
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SRS<P: PairingEngine> {
     pub g_alpha_powers: Vec<P::G1Projective>,
     pub h_beta_powers: Vec<P::G2Projective>,
@@ -101,7 +109,7 @@ pub struct SRS<P: PairingEngine> {
     pub h_alpha: P::G2Projective,
 }
 
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct VerifierSRS<P: PairingEngine> {
     pub g: P::G1Projective,
     pub h: P::G2Projective,
@@ -117,6 +125,16 @@ impl<P: PairingEngine> SRS<P> {
         (ck_1, ck_2)
     }
 
+    // Prefix of the SRS for vectors of length `size`, at most the length the SRS was set up for
+    pub fn truncate(&self, size: usize) -> Self {
+        SRS {
+            g_alpha_powers: self.g_alpha_powers[..2 * size - 1].to_vec(),
+            h_beta_powers: self.h_beta_powers[..2 * size - 1].to_vec(),
+            g_beta: self.g_beta.clone(),
+            h_alpha: self.h_alpha.clone(),
+        }
+    }
+
     pub fn get_verifier_key(&self) -> VerifierSRS<P> {
         VerifierSRS {
             g: self.g_alpha_powers[0].clone(),
@@ -197,12 +215,11 @@ where
         let c = loop {
             let mut hash_input = Vec::new();
             hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![
+            hash_input.extend_from_slice(&canonical_bytes![
                 transcript.first().unwrap(),
                 ck_a_final,
                 ck_b_final
-            ]?);
+            ]);
             if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
                 break c;
             };
@@ -260,12 +277,11 @@ where
         let c = loop {
             let mut hash_input = Vec::new();
             hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![
+            hash_input.extend_from_slice(&canonical_bytes![
                 transcript.first().unwrap(),
                 ck_a_final,
                 ck_b_final
-            ]?);
+            ]);
             if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
                 break c;
             };
@@ -302,6 +318,75 @@ where
     }
 }
 
+impl<IP, LMC, RMC, IPC, P, D> InnerProductArgument for TIPA<IP, LMC, RMC, IPC, P, D>
+where
+    D: Digest,
+    P: PairingEngine,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective>
+        + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: MulAssign<P::Fr>,
+    RMC::Message: MulAssign<P::Fr>,
+    IPC::Message: MulAssign<P::Fr>,
+    IPC::Key: MulAssign<P::Fr>,
+    LMC::Output: MulAssign<P::Fr>,
+    RMC::Output: MulAssign<P::Fr>,
+    IPC::Output: MulAssign<P::Fr>,
+{
+    type InnerProduct = IP;
+    type ProverKey = (SRS<P>, Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key);
+    type VerifierKey = (VerifierSRS<P>, IPC::Key);
+    type Commitment = (LMC::Output, RMC::Output, IPC::Output);
+    type Proof = TIPAProof<IP, LMC, RMC, IPC, P, D>;
+
+    fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Self::ProverKey, Self::VerifierKey), Error> {
+        let (srs, ck_t) = Self::setup(rng, size)?;
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        Ok(((srs, ck_a, ck_b, ck_t.clone()), (v_srs, ck_t)))
+    }
+
+    fn commit(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+    ) -> Result<Self::Commitment, Error> {
+        let t = IP::inner_product(left, right)?;
+        Ok((
+            LMC::commit(&pk.1, left)?,
+            RMC::commit(&pk.2, right)?,
+            IPC::commit(&vec![pk.3.clone()], &vec![t])?,
+        ))
+    }
+
+    // TIPA is run on the messages directly, the commitment is only used by the verifier
+    fn prove(
+        pk: &Self::ProverKey,
+        left: &[IP::LeftMessage],
+        right: &[IP::RightMessage],
+        _com: &Self::Commitment,
+    ) -> Result<Self::Proof, Error> {
+        Self::prove(&pk.0, (left, right), (&pk.1, &pk.2, &pk.3))
+    }
+
+    fn verify(
+        vk: &Self::VerifierKey,
+        com: &Self::Commitment,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        Self::verify(&vk.0, &vk.1, (&com.0, &com.1, &com.2), proof)
+    }
+}
+
 pub fn prove_commitment_key_kzg_opening<G: ProjectiveCurve>(
     srs_powers: &Vec<G>,
     transcript: &Vec<G::ScalarField>,
@@ -433,6 +518,7 @@ fn polynomial_coefficients_from_transcript<F: Field>(transcript: &Vec<F>, r_shif
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
+    use ark_ec::AffineCurve;
     use ark_std::rand::{rngs::StdRng, SeedableRng};
     use blake2::Blake2b;
 
@@ -444,8 +530,8 @@ mod tests {
         random_generators,
     };
     use ark_inner_products::{
-        ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct,
-        ScalarInnerProduct,
+        CompressedGTDeserialize, CompressedGTSerialize, ExtensionFieldElement, InnerProduct,
+        MultiexponentiationInnerProduct, PairingInnerProduct, ScalarInnerProduct,
     };
 
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
@@ -476,6 +562,106 @@ mod tests {
         let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
 
         assert!(PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &proof).unwrap());
+
+        // Opting into compressed target group elements saves two thirds of each of the six GT
+        // elements of a round
+        let mut proof_bytes = Vec::new();
+        proof.serialize(&mut proof_bytes).unwrap();
+        let mut compressed_proof_bytes = Vec::new();
+        proof
+            .serialize_compressed_gt(&mut compressed_proof_bytes)
+            .unwrap();
+        assert_eq!(compressed_proof_bytes.len(), proof.compressed_gt_size());
+        let gt_size = ExtensionFieldElement::<Bls12_381>::default().serialized_size();
+        assert_eq!(
+            proof_bytes.len() - compressed_proof_bytes.len(),
+            6 * proof.gipa_proof.r_commitment_steps.len() * (gt_size - gt_size / 3)
+        );
+        let loaded_proof =
+            TIPAProof::deserialize_compressed_gt(&compressed_proof_bytes[..]).unwrap();
+        assert!(
+            PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &loaded_proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn pairing_inner_product_deserialization_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC =
+            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        let mut proof_bytes = Vec::new();
+        let mut v_srs_bytes = Vec::new();
+        proof.serialize(&mut proof_bytes).unwrap();
+        v_srs.serialize(&mut v_srs_bytes).unwrap();
+        let loaded_proof = TIPAProof::deserialize(&proof_bytes[..]).unwrap();
+        let loaded_v_srs = VerifierSRS::deserialize(&v_srs_bytes[..]).unwrap();
+        assert!(PairingTIPA::verify(
+            &loaded_v_srs,
+            &ck_t,
+            (&com_a, &com_b, &com_t),
+            &loaded_proof
+        )
+        .unwrap());
+
+        let mut uncompressed_proof_bytes = Vec::new();
+        proof
+            .serialize_uncompressed(&mut uncompressed_proof_bytes)
+            .unwrap();
+        assert!(proof_bytes.len() < uncompressed_proof_bytes.len());
+        let loaded_proof =
+            TIPAProof::deserialize_uncompressed(&uncompressed_proof_bytes[..]).unwrap();
+        assert!(
+            PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &loaded_proof).unwrap()
+        );
+
+        // Curve points outside of the prime order subgroup are only accepted unchecked
+        let mut bad_v_srs = v_srs.clone();
+        bad_v_srs.g = loop {
+            let x = <Bls12_381 as PairingEngine>::Fq::rand(&mut rng);
+            if let Some(p) = <Bls12_381 as PairingEngine>::G1Affine::get_point_from_x(x, true) {
+                break p.into_projective();
+            }
+        };
+        let mut bytes = Vec::new();
+        bad_v_srs.serialize(&mut bytes).unwrap();
+        assert!(VerifierSRS::<Bls12_381>::deserialize(&bytes[..]).is_err());
+        let mut bytes = Vec::new();
+        bad_v_srs.serialize_uncompressed(&mut bytes).unwrap();
+        assert!(VerifierSRS::<Bls12_381>::deserialize_uncompressed(&bytes[..]).is_err());
+        let mut bytes = Vec::new();
+        bad_v_srs.serialize_unchecked(&mut bytes).unwrap();
+        assert!(VerifierSRS::<Bls12_381>::deserialize_unchecked(&bytes[..]).is_ok());
+
+        // So are target group elements outside of GT
+        let mut bad_proof_bytes = Vec::new();
+        let mut bad_proof =
+            TIPAProof::<IP, GC1, GC2, IPC, Bls12_381, Blake2b>::deserialize(&proof_bytes[..])
+                .unwrap();
+        bad_proof.gipa_proof.r_commitment_steps[0].0 .0 .0 *=
+            <Bls12_381 as PairingEngine>::Fqk::rand(&mut rng);
+        bad_proof
+            .serialize_uncompressed(&mut bad_proof_bytes)
+            .unwrap();
+        assert!(
+            TIPAProof::<IP, GC1, GC2, IPC, Bls12_381, Blake2b>::deserialize_uncompressed(
+                &bad_proof_bytes[..]
+            )
+            .is_err()
+        );
     }
 
     #[test]
@@ -0,0 +1,294 @@
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{tipa::SRS, Error};
+
+/// Transcript of a powers-of-tau ceremony for a secret `tau`:
+/// `g_powers = [g, g^tau, g^tau^2, ...]` in G1 and `h_powers = [h, h^tau, ...]` in G2.
+///
+/// File format: the arkworks canonical (compressed) serialization of this struct, i.e. a
+/// little-endian `u64` count followed by the compressed G1 points, then a `u64` count followed
+/// by the compressed G2 points. Points are checked to be on the curve and in the prime order
+/// subgroup when read.
+///
+/// The TIPA SRS takes `alpha` and `beta` from two independent transcripts: the `alpha`
+/// transcript supplies the long run of G1 powers and `h^alpha`, the `beta` transcript the long
+/// run of G2 powers and `g^beta`. Both must use the same generators `g` and `h`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PowersOfTau<P: PairingEngine> {
+    pub g_powers: Vec<P::G1Affine>,
+    pub h_powers: Vec<P::G2Affine>,
+}
+
+#[derive(Debug)]
+pub enum PowersOfTauError {
+    // (available, required)
+    NotEnoughPowers(usize, usize),
+    SizeInvalid(usize),
+    GeneratorMismatch,
+    InconsistentPowers,
+}
+
+impl ErrorTrait for PowersOfTauError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl Display for PowersOfTauError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            PowersOfTauError::NotEnoughPowers(available, required) => {
+                format!(
+                    "available powers, required powers: {}, {}",
+                    available, required
+                )
+            }
+            PowersOfTauError::SizeInvalid(size) => format!("SRS size must be positive: {}", size),
+            PowersOfTauError::GeneratorMismatch => "transcript generators differ".to_string(),
+            PowersOfTauError::InconsistentPowers => {
+                "transcript powers are not consecutive powers".to_string()
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl<P: PairingEngine> PowersOfTau<P> {
+    pub fn read<Rd: Read>(reader: Rd) -> Result<Self, Error> {
+        Ok(Self::deserialize(reader)?)
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        Ok(self.serialize(writer)?)
+    }
+}
+
+impl<P: PairingEngine> SRS<P> {
+    // SRS for vectors of length `size` (2 * size - 1 powers of alpha and beta)
+    pub fn from_powers_of_tau<R: Rng>(
+        alpha: &PowersOfTau<P>,
+        beta: &PowersOfTau<P>,
+        size: usize,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(Box::new(PowersOfTauError::SizeInvalid(size)));
+        }
+        let num_powers = 2 * size - 1;
+        verify_powers_of_tau(alpha, beta, (num_powers, num_powers), rng)?;
+        Ok(SRS {
+            g_alpha_powers: alpha.g_powers[..num_powers]
+                .iter()
+                .map(|g| g.into_projective())
+                .collect(),
+            h_beta_powers: beta.h_powers[..num_powers]
+                .iter()
+                .map(|h| h.into_projective())
+                .collect(),
+            g_beta: beta.g_powers[1].into_projective(),
+            h_alpha: alpha.h_powers[1].into_projective(),
+        })
+    }
+}
+
+// Checks that the first `lengths.0` G1 powers of `alpha` and the first `lengths.1` G2 powers of
+// `beta` are consecutive powers of the exponents behind h^alpha and g^beta. Consecutive powers
+// are checked with one pair of pairings per transcript on a random linear combination.
+pub(crate) fn verify_powers_of_tau<P: PairingEngine, R: Rng>(
+    alpha: &PowersOfTau<P>,
+    beta: &PowersOfTau<P>,
+    lengths: (usize, usize),
+    rng: &mut R,
+) -> Result<(), Error> {
+    let (alpha_len, beta_len) = lengths;
+    for (available, required) in &[
+        (alpha.g_powers.len(), alpha_len.max(2)),
+        (alpha.h_powers.len(), 2),
+        (beta.g_powers.len(), 2),
+        (beta.h_powers.len(), beta_len.max(2)),
+    ] {
+        if available < required {
+            return Err(Box::new(PowersOfTauError::NotEnoughPowers(
+                *available, *required,
+            )));
+        }
+    }
+
+    let g = alpha.g_powers[0];
+    let h = beta.h_powers[0];
+    if g.is_zero() || h.is_zero() || beta.g_powers[0] != g || alpha.h_powers[0] != h {
+        return Err(Box::new(PowersOfTauError::GeneratorMismatch));
+    }
+
+    let alpha_valid = g1_powers_consistent::<P, R>(
+        &alpha.g_powers[..alpha_len.max(2)],
+        &h,
+        &alpha.h_powers[1],
+        rng,
+    );
+    let beta_valid = g2_powers_consistent::<P, R>(
+        &beta.h_powers[..beta_len.max(2)],
+        &g,
+        &beta.g_powers[1],
+        rng,
+    );
+    if !(alpha_valid && beta_valid) {
+        return Err(Box::new(PowersOfTauError::InconsistentPowers));
+    }
+    Ok(())
+}
+
+// e(sum rho^i g_{i+1}, h) == e(sum rho^i g_i, h^tau) for random rho
+pub(crate) fn g1_powers_consistent<P: PairingEngine, R: Rng>(
+    powers: &[P::G1Affine],
+    h: &P::G2Affine,
+    h_tau: &P::G2Affine,
+    rng: &mut R,
+) -> bool {
+    if powers.len() < 2 {
+        return true;
+    }
+    let rho = random_scalars::<P, R>(powers.len() - 1, rng);
+    let shifted = VariableBaseMSM::multi_scalar_mul(&powers[1..], &rho);
+    let base = VariableBaseMSM::multi_scalar_mul(&powers[..powers.len() - 1], &rho);
+    P::pairing(shifted, *h) == P::pairing(base, *h_tau)
+}
+
+// e(g, sum rho^i h_{i+1}) == e(g^tau, sum rho^i h_i) for random rho
+pub(crate) fn g2_powers_consistent<P: PairingEngine, R: Rng>(
+    powers: &[P::G2Affine],
+    g: &P::G1Affine,
+    g_tau: &P::G1Affine,
+    rng: &mut R,
+) -> bool {
+    if powers.len() < 2 {
+        return true;
+    }
+    let rho = random_scalars::<P, R>(powers.len() - 1, rng);
+    let shifted = VariableBaseMSM::multi_scalar_mul(&powers[1..], &rho);
+    let base = VariableBaseMSM::multi_scalar_mul(&powers[..powers.len() - 1], &rho);
+    P::pairing(*g, shifted) == P::pairing(*g_tau, base)
+}
+
+fn random_scalars<P: PairingEngine, R: Rng>(
+    num: usize,
+    rng: &mut R,
+) -> Vec<<P::Fr as PrimeField>::BigInt> {
+    (0..num).map(|_| P::Fr::rand(rng).into_repr()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::ProjectiveCurve;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    use crate::tipa::{structured_generators_scalar_power, TIPA};
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        random_generators, DoublyHomomorphicCommitment,
+    };
+    use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type G1 = <Bls12_381 as PairingEngine>::G1Projective;
+    type G2 = <Bls12_381 as PairingEngine>::G2Projective;
+    type Fr = <Bls12_381 as PairingEngine>::Fr;
+
+    const TEST_SIZE: usize = 8;
+
+    fn powers_of_tau<R: Rng>(g_len: usize, h_len: usize, rng: &mut R) -> PowersOfTau<Bls12_381> {
+        let tau = Fr::rand(rng);
+        let g = G1::prime_subgroup_generator();
+        let h = G2::prime_subgroup_generator();
+        PowersOfTau {
+            g_powers: G1::batch_normalization_into_affine(&structured_generators_scalar_power(
+                g_len, &g, &tau,
+            )),
+            h_powers: G2::batch_normalization_into_affine(&structured_generators_scalar_power(
+                h_len, &h, &tau,
+            )),
+        }
+    }
+
+    #[test]
+    fn pairing_inner_product_from_powers_of_tau_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC = IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let alpha = powers_of_tau(2 * TEST_SIZE - 1, 2, &mut rng);
+        let beta = powers_of_tau(2, 2 * TEST_SIZE - 1, &mut rng);
+
+        // Round trip through the file format
+        let mut alpha_file = Vec::new();
+        let mut beta_file = Vec::new();
+        alpha.write(&mut alpha_file).unwrap();
+        beta.write(&mut beta_file).unwrap();
+        let alpha = PowersOfTau::<Bls12_381>::read(&alpha_file[..]).unwrap();
+        let beta = PowersOfTau::<Bls12_381>::read(&beta_file[..]).unwrap();
+
+        let srs = SRS::from_powers_of_tau(&alpha, &beta, TEST_SIZE, &mut rng).unwrap();
+        let ck_t = IPC::setup(&mut rng, 1).unwrap().pop().unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+
+        let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        assert!(PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &proof).unwrap());
+    }
+
+    #[test]
+    fn malformed_powers_of_tau_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let alpha = powers_of_tau(2 * TEST_SIZE - 1, 2, &mut rng);
+        let beta = powers_of_tau(2, 2 * TEST_SIZE - 1, &mut rng);
+        assert!(SRS::from_powers_of_tau(&alpha, &beta, TEST_SIZE, &mut rng).is_ok());
+
+        // Empty SRS
+        assert!(SRS::from_powers_of_tau(&alpha, &beta, 0, &mut rng).is_err());
+
+        // Too few powers
+        assert!(SRS::from_powers_of_tau(&alpha, &beta, 2 * TEST_SIZE, &mut rng).is_err());
+
+        // Non-consecutive G1 power
+        let mut bad_alpha = alpha.clone();
+        bad_alpha.g_powers[3] = (bad_alpha.g_powers[3].into_projective()
+            + G1::prime_subgroup_generator())
+        .into_affine();
+        assert!(SRS::from_powers_of_tau(&bad_alpha, &beta, TEST_SIZE, &mut rng).is_err());
+
+        // Non-consecutive G2 power
+        let mut bad_beta = beta.clone();
+        bad_beta.h_powers.swap(1, 2);
+        assert!(SRS::from_powers_of_tau(&alpha, &bad_beta, TEST_SIZE, &mut rng).is_err());
+
+        // h^alpha inconsistent with the G1 powers
+        let mut bad_alpha = alpha.clone();
+        bad_alpha.h_powers[1] = beta.h_powers[1];
+        assert!(SRS::from_powers_of_tau(&bad_alpha, &beta, TEST_SIZE, &mut rng).is_err());
+
+        // Transcripts over different generators
+        let mut bad_beta = beta.clone();
+        bad_beta.g_powers[0] = alpha.g_powers[1];
+        assert!(SRS::from_powers_of_tau(&alpha, &bad_beta, TEST_SIZE, &mut rng).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use ark_ec::{group::Group, PairingEngine, ProjectiveCurve};
-use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand, Zero};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::{cfg_iter, rand::Rng};
 use ark_std::{end_timer, start_timer};
@@ -40,6 +40,10 @@ impl<F: PrimeField> DoublyHomomorphicCommitment for SSMPlaceholderCommitment<F>
         Ok(vec![HomomorphicPlaceholderValue {}; size])
     }
 
+    fn setup_from_seed(_domain_separator: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(vec![HomomorphicPlaceholderValue {}; size])
+    }
+
     //TODO: Doesn't include message which means scalar b not included in generating challenges
     fn commit(_k: &[Self::Key], _m: &[Self::Message]) -> Result<Self::Output, Error> {
         Ok(F::zero())
@@ -88,6 +92,44 @@ where
         com: (&LMC::Output, &IPC::Output),
         scalar_b: &LMC::Scalar,
         proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+    ) -> Result<bool, Error> {
+        Self::_verify_with_final_scalar(ck, com, proof, |transcript| {
+            let mut power_2_b = scalar_b.clone();
+            let mut product_form = Vec::new();
+            for x in transcript.iter() {
+                product_form.push(<LMC::Scalar>::one() + &(x.inverse().unwrap() * &power_2_b));
+                power_2_b *= &power_2_b.clone();
+            }
+            cfg_iter!(product_form).product::<LMC::Scalar>()
+        })
+    }
+
+    // Verifies a proof for the message `structured_scalar_tensor(point)`
+    pub fn verify_with_tensor_structured_scalar_message(
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        point: &[LMC::Scalar],
+        proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+    ) -> Result<bool, Error> {
+        if ck.0.len() != 1 << point.len() || proof.r_commitment_steps.len() != point.len() {
+            return Ok(false);
+        }
+        Self::_verify_with_final_scalar(ck, com, proof, |transcript| {
+            // Transcript is ordered from the last round, which folds the lowest variable
+            let product_form = transcript
+                .iter()
+                .zip(point)
+                .map(|(x, r)| <LMC::Scalar>::one() - r + &(x.inverse().unwrap() * r))
+                .collect::<Vec<_>>();
+            cfg_iter!(product_form).product::<LMC::Scalar>()
+        })
+    }
+
+    fn _verify_with_final_scalar<F: Fn(&[LMC::Scalar]) -> LMC::Scalar>(
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+        compute_final_scalar: F,
     ) -> Result<bool, Error> {
         // Calculate base commitments and recursive transcript
         //TODO: Scalar b not included in generating challenges
@@ -108,13 +150,7 @@ where
         )?;
 
         // Compute final scalar
-        let mut power_2_b = scalar_b.clone();
-        let mut product_form = Vec::new();
-        for x in transcript.iter() {
-            product_form.push(<LMC::Scalar>::one() + &(x.inverse().unwrap() * &power_2_b));
-            power_2_b *= &power_2_b.clone();
-        }
-        let b_base = cfg_iter!(product_form).product::<LMC::Scalar>();
+        let b_base = compute_final_scalar(&transcript);
 
         // Verify base inner product commitment
         let (com_a, _, com_t) = base_com;
@@ -149,7 +185,7 @@ where
     LMC::Message: MulAssign<P::Fr>,
     LMC::Output: MulAssign<P::Fr>,
 {
-    gipa_proof: GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+    pub(crate) gipa_proof: GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
     final_ck: LMC::Key,
     final_ck_proof: P::G2Projective,
     _pairing: PhantomData<P>,
@@ -239,8 +275,8 @@ where
         let c = loop {
             let mut hash_input = Vec::new();
             hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![transcript.first().unwrap(), ck_a_final]?);
+            hash_input
+                .extend_from_slice(&canonical_bytes![transcript.first().unwrap(), ck_a_final]);
             if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
                 break c;
             };
@@ -287,8 +323,8 @@ where
         let c = loop {
             let mut hash_input = Vec::new();
             hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![transcript.first().unwrap(), ck_a_final]?);
+            hash_input
+                .extend_from_slice(&canonical_bytes![transcript.first().unwrap(), ck_a_final]);
             if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
                 break c;
             };
@@ -333,6 +369,18 @@ pub fn structured_scalar_power<F: Field>(num: usize, s: &F) -> Vec<F> {
     powers
 }
 
+// Equality polynomial evaluations eq(w, point) over the boolean hypercube, with variable i in bit i
+// of the index
+pub fn structured_scalar_tensor<F: Field>(point: &[F]) -> Vec<F> {
+    let mut tensor = vec![F::one()];
+    for r in point {
+        let high = tensor.iter().map(|t| *t * r).collect::<Vec<_>>();
+        tensor.iter_mut().for_each(|t| *t *= F::one() - r);
+        tensor.extend(high);
+    }
+    tensor
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,300 @@
+use ark_ec::PairingEngine;
+use ark_ff::{Field, One, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
+use digest::Digest;
+use std::{marker::PhantomData, ops::MulAssign};
+
+use crate::{
+    gipa_with_zk::{BlindingBases, GIPAProof, GIPA},
+    tipa::{
+        prove_commitment_key_kzg_opening, verify_commitment_key_g1_kzg_opening,
+        verify_commitment_key_g2_kzg_opening, TIPACompatibleSetup, VerifierSRS, SRS,
+    },
+    Error,
+};
+use ark_dh_commitments::DoublyHomomorphicCommitment;
+use ark_inner_products::InnerProduct;
+
+// Zero-knowledge TIPA: zero-knowledge GIPA on blinded commitments, with the final commitment
+// keys proven well-formed by KZG openings as in TIPA. The final keys only depend on the round
+// challenges, so opening them reveals nothing about the messages.
+pub struct TIPA<IP, LMC, RMC, IPC, P, D> {
+    _inner_product: PhantomData<IP>,
+    _left_commitment: PhantomData<LMC>,
+    _right_commitment: PhantomData<RMC>,
+    _inner_product_commitment: PhantomData<IPC>,
+    _pair: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: Digest,
+    P: PairingEngine,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    pub(crate) gipa_proof: GIPAProof<IP, LMC, RMC, IPC, D>,
+    final_ck: (LMC::Key, RMC::Key),
+    final_ck_proof: (P::G2Projective, P::G1Projective),
+    _pair: PhantomData<P>,
+}
+
+impl<IP, LMC, RMC, IPC, P, D> Clone for TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: Digest,
+    P: PairingEngine,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    RMC::Message: MulAssign<LMC::Scalar>,
+    IPC::Message: MulAssign<LMC::Scalar>,
+    RMC::Key: MulAssign<LMC::Scalar>,
+    IPC::Key: MulAssign<LMC::Scalar>,
+    RMC::Output: MulAssign<LMC::Scalar>,
+    IPC::Output: MulAssign<LMC::Scalar>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            gipa_proof: self.gipa_proof.clone(),
+            final_ck: self.final_ck.clone(),
+            final_ck_proof: self.final_ck_proof,
+            _pair: PhantomData,
+        }
+    }
+}
+
+impl<IP, LMC, RMC, IPC, P, D> TIPA<IP, LMC, RMC, IPC, P, D>
+where
+    D: Digest,
+    P: PairingEngine,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective>
+        + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: MulAssign<P::Fr>,
+    RMC::Message: MulAssign<P::Fr>,
+    IPC::Message: MulAssign<P::Fr>,
+    IPC::Key: MulAssign<P::Fr>,
+    LMC::Output: MulAssign<P::Fr>,
+    RMC::Output: MulAssign<P::Fr>,
+    IPC::Output: MulAssign<P::Fr>,
+    IP::LeftMessage: UniformRand,
+    IP::RightMessage: UniformRand,
+{
+    // Returns the SRS, the inner product commitment key and blinding bases (h_a, h_b, h_t)
+    pub fn setup<R: Rng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(SRS<P>, IPC::Key, BlindingBases<LMC, RMC, IPC>), Error> {
+        let (srs, ck_t) = super::TIPA::<IP, LMC, RMC, IPC, P, D>::setup(rng, size)?;
+        let h = GIPA::<IP, LMC, RMC, IPC, D>::setup_blinding_bases(rng, &ck_t)?;
+        Ok((srs, ck_t, h))
+    }
+
+    pub fn prove<R: Rng>(
+        srs: &SRS<P>,
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        randomness: (&LMC::Scalar, &LMC::Scalar, &LMC::Scalar),
+        rng: &mut R,
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error> {
+        // Run zero-knowledge GIPA
+        let (proof, aux) = GIPA::<IP, LMC, RMC, IPC, D>::prove_with_aux(
+            values,
+            (ck.0, ck.1, &[ck.2.clone()]),
+            h,
+            randomness,
+            rng,
+        )?;
+
+        // Prove final commitment keys are wellformed
+        let (ck_a_final, ck_b_final) = aux.ck_base;
+        let transcript = aux.r_transcript;
+        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+        let c =
+            Self::_compute_kzg_challenge(transcript.first().unwrap(), &ck_a_final, &ck_b_final)?;
+
+        // Complete KZG proofs
+        let ck_a_kzg_opening = prove_commitment_key_kzg_opening(
+            &srs.h_beta_powers,
+            &transcript_inverse,
+            &<P::Fr>::one(),
+            &c,
+        )?;
+        let ck_b_kzg_opening = prove_commitment_key_kzg_opening(
+            &srs.g_alpha_powers,
+            &transcript,
+            &<P::Fr>::one(),
+            &c,
+        )?;
+
+        Ok(TIPAProof {
+            gipa_proof: proof,
+            final_ck: (ck_a_final, ck_b_final),
+            final_ck_proof: (ck_a_kzg_opening, ck_b_kzg_opening),
+            _pair: PhantomData,
+        })
+    }
+
+    pub fn verify(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        h: (&LMC::Output, &RMC::Output, &IPC::Output),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+    ) -> Result<bool, Error> {
+        let (base_com, transcript) =
+            GIPA::verify_recursive_challenge_transcript(com, &proof.gipa_proof)?;
+        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+
+        // Verify commitment keys wellformed
+        let (ck_a_final, ck_b_final) = &proof.final_ck;
+        let (ck_a_proof, ck_b_proof) = &proof.final_ck_proof;
+        let c = Self::_compute_kzg_challenge(transcript.first().unwrap(), ck_a_final, ck_b_final)?;
+
+        let ck_a_valid = verify_commitment_key_g2_kzg_opening(
+            v_srs,
+            ck_a_final,
+            ck_a_proof,
+            &transcript_inverse,
+            &<P::Fr>::one(),
+            &c,
+        )?;
+        let ck_b_valid = verify_commitment_key_g1_kzg_opening(
+            v_srs,
+            ck_b_final,
+            ck_b_proof,
+            &transcript,
+            &<P::Fr>::one(),
+            &c,
+        )?;
+
+        // Verify knowledge of an opening of the base commitment
+        let base_valid = GIPA::_verify_base_sigma(
+            (ck_a_final, ck_b_final, &vec![ck_t.clone()]),
+            h,
+            base_com,
+            transcript.first().unwrap(),
+            &proof.gipa_proof,
+        )?;
+
+        Ok(ck_a_valid && ck_b_valid && base_valid)
+    }
+
+    // KZG challenge point
+    fn _compute_kzg_challenge(
+        transcript: &LMC::Scalar,
+        ck_a_final: &LMC::Key,
+        ck_b_final: &RMC::Key,
+    ) -> Result<LMC::Scalar, Error> {
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            let mut hash_input = Vec::new();
+            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
+            hash_input.extend_from_slice(&canonical_bytes![transcript, ck_a_final, ck_b_final]);
+            if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
+                break c;
+            };
+            counter_nonce += 1;
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use blake2::Blake2b;
+
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        random_generators,
+    };
+    use ark_inner_products::{ExtensionFieldElement, PairingInnerProduct};
+
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn pairing_inner_product_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC =
+            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t, (h_a, h_b, h_t)) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let ((com_a, com_b, com_t), (r_a, r_b, r_t)) = PairingGIPA::init_commit(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        let proof = PairingTIPA::prove(
+            &srs,
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&h_a, &h_b, &h_t),
+            (&r_a, &r_b, &r_t),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(PairingTIPA::verify(
+            &v_srs,
+            &ck_t,
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t),
+            &proof,
+        )
+        .unwrap());
+
+        // A proof does not verify for a different inner product commitment
+        let com_t_other = com_t.clone() + h_t.clone();
+        assert!(!PairingTIPA::verify(
+            &v_srs,
+            &ck_t,
+            (&h_a, &h_b, &h_t),
+            (&com_a, &com_b, &com_t_other),
+            &proof,
+        )
+        .unwrap());
+    }
+}
@@ -62,6 +62,7 @@ fn main() {
         };
         let a_s = &mut a_s[..m];
         let b_s = &mut b_s[..m];
+        let r_s = &r_s[..m];
 
         let mut direct_time = 0.0;
         let mut prover_time = 0.0;
@@ -70,15 +71,15 @@ fn main() {
             a_s.shuffle(&mut rng);
             b_s.shuffle(&mut rng);
             let start = Instant::now();
-            let z = ark_sipp::product_of_pairings_with_coeffs::<Bls12_377>(a_s, b_s, &r_s);
+            let z = ark_sipp::product_of_pairings_with_coeffs::<Bls12_377>(a_s, b_s, r_s);
             direct_time += (start.elapsed().as_millis() as f64) / 1_000.0;
 
             let start = Instant::now();
-            let proof = ExampleSIPP::prove(a_s, b_s, &r_s, z.clone()).unwrap();
+            let proof = ExampleSIPP::prove(a_s, b_s, r_s, z.clone()).unwrap();
             prover_time += (start.elapsed().as_millis() as f64) / 1_000.0;
 
             let start = Instant::now();
-            assert!(ExampleSIPP::verify(a_s, b_s, &r_s, z.clone(), &proof).unwrap());
+            assert!(ExampleSIPP::verify(a_s, b_s, r_s, z.clone(), &proof).unwrap());
             verifier_time += (start.elapsed().as_millis() as f64) / 1_000.0;
         }
         let num_iters = num_iters as f64;
@@ -4,9 +4,19 @@
 
 use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand};
+use ark_inner_products::{
+    Error, ExtensionFieldElement, InnerProductArgument, InnerProductError, PairingInnerProduct,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::Rng;
 use digest::Digest;
 use rayon::prelude::*;
-use std::marker::PhantomData;
+use std::{
+    error::Error as ErrorTrait,
+    fmt::{Display, Formatter, Result as FmtResult},
+    io,
+    marker::PhantomData,
+};
 
 /// Fiat-Shamir Rng
 pub mod rng;
@@ -20,10 +30,146 @@ pub struct SIPP<E: PairingEngine, D: Digest> {
     _digest: PhantomData<D>,
 }
 
+/// A statement `(a, b, r, claimed_value)` together with its proof, as taken by `batch_verify`.
+pub type SIPPInstance<'a, E> = (
+    &'a [<E as PairingEngine>::G1Affine],
+    &'a [<E as PairingEngine>::G2Affine],
+    &'a [<E as PairingEngine>::Fr],
+    <E as PairingEngine>::Fqk,
+    &'a Proof<E>,
+);
+
+// The single claim `e(a', b') = z'` that `reduce` leaves to check
+type ReducedClaim<E> = (
+    <E as PairingEngine>::G1Projective,
+    <E as PairingEngine>::G2Projective,
+    <E as PairingEngine>::Fqk,
+);
+
 /// `Proof` contains the GT elements produced by the prover.
 // TODO(psi): why not just make Proof an alias since there's only one field?
+#[derive(Clone, Debug, PartialEq)]
 pub struct Proof<E: PairingEngine> {
-    gt_elems: Vec<(E::Fqk, E::Fqk)>,
+    /// The cross terms `(z_l, z_r)` of each halving round, `log2(n)` in total.
+    pub gt_elems: Vec<(E::Fqk, E::Fqk)>,
+}
+
+impl<E: PairingEngine> Proof<E> {
+    // The cross terms as `ExtensionFieldElement`s, which are checked to lie in the order r target
+    // group unless deserialized unchecked, and are compressed by `serialize_compressed_gt`
+    fn wrapped(&self) -> Vec<(ExtensionFieldElement<E>, ExtensionFieldElement<E>)> {
+        self.gt_elems
+            .iter()
+            .map(|(z_l, z_r)| (ExtensionFieldElement(*z_l), ExtensionFieldElement(*z_r)))
+            .collect()
+    }
+
+    fn unwrapped(gt_elems: Vec<(ExtensionFieldElement<E>, ExtensionFieldElement<E>)>) -> Self {
+        Proof {
+            gt_elems: gt_elems
+                .into_iter()
+                .map(|(z_l, z_r)| (z_l.0, z_r.0))
+                .collect(),
+        }
+    }
+}
+
+impl<E: PairingEngine> CanonicalSerialize for Proof<E> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.wrapped().serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.wrapped().serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.wrapped().serialize_uncompressed(writer)
+    }
+
+    fn serialize_unchecked<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.wrapped().serialize_unchecked(writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.wrapped().uncompressed_size()
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for Proof<E> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::unwrapped(CanonicalDeserialize::deserialize(reader)?))
+    }
+
+    fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::unwrapped(
+            CanonicalDeserialize::deserialize_uncompressed(reader)?,
+        ))
+    }
+
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::unwrapped(
+            CanonicalDeserialize::deserialize_unchecked(reader)?,
+        ))
+    }
+}
+
+/// Errors returned when proving or verifying with SIPP.
+#[derive(Debug)]
+pub enum SIPPError {
+    /// `a`, `b` and `r` have different lengths: `(a, b, r)`.
+    LengthMismatch(usize, usize, usize),
+    /// The input length is not a power of two no smaller than 2.
+    LengthInvalid(usize),
+    /// The proof does not have `log2(n)` elements: `(expected, found)`.
+    ProofLengthInvalid(usize, usize),
+    /// Writing the Fiat-Shamir transcript failed.
+    Io(io::Error),
+}
+
+impl ErrorTrait for SIPPError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        match self {
+            SIPPError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SIPPError {
+    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
+        let msg = match self {
+            SIPPError::LengthMismatch(a, b, r) => {
+                format!("a length, b length, r length: {}, {}, {}", a, b, r)
+            }
+            SIPPError::LengthInvalid(length) => {
+                format!("length is not a power of two >= 2: {}", length)
+            }
+            SIPPError::ProofLengthInvalid(expected, found) => {
+                format!("expected proof length, found: {}, {}", expected, found)
+            }
+            SIPPError::Io(e) => format!("transcript error: {}", e),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl From<io::Error> for SIPPError {
+    fn from(e: io::Error) -> Self {
+        SIPPError::Io(e)
+    }
+}
+
+// Ensure the inputs have equal lengths and that the length is a power of 2, with at least one
+// halving round
+fn check_input_lengths(a: usize, b: usize, r: usize) -> Result<(), SIPPError> {
+    if a != b || a != r {
+        return Err(SIPPError::LengthMismatch(a, b, r));
+    }
+    if !a.is_power_of_two() || a < 2 {
+        return Err(SIPPError::LengthInvalid(a));
+    }
+    Ok(())
 }
 
 impl<E: PairingEngine, D: Digest> SIPP<E, D> {
@@ -33,16 +179,12 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         b: &[E::G2Affine],
         r: &[E::Fr],
         value: E::Fqk,
-    ) -> Result<Proof<E>, ()> {
-        assert_eq!(a.len(), b.len());
-        // Ensure the order of the input vectors is a power of 2
-        assert_eq!(a.len().count_ones(), 1);
+    ) -> Result<Proof<E>, SIPPError> {
+        check_input_lengths(a.len(), b.len(), r.len())?;
         let mut length = a.len();
-        assert_eq!(length, b.len());
-        assert_eq!(length.count_ones(), 1);
         let mut proof_vec = Vec::new();
         // TODO(psi): should we also input a succinct bilinear group description to the rng?
-        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, value].unwrap());
+        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, value]?);
         let a = a
             .into_par_iter()
             .zip(r)
@@ -62,7 +204,7 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
             let z_l = product_of_pairings::<E>(a_r, b_l);
             let z_r = product_of_pairings::<E>(a_l, b_r);
             proof_vec.push((z_l, z_r));
-            rng.absorb(&to_bytes![z_l, z_r].unwrap());
+            rng.absorb(&to_bytes![z_l, z_r]?);
             let x: E::Fr = u128::rand(&mut rng).into();
 
             let a_proj = a_l
@@ -101,28 +243,69 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         r: &[E::Fr],
         claimed_value: E::Fqk,
         proof: &Proof<E>,
-    ) -> Result<bool, ()> {
-        // Ensure the order of the input vectors is a power of 2
+    ) -> Result<bool, SIPPError> {
+        let (a_prime, b_prime, z_prime) = Self::reduce(a, b, r, claimed_value, proof)?;
+        Ok(E::pairing(a_prime, b_prime) == z_prime)
+    }
+
+    /// Verify many inner-pairing-product proofs at once. The final pairing checks
+    /// `e(a', b') = z'` of all proofs are combined with random weights into a single
+    /// multi-pairing.
+    pub fn batch_verify(instances: &[SIPPInstance<E>]) -> Result<bool, SIPPError> {
+        let mut a_primes = Vec::with_capacity(instances.len());
+        let mut b_primes = Vec::with_capacity(instances.len());
+        let mut z_primes = Vec::with_capacity(instances.len());
+        for (a, b, r, claimed_value, proof) in instances {
+            let (a_prime, b_prime, z_prime) = Self::reduce(a, b, r, *claimed_value, proof)?;
+            a_primes.push(a_prime);
+            b_primes.push(b_prime);
+            z_primes.push(z_prime);
+        }
+        let a_primes = E::G1Projective::batch_normalization_into_affine(&a_primes);
+        let b_primes = E::G2Projective::batch_normalization_into_affine(&b_primes);
+
+        // The weights are derived from all reduced claims, so they are fixed only once every
+        // proof is.
+        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a_primes, b_primes, z_primes]?);
+        let weights = (0..instances.len())
+            .map(|_| E::Fr::from(u128::rand(&mut rng)))
+            .collect::<Vec<_>>();
+
+        let z = z_primes
+            .par_iter()
+            .zip(&weights)
+            .map(|(z_prime, w)| z_prime.pow(w.into_repr()))
+            .reduce(|| E::Fqk::one(), |a, b| a * &b);
+
+        Ok(product_of_pairings_with_coeffs::<E>(&a_primes, &b_primes, &weights) == z)
+    }
+
+    /// Reduces a claim about `n` pairings to a single claim `e(a', b') = z'`.
+    fn reduce(
+        a: &[E::G1Affine],
+        b: &[E::G2Affine],
+        r: &[E::Fr],
+        claimed_value: E::Fqk,
+        proof: &Proof<E>,
+    ) -> Result<ReducedClaim<E>, SIPPError> {
+        check_input_lengths(a.len(), b.len(), r.len())?;
         let length = a.len();
-        assert_eq!(length.count_ones(), 1);
-        assert!(length >= 2);
-        assert_eq!(length, b.len());
         // Ensure there are the correct number of proof elements
         let proof_len = proof.gt_elems.len();
-        assert_eq!(proof_len as f32, f32::log2(length as f32));
+        let expected_proof_len = length.trailing_zeros() as usize;
+        if proof_len != expected_proof_len {
+            return Err(SIPPError::ProofLengthInvalid(expected_proof_len, proof_len));
+        }
 
         // TODO(psi): should we also input a succinct bilinear group description to the rng?
-        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, claimed_value].unwrap());
+        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, claimed_value]?);
 
-        let x_s = proof
-            .gt_elems
-            .iter()
-            .map(|(z_l, z_r)| {
-                rng.absorb(&to_bytes![z_l, z_r].unwrap());
-                let x: E::Fr = u128::rand(&mut rng).into();
-                x
-            })
-            .collect::<Vec<_>>();
+        let mut x_s = Vec::with_capacity(proof_len);
+        for (z_l, z_r) in &proof.gt_elems {
+            rng.absorb(&to_bytes![z_l, z_r]?);
+            let x: E::Fr = u128::rand(&mut rng).into();
+            x_s.push(x);
+        }
 
         let mut x_invs = x_s.clone();
         ark_ff::batch_inversion(&mut x_invs);
@@ -138,17 +321,8 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
                 })
                 .reduce(|| E::Fqk::one(), |a, b| a * &b);
 
-        let mut s: Vec<E::Fr> = vec![E::Fr::one(); length];
-        let mut s_invs: Vec<E::Fr> = vec![E::Fr::one(); length];
-        // TODO(psi): batch verify
-        for (j, (x, x_inv)) in x_s.into_iter().zip(x_invs).enumerate() {
-            for i in 0..length {
-                if i & (1 << (proof_len - j - 1)) != 0 {
-                    s[i] *= &x;
-                    s_invs[i] *= &x_inv;
-                }
-            }
-        }
+        let s = challenge_products(&x_s);
+        let s_invs = challenge_products(&x_invs);
 
         let s = s
             .into_iter()
@@ -163,9 +337,79 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         let a_prime = VariableBaseMSM::multi_scalar_mul(&a, &s);
         let b_prime = VariableBaseMSM::multi_scalar_mul(&b, &s_invs);
 
-        let accept = E::pairing(a_prime, b_prime) == z_prime;
+        Ok((a_prime, b_prime, z_prime))
+    }
+}
 
-        Ok(accept)
+// The folding coefficients s_i = prod_j x_j^{i_j}, where i_j is the bit of i that round j folds
+// (the first challenge folds the most significant bit). Built as a product tree, doubling the
+// vector once per challenge, in O(n) multiplications.
+fn challenge_products<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut s = Vec::with_capacity(1 << challenges.len());
+    s.push(F::one());
+    for x in challenges.iter().rev() {
+        let len = s.len();
+        for i in 0..len {
+            let s_i = s[i] * x;
+            s.push(s_i);
+        }
+    }
+    s
+}
+
+// SIPP needs at least one halving round, so lengths must be a power of two no smaller than 2
+fn check_length(left: usize, right: usize) -> Result<(), Error> {
+    if left != right || left.count_ones() != 1 || left < 2 {
+        return Err(Box::new(InnerProductError::MessageLengthInvalid(
+            left, right,
+        )));
+    }
+    Ok(())
+}
+
+/// SIPP has no commitment keys: the verifier reads `A` and `B` in the clear, so the
+/// "commitment" is the affine vectors together with the claimed product `T`.
+impl<E: PairingEngine, D: Digest> InnerProductArgument for SIPP<E, D> {
+    type InnerProduct = PairingInnerProduct<E>;
+    type ProverKey = ();
+    type VerifierKey = ();
+    type Commitment = (Vec<E::G1Affine>, Vec<E::G2Affine>, E::Fqk);
+    type Proof = Proof<E>;
+
+    fn setup<R: Rng>(_rng: &mut R, _size: usize) -> Result<((), ()), Error> {
+        Ok(((), ()))
+    }
+
+    fn commit(
+        _pk: &(),
+        left: &[E::G1Projective],
+        right: &[E::G2Projective],
+    ) -> Result<Self::Commitment, Error> {
+        check_length(left.len(), right.len())?;
+        let a = E::G1Projective::batch_normalization_into_affine(left);
+        let b = E::G2Projective::batch_normalization_into_affine(right);
+        let t = product_of_pairings::<E>(&a, &b);
+        Ok((a, b, t))
+    }
+
+    fn prove(
+        _pk: &(),
+        _left: &[E::G1Projective],
+        _right: &[E::G2Projective],
+        com: &Self::Commitment,
+    ) -> Result<Self::Proof, Error> {
+        check_length(com.0.len(), com.1.len())?;
+        let r = vec![E::Fr::one(); com.0.len()];
+        Ok(Self::prove(&com.0, &com.1, &r, com.2)?)
+    }
+
+    fn verify(_vk: &(), com: &Self::Commitment, proof: &Self::Proof) -> Result<bool, Error> {
+        check_length(com.0.len(), com.1.len())?;
+        if proof.gt_elems.len() != com.0.len().trailing_zeros() as usize {
+            return Ok(false);
+        }
+        let r = vec![E::Fr::one(); com.0.len()];
+        Ok(Self::verify(&com.0, &com.1, &r, com.2, proof)?)
     }
 }
 
@@ -210,6 +454,7 @@ pub fn product_of_pairings<E: PairingEngine>(a: &[E::G1Affine], b: &[E::G2Affine
 mod tests {
     use super::*;
     use ark_bls12_377::{Bls12_377, Fr, G1Projective, G2Projective};
+    use ark_inner_products::{CompressedGTDeserialize, CompressedGTSerialize};
     use blake2::Blake2s;
 
     #[test]
@@ -234,4 +479,93 @@ mod tests {
         assert!(accept.is_ok());
         assert!(accept.unwrap());
     }
+
+    #[test]
+    fn proof_serialization_and_errors() {
+        let mut rng = FiatShamirRng::<Blake2s>::from_seed(&to_bytes![b"falafel"].unwrap());
+        let mut a = Vec::with_capacity(8);
+        let mut b = Vec::with_capacity(8);
+        let mut r = Vec::with_capacity(8);
+        for _ in 0..8 {
+            a.push(G1Projective::rand(&mut rng).into_affine());
+            b.push(G2Projective::rand(&mut rng).into_affine());
+            r.push(Fr::rand(&mut rng));
+        }
+        let z = product_of_pairings_with_coeffs::<Bls12_377>(&a, &b, &r);
+        let proof = SIPP::<Bls12_377, Blake2s>::prove(&a, &b, &r, z).unwrap();
+
+        // Round trip through canonical serialization
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        let deserialized = Proof::<Bls12_377>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(proof, deserialized);
+        assert!(SIPP::<Bls12_377, Blake2s>::verify(&a, &b, &r, z, &deserialized).unwrap());
+
+        // and with compressed cross terms, a third of the size after the 8-byte length
+        let mut compressed_bytes = Vec::new();
+        proof
+            .serialize_compressed_gt(&mut compressed_bytes)
+            .unwrap();
+        assert_eq!(3 * (compressed_bytes.len() - 8), bytes.len() - 8);
+        assert_eq!(
+            proof,
+            Proof::<Bls12_377>::deserialize_compressed_gt(&compressed_bytes[..]).unwrap()
+        );
+
+        // Cross terms outside of GT are only accepted unchecked
+        let mut bad_proof = proof.clone();
+        bad_proof.gt_elems[0].1 *= <Bls12_377 as PairingEngine>::Fqk::rand(&mut rng);
+        let mut bytes = Vec::new();
+        bad_proof.serialize(&mut bytes).unwrap();
+        assert!(Proof::<Bls12_377>::deserialize(&bytes[..]).is_err());
+        assert!(Proof::<Bls12_377>::deserialize_uncompressed(&bytes[..]).is_err());
+        assert_eq!(
+            bad_proof,
+            Proof::<Bls12_377>::deserialize_unchecked(&bytes[..]).unwrap()
+        );
+
+        // Malformed inputs are errors rather than panics
+        assert!(matches!(
+            SIPP::<Bls12_377, Blake2s>::prove(&a[..7], &b[..7], &r[..7], z),
+            Err(SIPPError::LengthInvalid(7))
+        ));
+        assert!(matches!(
+            SIPP::<Bls12_377, Blake2s>::verify(&a, &b[..4], &r, z, &proof),
+            Err(SIPPError::LengthMismatch(8, 4, 8))
+        ));
+        let mut short_proof = proof.clone();
+        short_proof.gt_elems.pop();
+        assert!(matches!(
+            SIPP::<Bls12_377, Blake2s>::verify(&a, &b, &r, z, &short_proof),
+            Err(SIPPError::ProofLengthInvalid(3, 2))
+        ));
+    }
+
+    #[test]
+    fn batch_verify() {
+        let mut rng = FiatShamirRng::<Blake2s>::from_seed(&to_bytes![b"falafel"].unwrap());
+        let mut instances = Vec::new();
+        for &n in &[2, 4, 8, 8] {
+            let a = (0..n)
+                .map(|_| G1Projective::rand(&mut rng).into_affine())
+                .collect::<Vec<_>>();
+            let b = (0..n)
+                .map(|_| G2Projective::rand(&mut rng).into_affine())
+                .collect::<Vec<_>>();
+            let r = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+            let z = product_of_pairings_with_coeffs::<Bls12_377>(&a, &b, &r);
+            let proof = SIPP::<Bls12_377, Blake2s>::prove(&a, &b, &r, z).unwrap();
+            instances.push((a, b, r, z, proof));
+        }
+        let batch = instances
+            .iter()
+            .map(|(a, b, r, z, proof)| (&a[..], &b[..], &r[..], *z, proof))
+            .collect::<Vec<_>>();
+        assert!(SIPP::<Bls12_377, Blake2s>::batch_verify(&batch).unwrap());
+
+        // A single wrong claim fails the whole batch
+        let mut bad_batch = batch.clone();
+        bad_batch[2].3 = bad_batch[2].3 * &bad_batch[1].3;
+        assert!(!SIPP::<Bls12_377, Blake2s>::batch_verify(&bad_batch).unwrap());
+    }
 }